@@ -0,0 +1,488 @@
+//! Sprout allocation
+//!
+//! Sprouts that are ready to ship are matched to the customers due a package.
+//! Customers are served in subscription tier order, premium first, then
+//! whoever has waited longest since their last package. A record suits a
+//! customer when its species isn't excluded, its shipment type is the one
+//! they prefer and its guide's difficulty is within their experience; their
+//! own seeds come first, then their preferred species, then species they have
+//! the light for, oldest ready first. Each customer gets up to
+//! `sprouts_per_shipment_max`, or nothing while fewer than
+//! `sprouts_per_shipment_min` suit them, as a proposed `SproutShipmentPackage`
+//! for staff to approve or reject.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use api::{response, ApiError, ApiResult, FieldError, Request, Role};
+use database::DatabaseError;
+use models::{
+    CustomerSproutPreferences, DeliveryFrequency, ExperienceLevel, GerminationGuide, GerminationPhase,
+    GerminationRecord, PackageStatus, PackageType, ShipmentType, Species, SproutShipmentPackage, SubscriptionStatus,
+    SubscriptionTier, Weekday,
+};
+
+use crate::{record_guide, transit, Repositories};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SproutPreferencesRequest {
+    #[serde(default)]
+    preferred_species: Vec<String>,
+    /// Never sent, whatever else is ready
+    #[serde(default)]
+    excluded_species: Vec<String>,
+    preferred_shipment_type: ShipmentType,
+    delivery_frequency: DeliveryFrequency,
+    #[serde(default)]
+    preferred_delivery_days: Vec<Weekday>,
+    sprouts_per_shipment_min: u32,
+    sprouts_per_shipment_max: u32,
+    experience_level: ExperienceLevel,
+    #[serde(default)]
+    has_grow_lights: bool,
+    #[serde(default)]
+    has_greenhouse: bool,
+    #[serde(default)]
+    has_outdoor_space: bool,
+}
+
+/// A customer due a package who got none this run
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedCustomer {
+    customer_id: Uuid,
+    reason: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationReport {
+    allocated_at: DateTime<Utc>,
+    proposals: Vec<SproutShipmentPackage>,
+    skipped: Vec<SkippedCustomer>,
+    /// Ready records no customer took
+    unallocated: Vec<Uuid>,
+}
+
+/// Why a record suits a customer, best first
+struct Match {
+    score: u32,
+    reasons: Vec<&'static str>,
+}
+
+/// Records a proposed or approved package already holds
+pub(crate) async fn held_records(repos: &Repositories) -> Result<HashSet<Uuid>, ApiError> {
+    let mut held = HashSet::new();
+    for status in [PackageStatus::Proposed, PackageStatus::Approved] {
+        for package in repos.packages.list_by_status(status).await? {
+            held.extend(package.germination_records);
+        }
+    }
+    Ok(held)
+}
+
+/// Propose packages of the ready records not already held by a proposed or
+/// approved package
+pub(crate) async fn allocate(repos: &Repositories, now: DateTime<Utc>) -> Result<AllocationReport, ApiError> {
+    let held = held_records(repos).await?;
+
+    let mut guides: HashMap<(String, Option<String>), Option<GerminationGuide>> = HashMap::new();
+    let mut pool = Vec::new();
+    for record in repos.records.list_by_phase(&GerminationPhase::ReadyForShipment).await? {
+        if held.contains(&record.id) || record.package_id.is_some() {
+            continue;
+        }
+        let key = (record.species.clone(), record.variety.clone());
+        if !guides.contains_key(&key) {
+            guides.insert(key.clone(), record_guide(repos, &record).await?);
+        }
+        // Without a guide there is no telling whether it's ready, or who can grow it
+        let Some(guide) = guides[&key].clone() else {
+            continue;
+        };
+        let observations = repos.observations.list_by_record(record.id).await?;
+        if record.readiness(&guide, &observations, now).ready {
+            pool.push((record, guide));
+        }
+    }
+    pool.sort_by_key(|(record, _)| record.ready_for_shipment_at);
+
+    let mut skipped = Vec::new();
+    let mut customers = Vec::new();
+    for preferences in repos.preferences.list_all().await? {
+        let customer_id = preferences.customer_id;
+        let mut skip = |reason: String| skipped.push(SkippedCustomer { customer_id, reason });
+
+        let Some(tier) = repos
+            .subscriptions
+            .get_by_customer(customer_id)
+            .await?
+            .into_iter()
+            .filter(|subscription| subscription.status == SubscriptionStatus::Active)
+            .map(|subscription| subscription.tier)
+            .max_by_key(tier_rank)
+        else {
+            skip("No active subscription".to_string());
+            continue;
+        };
+
+        let packages = repos.packages.list_by_customer(customer_id).await?;
+        if packages.iter().any(|package| package.status == PackageStatus::Proposed) {
+            skip("A proposed package is awaiting review".to_string());
+            continue;
+        }
+        let last = packages
+            .iter()
+            .filter(|package| package.status == PackageStatus::Approved)
+            .map(|package| package.created_at)
+            .max();
+        if let Some(last) = last {
+            let due = last + Duration::days(preferences.delivery_frequency.min_days_between());
+            if due > now {
+                skip(format!("Not due until {}", due.date_naive()));
+                continue;
+            }
+        }
+
+        customers.push((tier_rank(&tier), last, preferences));
+    }
+    // Never-served customers sort ahead of any last package date
+    customers.sort_by_key(|(rank, last, _)| (Reverse(*rank), *last));
+
+    let mut proposals = Vec::new();
+    for (_, _, preferences) in customers {
+        // A record another run claims first drops out of the pool and the
+        // customer is matched again
+        loop {
+            let mut matches: Vec<(usize, Match)> = pool
+                .iter()
+                .enumerate()
+                .filter_map(|(i, (record, guide))| Some((i, suitability(&preferences, record, guide)?)))
+                .collect();
+            // Stable, so equally good records stay oldest ready first
+            matches.sort_by_key(|(_, m)| Reverse(m.score));
+            matches.truncate(preferences.sprouts_per_shipment_max as usize);
+
+            if matches.len() < preferences.sprouts_per_shipment_min as usize {
+                skipped.push(SkippedCustomer {
+                    customer_id: preferences.customer_id,
+                    reason: format!(
+                        "Only {} ready sprouts suit them; they want at least {}",
+                        matches.len(),
+                        preferences.sprouts_per_shipment_min
+                    ),
+                });
+                break;
+            }
+            if matches.is_empty() {
+                break;
+            }
+
+            let reasons = matches
+                .iter()
+                .map(|(i, m)| {
+                    let record = &pool[*i].0;
+                    let why =
+                        if m.reasons.is_empty() { "suits their experience".to_string() } else { m.reasons.join(", ") };
+                    format!("{} {}: {}", record.species, record.id, why)
+                })
+                .collect();
+            let mut records: Vec<GerminationRecord> = matches.iter().map(|(i, _)| pool[*i].0.clone()).collect();
+            let package = proposal(&preferences, &records, reasons, now);
+
+            if let Some(lost) = claim(repos, &mut records, package.id).await? {
+                let lost = records[lost].id;
+                pool.retain(|(record, _)| record.id != lost);
+                // The records put back carry their new versions
+                for (record, _) in pool.iter_mut() {
+                    if let Some(released) = records.iter().find(|released| released.id == record.id) {
+                        *record = released.clone();
+                    }
+                }
+                continue;
+            }
+            pool.retain(|(record, _)| !records.iter().any(|taken| taken.id == record.id));
+
+            repos.packages.create(&package).await?;
+            proposals.push(package);
+            break;
+        }
+    }
+
+    info!(
+        "Allocation: {} packages proposed, {} customers skipped, {} sprouts left",
+        proposals.len(),
+        skipped.len(),
+        pool.len()
+    );
+
+    Ok(AllocationReport {
+        allocated_at: now,
+        proposals,
+        skipped,
+        unallocated: pool.into_iter().map(|(record, _)| record.id).collect(),
+    })
+}
+
+/// Mark each record as held by the package, each with a conditional write
+/// against the version it was read at
+///
+/// On a conflict the records already marked are put back and the index of
+/// the one that changed underneath is returned. `records` is left holding
+/// what was stored.
+pub(crate) async fn claim(
+    repos: &Repositories,
+    records: &mut [GerminationRecord],
+    package_id: Uuid,
+) -> Result<Option<usize>, ApiError> {
+    for i in 0..records.len() {
+        let mut record = records[i].clone();
+        record.package_id = Some(package_id);
+        match repos.records.update(&record).await {
+            Ok(stored) => records[i] = stored,
+            Err(DatabaseError::Conflict { .. }) => {
+                for record in &mut records[..i] {
+                    record.package_id = None;
+                    *record = repos.records.update(record).await?;
+                }
+                return Ok(Some(i));
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(None)
+}
+
+/// `None` when the record must not go to the customer
+fn suitability(
+    preferences: &CustomerSproutPreferences,
+    record: &GerminationRecord,
+    guide: &GerminationGuide,
+) -> Option<Match> {
+    if preferences.excluded_species.contains(&record.species)
+        || record.shipment_type != preferences.preferred_shipment_type
+        || !preferences.can_grow(guide.difficulty)
+    {
+        return None;
+    }
+
+    let mut m = Match { score: 0, reasons: Vec::new() };
+    if record.customer_id == preferences.customer_id {
+        m.score += 4;
+        m.reasons.push("grown from their own seed");
+    }
+    if preferences.preferred_species.contains(&record.species) {
+        m.score += 2;
+        m.reasons.push("a preferred species");
+    }
+    if preferences.has_light_for(&guide.light_requirement) {
+        m.score += 1;
+    }
+    Some(m)
+}
+
+fn tier_rank(tier: &SubscriptionTier) -> u8 {
+    match tier {
+        SubscriptionTier::Premium => 2,
+        SubscriptionTier::Standard => 1,
+        SubscriptionTier::BringYourOwnBags => 0,
+    }
+}
+
+/// The transit plan is made when staff prepare the shipment; until then the
+/// package assumes the worst and carries temperature control
+fn proposal(
+    preferences: &CustomerSproutPreferences,
+    records: &[GerminationRecord],
+    allocation_reasons: Vec<String>,
+    now: DateTime<Utc>,
+) -> SproutShipmentPackage {
+    let package_type = match preferences.preferred_shipment_type {
+        ShipmentType::Microgreens => PackageType::PaddedEnvelope,
+        _ => PackageType::VentilatedBox,
+    };
+    SproutShipmentPackage {
+        id: Uuid::new_v4(),
+        germination_records: records.iter().map(|record| record.id).collect(),
+        customer_id: preferences.customer_id,
+        shipment_cycle_id: None,
+        package_type,
+        container_count: 1,
+        total_sprouts: records.len() as u32,
+        moisture_retention: true,
+        temperature_control: true,
+        ventilation: true,
+        expedited_shipping_required: false,
+        ship_on_days: Vec::new(),
+        max_transit_days: transit::MAX_TRANSIT_DAYS,
+        care_instructions_included: true,
+        transplant_instructions_included: true,
+        species_info_card_included: true,
+        packed_at: None,
+        shipped_at: None,
+        expected_delivery: None,
+        status: PackageStatus::Proposed,
+        allocation_reasons,
+        created_at: now,
+        reviewed_by: None,
+        reviewed_at: None,
+        version: 0,
+    }
+}
+
+pub(crate) async fn get_preferences(repos: &Repositories, request: Request) -> ApiResult {
+    let customer_id = own_customer(&request)?;
+
+    match repos.preferences.get(customer_id).await {
+        Ok(preferences) => response::ok(&preferences),
+        Err(DatabaseError::NotFound(_)) => {
+            Err(ApiError::not_found(format!("Customer {} has no sprout preferences", customer_id)))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub(crate) async fn put_preferences(repos: &Repositories, request: Request) -> ApiResult {
+    let customer_id = own_customer(&request)?;
+    let req: SproutPreferencesRequest = request.json()?;
+
+    let mut errors = Vec::new();
+    // Each species id with the index of the first name that resolved to it
+    let mut resolve = |field: &str, names: &[String]| {
+        let mut ids: Vec<(usize, String)> = Vec::new();
+        for (i, name) in names.iter().enumerate() {
+            match Species::resolve(name) {
+                Some(species) if !ids.iter().any(|(_, id)| id == species.id) => ids.push((i, species.id.to_string())),
+                Some(_) => {}
+                None => errors.push(FieldError {
+                    field: format!("{}[{}]", field, i),
+                    message: format!("Unknown species '{}'", name),
+                }),
+            }
+        }
+        ids
+    };
+    let preferred_species: Vec<String> =
+        resolve("preferredSpecies", &req.preferred_species).into_iter().map(|(_, id)| id).collect();
+    let excluded = resolve("excludedSpecies", &req.excluded_species);
+
+    for (i, id) in &excluded {
+        if preferred_species.contains(id) {
+            errors.push(FieldError {
+                field: format!("excludedSpecies[{}]", i),
+                message: "is also preferred".to_string(),
+            });
+        }
+    }
+    let excluded_species = excluded.into_iter().map(|(_, id)| id).collect();
+    if req.sprouts_per_shipment_max == 0 {
+        errors
+            .push(FieldError { field: "sproutsPerShipmentMax".to_string(), message: "must be at least 1".to_string() });
+    }
+    if req.sprouts_per_shipment_min > req.sprouts_per_shipment_max {
+        errors.push(FieldError {
+            field: "sproutsPerShipmentMin".to_string(),
+            message: "must not exceed sproutsPerShipmentMax".to_string(),
+        });
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::Validation { message: "Sprout preferences are invalid".to_string(), errors });
+    }
+
+    let preferences = repos
+        .preferences
+        .put(&CustomerSproutPreferences {
+            customer_id,
+            preferred_species,
+            excluded_species,
+            preferred_shipment_type: req.preferred_shipment_type,
+            delivery_frequency: req.delivery_frequency,
+            preferred_delivery_days: req.preferred_delivery_days,
+            sprouts_per_shipment_min: req.sprouts_per_shipment_min,
+            sprouts_per_shipment_max: req.sprouts_per_shipment_max,
+            experience_level: req.experience_level,
+            has_grow_lights: req.has_grow_lights,
+            has_greenhouse: req.has_greenhouse,
+            has_outdoor_space: req.has_outdoor_space,
+        })
+        .await?;
+
+    response::ok(&preferences)
+}
+
+/// The `{id}` customer, if it's the caller or the caller is staff
+fn own_customer(request: &Request) -> Result<Uuid, ApiError> {
+    let customer_id = request.uuid_param("id")?;
+    let principal = request.principal()?;
+    if customer_id != principal.user_id && !principal.has_role(Role::Staff) {
+        return Err(ApiError::forbidden("Sprout preferences belong to another customer"));
+    }
+    Ok(customer_id)
+}
+
+pub(crate) async fn allocate_now(repos: &Repositories, _: Request) -> ApiResult {
+    let report = allocate(repos, Utc::now()).await?;
+
+    response::ok(&report)
+}
+
+/// Packages by `?status=`, proposals awaiting review by default
+pub(crate) async fn list_packages(repos: &Repositories, request: Request) -> ApiResult {
+    let status = request.query_as::<PackageStatus>("status")?.unwrap_or(PackageStatus::Proposed);
+
+    let mut packages = repos.packages.list_by_status(status).await?;
+    packages.sort_by_key(|package| package.created_at);
+
+    response::ok(&serde_json::json!({
+        "count": packages.len(),
+        "packages": packages,
+    }))
+}
+
+pub(crate) async fn approve_package(repos: &Repositories, request: Request) -> ApiResult {
+    review_package(repos, request, PackageStatus::Approved).await
+}
+
+pub(crate) async fn reject_package(repos: &Repositories, request: Request) -> ApiResult {
+    review_package(repos, request, PackageStatus::Rejected).await
+}
+
+async fn review_package(repos: &Repositories, request: Request, status: PackageStatus) -> ApiResult {
+    let id = request.uuid_param("id")?;
+    let principal = request.principal()?;
+
+    let mut package = repos.packages.get(id).await?;
+    if package.status != PackageStatus::Proposed {
+        return Err(ApiError::Conflict {
+            message: format!("Package {} was already {:?}", id, package.status).to_lowercase(),
+            current: serde_json::to_value(&package).ok(),
+        });
+    }
+
+    package.status = status;
+    package.reviewed_by = Some(principal.user_id.to_string());
+    package.reviewed_at = Some(Utc::now());
+    let package = repos.packages.update(&package).await?;
+
+    // A rejected package lets its records go to the next allocation run
+    if status == PackageStatus::Rejected {
+        for &record_id in &package.germination_records {
+            let mut record = repos.records.get(record_id).await?;
+            if record.package_id == Some(package.id) {
+                record.package_id = None;
+                repos.records.update(&record).await?;
+            }
+        }
+    }
+
+    info!("{} {:?} sprout package {}", principal.user_id, status, id);
+
+    response::ok(&package)
+}
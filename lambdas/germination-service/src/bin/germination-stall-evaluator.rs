@@ -0,0 +1,18 @@
+//! Scheduled stall evaluation; EventBridge invokes it daily (see template.yaml)
+
+use lambda_runtime::{run, service_fn, Error, LambdaEvent};
+
+use germination_service::{evaluate_stalls, Repositories};
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).with_target(false).without_time().init();
+
+    let repos = Repositories::new(database::get_client().await);
+
+    run(service_fn(|_: LambdaEvent<serde_json::Value>| async {
+        let report = evaluate_stalls(&repos, chrono::Utc::now(), true).await?;
+        Ok::<_, Error>(serde_json::to_value(report)?)
+    }))
+    .await
+}
@@ -0,0 +1,295 @@
+//! The germination guide catalog
+//!
+//! Guides live in the germination-guides table, keyed by the species' canonical
+//! id in the registry ([`Species`]).
+//! `guides.toml` is the bundled catalog that seeds an empty table; from there
+//! admins maintain it through the `/germination/guides` endpoints. Every guide
+//! passes [`validate`] before it is stored.
+
+use std::collections::HashMap;
+
+use api::{ApiError, FieldError};
+use models::{GerminationGuide, Species};
+use serde::{Deserialize, Serialize};
+
+/// The catalog shipped with the service
+pub const BUNDLED: &str = include_str!("../guides.toml");
+
+/// Guide fields a variety cannot override
+const IDENTITY_FIELDS: [&str; 4] = ["species", "variety", "varieties", "version"];
+
+/// A set of guides to import, as TOML (`[[guides]]`) or JSON (`{"guides": [...]}`)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Catalog {
+    pub guides: Vec<GerminationGuide>,
+}
+
+impl Catalog {
+    pub fn bundled() -> Self {
+        Self::from_toml(BUNDLED).expect("bundled guides.toml is a valid catalog")
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ApiError> {
+        toml::from_str(text).map_err(|err| ApiError::validation(format!("Invalid TOML catalog: {}", err.message())))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, ApiError> {
+        Ok(serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(text))?)
+    }
+
+    /// Normalize every guide, then validate each one and check that no
+    /// species has two guides
+    pub fn validate(&mut self) -> Result<(), ApiError> {
+        let mut errors = Vec::new();
+        let mut seen: HashMap<String, usize> = HashMap::new();
+
+        for (i, guide) in self.guides.iter_mut().enumerate() {
+            normalize(guide);
+            let prefix = format!("guides[{}].", i);
+            check(guide, &prefix, &mut errors);
+
+            if let Some(first) = seen.insert(guide.species.clone(), i) {
+                errors.push(FieldError {
+                    field: format!("{}species", prefix),
+                    message: format!("{} already has a guide at guides[{}]", guide.species, first),
+                });
+            }
+        }
+
+        into_result(errors)
+    }
+}
+
+/// The lookup key for a species, variety or alias
+pub fn key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// The guide key for a species name: its canonical id when registered
+pub fn species_key(name: &str) -> String {
+    Species::resolve(name).map_or_else(|| key(name), |species| species.id.to_string())
+}
+
+/// Key the guide by canonical species id and lowercase its variety names
+pub fn normalize(guide: &mut GerminationGuide) {
+    guide.species = species_key(&guide.species);
+    for variety in &mut guide.varieties {
+        variety.variety = key(&variety.variety);
+    }
+}
+
+/// Check a (normalized) guide and every variety it defines
+pub fn validate(guide: &GerminationGuide) -> Result<(), ApiError> {
+    let mut errors = Vec::new();
+    check(guide, "", &mut errors);
+    into_result(errors)
+}
+
+/// The guide for `variety`: the species guide with that variety's overrides
+/// applied, or the species guide itself when the variety has none
+pub fn resolve(guide: &GerminationGuide, variety: Option<&str>) -> Result<GerminationGuide, ApiError> {
+    let Some(variety) = variety.map(key) else {
+        return Ok(guide.clone());
+    };
+    match guide.varieties.iter().position(|v| v.variety == variety) {
+        Some(i) => apply(guide, i).map_err(|error| ApiError::Validation {
+            message: format!("Overrides for {} {} are invalid", guide.species, variety),
+            errors: vec![error],
+        }),
+        None => Ok(guide.clone()),
+    }
+}
+
+/// Merge the overrides of `guide.varieties[i]` over the species guide
+fn apply(guide: &GerminationGuide, i: usize) -> Result<GerminationGuide, FieldError> {
+    let variety = &guide.varieties[i];
+    let prefix = format!("varieties[{}].overrides", i);
+
+    let mut merged = match serde_json::to_value(guide) {
+        Ok(serde_json::Value::Object(fields)) => fields,
+        _ => unreachable!("a guide serializes to an object"),
+    };
+    for (field, value) in &variety.overrides {
+        if IDENTITY_FIELDS.contains(&field.as_str()) || !merged.contains_key(field) {
+            return Err(FieldError {
+                field: format!("{}.{}", prefix, field),
+                message: "not a guide field a variety can override".to_string(),
+            });
+        }
+        merged.insert(field.clone(), value.clone());
+    }
+
+    let mut resolved: GerminationGuide =
+        serde_path_to_error::deserialize(serde_json::Value::Object(merged)).map_err(|err| FieldError {
+            field: format!("{}.{}", prefix, err.path()),
+            message: err.into_inner().to_string(),
+        })?;
+    resolved.variety = Some(variety.variety.clone());
+    resolved.varieties = Vec::new();
+    Ok(resolved)
+}
+
+fn check(guide: &GerminationGuide, prefix: &str, errors: &mut Vec<FieldError>) {
+    check_fields(guide, prefix, errors);
+
+    let mut seen = Vec::new();
+    for (i, variety) in guide.varieties.iter().enumerate() {
+        if seen.contains(&variety.variety) {
+            errors.push(FieldError {
+                field: format!("{}varieties[{}].variety", prefix, i),
+                message: format!("{} is listed twice", variety.variety),
+            });
+        }
+        seen.push(variety.variety.clone());
+
+        match apply(guide, i) {
+            Ok(resolved) => check_fields(&resolved, &format!("{}varieties[{}].", prefix, i), errors),
+            Err(mut error) => {
+                error.field = format!("{}{}", prefix, error.field);
+                errors.push(error);
+            }
+        }
+    }
+}
+
+/// Day ranges must be well formed and the stages must follow one another
+fn check_fields(guide: &GerminationGuide, prefix: &str, errors: &mut Vec<FieldError>) {
+    let mut fail = |field: &str, message: String| {
+        errors.push(FieldError { field: format!("{}{}", prefix, field), message });
+    };
+
+    if Species::resolve(&guide.species).is_none() {
+        fail("species", format!("{} is not in the species registry", guide.species));
+    }
+
+    let stages = [
+        ("radicle_emergence_days", guide.radicle_emergence_days_min, guide.radicle_emergence_days_max),
+        ("shoot_emergence_days", guide.shoot_emergence_days_min, guide.shoot_emergence_days_max),
+        ("cotyledon_expansion_days", guide.cotyledon_expansion_days_min, guide.cotyledon_expansion_days_max),
+        ("true_leaf_emergence_days", guide.true_leaf_emergence_days_min, guide.true_leaf_emergence_days_max),
+        ("photosynthesis_days", guide.photosynthesis_days_min, guide.photosynthesis_days_max),
+    ];
+    for (stage, min, max) in stages {
+        if min > max {
+            fail(&format!("{}_max", stage), format!("must be at least {}_min ({})", stage, min));
+        }
+    }
+    if guide.imbibition_days > guide.radicle_emergence_days_min {
+        fail(
+            "imbibition_days",
+            format!("must not exceed radicle_emergence_days_min ({})", guide.radicle_emergence_days_min),
+        );
+    }
+    for pair in stages.windows(2) {
+        let ((earlier, earlier_min, earlier_max), (later, later_min, later_max)) = (pair[0], pair[1]);
+        if later_min < earlier_min {
+            fail(&format!("{}_min", later), format!("must not come before {}_min ({})", earlier, earlier_min));
+        }
+        if later_max < earlier_max {
+            fail(&format!("{}_max", later), format!("must not come before {}_max ({})", earlier, earlier_max));
+        }
+    }
+
+    if guide.must_be_true_plant && guide.ready_to_ship_days < guide.true_leaf_emergence_days_min {
+        fail(
+            "ready_to_ship_days",
+            format!("must allow for true leaves (true_leaf_emergence_days_min {})", guide.true_leaf_emergence_days_min),
+        );
+    }
+    if guide.must_be_autotrophic && guide.ready_to_ship_days < guide.photosynthesis_days_min {
+        fail(
+            "ready_to_ship_days",
+            format!("must allow for photosynthesis (photosynthesis_days_min {})", guide.photosynthesis_days_min),
+        );
+    }
+
+    let temperature = &guide.optimal_temperature_celsius;
+    if !(temperature.min <= temperature.optimal && temperature.optimal <= temperature.max) {
+        fail("optimal_temperature_celsius", "must have min <= optimal <= max".to_string());
+    }
+    let humidity = &guide.optimal_humidity_percent;
+    if !(0.0 <= humidity.min
+        && humidity.min <= humidity.optimal
+        && humidity.optimal <= humidity.max
+        && humidity.max <= 100.0)
+    {
+        fail("optimal_humidity_percent", "must have 0 <= min <= optimal <= max <= 100".to_string());
+    }
+
+    if guide.pre_soak_required && guide.pre_soak_hours.is_none() {
+        fail("pre_soak_hours", "is required when pre_soak_required is set".to_string());
+    }
+    if guide.stratification_required && guide.stratification_days.is_none() {
+        fail("stratification_days", "is required when stratification_required is set".to_string());
+    }
+}
+
+fn into_result(errors: Vec<FieldError>) -> Result<(), ApiError> {
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(ApiError::Validation { message: format!("Germination guide is invalid ({} problem(s))", errors.len()), errors })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tomato() -> GerminationGuide {
+        Catalog::bundled().guides.into_iter().find(|g| g.species == "tomato").unwrap()
+    }
+
+    fn fields(err: ApiError) -> Vec<String> {
+        match err {
+            ApiError::Validation { errors, .. } => errors.into_iter().map(|e| e.field).collect(),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_bundled_catalog_is_valid() {
+        let mut catalog = Catalog::bundled();
+        catalog.validate().unwrap();
+
+        let mut catalog = Catalog::from_json(r#"{"guides": []}"#).unwrap();
+        let mut guide = tomato();
+        guide.species = "Tomatoes".to_string();
+        catalog.guides = vec![tomato(), guide];
+        assert_eq!(fields(catalog.validate().unwrap_err()), ["guides[1].species"]);
+    }
+
+    #[test]
+    fn test_out_of_order_stages_are_rejected() {
+        let mut guide = tomato();
+        guide.shoot_emergence_days_min = 9;
+        guide.shoot_emergence_days_max = 8;
+        guide.cotyledon_expansion_days_max = 7;
+
+        assert_eq!(
+            fields(validate(&guide).unwrap_err()),
+            ["shoot_emergence_days_max", "cotyledon_expansion_days_min", "cotyledon_expansion_days_max"]
+        );
+    }
+
+    #[test]
+    fn test_variety_overrides_the_species_guide() {
+        let mut guide = tomato();
+        guide.varieties.push(models::VarietyOverride {
+            variety: "cherry".to_string(),
+            overrides: serde_json::json!({ "ready_to_ship_days": 12 }).as_object().unwrap().clone(),
+        });
+        validate(&guide).unwrap();
+
+        let cherry = resolve(&guide, Some("Cherry")).unwrap();
+        assert_eq!(cherry.ready_to_ship_days, 12);
+        assert_eq!(cherry.variety.as_deref(), Some("cherry"));
+        assert_eq!(resolve(&guide, Some("roma")).unwrap().ready_to_ship_days, 14);
+
+        // An override is validated as the guide it produces
+        guide.varieties[0].overrides.insert("radicle_emergence_days_max".to_string(), 2.into());
+        guide.varieties[0].overrides.insert("min_leaf_count".to_string(), 2.into());
+        assert_eq!(fields(validate(&guide).unwrap_err()), ["varieties[0].overrides.min_leaf_count"]);
+        guide.varieties[0].overrides.remove("min_leaf_count");
+        assert_eq!(fields(validate(&guide).unwrap_err()), ["varieties[0].radicle_emergence_days_max"]);
+    }
+}
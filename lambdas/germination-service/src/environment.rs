@@ -0,0 +1,76 @@
+//! Environmental compliance
+//!
+//! Each observation's temperature and humidity are checked against the
+//! optimal ranges in the record's guide. Readings outside them are noted on
+//! the observation; a condition that stays out of range across
+//! `ALERT_AFTER_OBSERVATIONS` consecutive readings raises an alert on the
+//! record, which escalates to critical and resolves once a reading is back in
+//! range (see [`GerminationRecord::track_environment`]).
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use uuid::Uuid;
+
+use api::ApiError;
+use models::{
+    EnvironmentalAlert, EnvironmentalDeviation, GerminationObservation, GerminationRecord, HumidityRange,
+    TemperatureRange,
+};
+
+use crate::{record_guide, Repositories};
+
+/// One observation's conditions
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentalReading {
+    observation_id: Uuid,
+    observed_at: DateTime<Utc>,
+    temperature_celsius: Option<f32>,
+    humidity_percent: Option<f32>,
+    deviations: Vec<EnvironmentalDeviation>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentalHistory {
+    germination_record_id: Uuid,
+    species: String,
+    variety: Option<String>,
+    /// The guide's current ranges; `None` when the species no longer has a guide
+    optimal_temperature_celsius: Option<TemperatureRange>,
+    optimal_humidity_percent: Option<HumidityRange>,
+    /// Oldest first
+    readings: Vec<EnvironmentalReading>,
+    alerts: Vec<EnvironmentalAlert>,
+}
+
+pub(crate) async fn environmental_history(
+    repos: &Repositories,
+    record: GerminationRecord,
+) -> Result<EnvironmentalHistory, ApiError> {
+    let guide = record_guide(repos, &record).await?;
+    let observations = repos.observations.list_by_record(record.id).await?;
+
+    Ok(EnvironmentalHistory {
+        germination_record_id: record.id,
+        species: record.species,
+        variety: record.variety,
+        optimal_temperature_celsius: guide.as_ref().map(|g| g.optimal_temperature_celsius.clone()),
+        optimal_humidity_percent: guide.map(|g| g.optimal_humidity_percent),
+        readings: observations.into_iter().map(EnvironmentalReading::from).collect(),
+        alerts: record.environmental_alerts,
+    })
+}
+
+impl From<GerminationObservation> for EnvironmentalReading {
+    fn from(observation: GerminationObservation) -> Self {
+        Self {
+            observation_id: observation.id,
+            observed_at: observation.observed_at,
+            temperature_celsius: observation.temperature_celsius,
+            humidity_percent: observation.humidity_percent,
+            deviations: observation.environmental_deviations,
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Ship-date forecasting
+//!
+//! A record's ship date starts from a species prior: how long past records
+//! of the species took to become ready, or the guide's `ready_to_ship_days`
+//! until there are enough of them. Each observation then fits a straight-line
+//! growth curve per measurement the guide requires (root, shoot, true leaves)
+//! and projects when it reaches the requirement; the slowest one is the
+//! evidence. Prior and evidence are combined by their precision, so a record
+//! with a few noisy observations still leans on the species, and one with a
+//! clear trend follows its own. A warm lambda keeps each species' past
+//! durations for `PRIOR_CACHE_MINUTES` rather than reloading the species on
+//! every start and observation.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use api::ApiError;
+use models::{GerminationGuide, GerminationObservation, GerminationRecord, ShipDateForecast};
+
+use crate::Repositories;
+
+/// Two-sided confidence of the published interval, and its z-score
+const CONFIDENCE: f32 = 0.8;
+const Z: f64 = 1.2816;
+/// Past records needed before they replace the guide as the prior
+const MIN_PRIOR_RECORDS: usize = 3;
+/// Floor on any standard deviation, in days; two points fit a line exactly
+const MIN_SD_DAYS: f64 = 0.5;
+/// How long a species' past durations are reused before they are reloaded
+const PRIOR_CACHE_MINUTES: i64 = 60;
+
+/// Days from start to ready for a species
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Prior {
+    mean_days: f64,
+    sd_days: f64,
+    records: usize,
+}
+
+impl Prior {
+    /// From the days the species' past records took to reach shipment,
+    /// falling back to the guide's timing while there are fewer than
+    /// `MIN_PRIOR_RECORDS`
+    pub(crate) fn for_species(guide: &GerminationGuide, durations: &[f64]) -> Self {
+        if durations.len() < MIN_PRIOR_RECORDS {
+            let window = guide.photosynthesis_days_max.saturating_sub(guide.photosynthesis_days_min) as f64;
+            return Self { mean_days: guide.ready_to_ship_days as f64, sd_days: (window / 2.0).max(1.0), records: 0 };
+        }
+
+        let n = durations.len() as f64;
+        let mean = durations.iter().sum::<f64>() / n;
+        let variance = durations.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        Self { mean_days: mean, sd_days: variance.sqrt().max(MIN_SD_DAYS), records: durations.len() }
+    }
+}
+
+/// Start-to-ready days of a species' past records, and when they were loaded
+type Durations = (DateTime<Utc>, Arc<Vec<f64>>);
+
+#[derive(Default)]
+pub(crate) struct PriorCache(Mutex<HashMap<String, Durations>>);
+
+impl PriorCache {
+    async fn durations(
+        &self,
+        repos: &Repositories,
+        species: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Arc<Vec<f64>>, ApiError> {
+        let cached = self.0.lock().unwrap().get(species).cloned();
+        if let Some((loaded, durations)) = cached {
+            if now - loaded < Duration::minutes(PRIOR_CACHE_MINUTES) {
+                return Ok(durations);
+            }
+        }
+
+        let durations: Arc<Vec<f64>> = Arc::new(
+            repos
+                .records
+                .list_by_species(species)
+                .await?
+                .iter()
+                .filter_map(|record| Some(days_between(record.started_at, record.ready_for_shipment_at?)))
+                .collect(),
+        );
+        self.0.lock().unwrap().insert(species.to_string(), (now, durations.clone()));
+        Ok(durations)
+    }
+}
+
+/// Shipments forecast for one day
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipDay {
+    date: NaiveDate,
+    /// Records whose estimate falls on the day
+    expected: usize,
+    /// Records whose interval covers the day; pack for this many at most
+    possible: usize,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipForecast {
+    confidence: f32,
+    days: Vec<ShipDay>,
+}
+
+/// Refresh the record's forecast and `estimated_ship_date` from its
+/// observations, oldest first
+pub(crate) async fn reforecast(
+    repos: &Repositories,
+    record: &mut GerminationRecord,
+    guide: &GerminationGuide,
+    observations: &[GerminationObservation],
+) -> Result<(), ApiError> {
+    let now = Utc::now();
+    let durations = repos.priors.durations(repos, &record.species, now).await?;
+    let forecast = forecast(record, guide, observations, Prior::for_species(guide, &durations), now);
+
+    record.estimated_ship_date = forecast.as_ref().map(|forecast| forecast.estimated);
+    record.ship_date_forecast = forecast;
+    Ok(())
+}
+
+/// Expected and possible shipments per day from `from`, over records still
+/// growing, which includes those waiting to ship
+pub(crate) async fn ship_forecast(repos: &Repositories, from: NaiveDate, days: u32) -> Result<ShipForecast, ApiError> {
+    let forecasts: Vec<ShipDateForecast> = repos
+        .records
+        .list_all()
+        .await?
+        .into_iter()
+        .filter(|record| record.germination_phase.is_growing())
+        .filter_map(|record| record.ship_date_forecast)
+        .collect();
+
+    let days = from
+        .iter_days()
+        .take(days as usize)
+        .map(|date| ShipDay {
+            date,
+            expected: forecasts.iter().filter(|f| f.estimated.date_naive() == date).count(),
+            possible: forecasts
+                .iter()
+                .filter(|f| f.earliest.date_naive() <= date && date <= f.latest.date_naive())
+                .count(),
+        })
+        .collect();
+
+    Ok(ShipForecast { confidence: CONFIDENCE, days })
+}
+
+/// Forecast the record's ship date from the prior and its observations,
+/// oldest first. No date is earlier than `now`; None when growth is so slow
+/// the estimate falls past any date that can be held
+pub(crate) fn forecast(
+    record: &GerminationRecord,
+    guide: &GerminationGuide,
+    observations: &[GerminationObservation],
+    prior: Prior,
+    now: DateTime<Utc>,
+) -> Option<ShipDateForecast> {
+    let mut days = prior.mean_days;
+    let mut sd = prior.sd_days;
+
+    if let Some((evidence_days, evidence_sd)) = evidence(record, guide, observations) {
+        let (prior_precision, evidence_precision) = (sd.powi(-2), evidence_sd.powi(-2));
+        days = (prior.mean_days * prior_precision + evidence_days * evidence_precision)
+            / (prior_precision + evidence_precision);
+        sd = (prior_precision + evidence_precision).powf(-0.5);
+    }
+
+    let at = |days: f64| Some(days_after(record.started_at, days)?.max(now));
+    Some(ShipDateForecast {
+        estimated: at(days)?,
+        // Only out of range before the start, which is long past
+        earliest: at(days - Z * sd).unwrap_or(now),
+        latest: at(days + Z * sd)?,
+        confidence: CONFIDENCE,
+        observations: observations.len(),
+        prior_records: prior.records,
+        forecast_at: now,
+    })
+}
+
+/// `days` after `start`, to the minute; None out of the range dates can hold
+fn days_after(start: DateTime<Utc>, days: f64) -> Option<DateTime<Utc>> {
+    Duration::try_minutes((days * 24.0 * 60.0).round() as i64).and_then(|offset| start.checked_add_signed(offset))
+}
+
+/// The slowest projection across the measurements the guide requires, as
+/// (days from start, standard deviation); `None` until a curve can be fitted
+fn evidence(
+    record: &GerminationRecord,
+    guide: &GerminationGuide,
+    observations: &[GerminationObservation],
+) -> Option<(f64, f64)> {
+    let curve = |reading: fn(&GerminationObservation) -> Option<f32>, since: Option<DateTime<Utc>>, required: f32| {
+        let mut points: Vec<(f64, f64)> = observations
+            .iter()
+            .filter_map(|observation| {
+                Some((days_between(record.started_at, observation.observed_at), reading(observation)? as f64))
+            })
+            .collect();
+        // With a single reading, growth is taken from zero when the stage emerged
+        if let (1, Some(since)) = (points.len(), since) {
+            points.insert(0, (days_between(record.started_at, since), 0.0));
+        }
+        project(&points, required as f64)
+    };
+
+    [
+        curve(|o| o.root_length_mm, record.radicle_emerged_at, guide.min_root_length_mm),
+        curve(|o| o.shoot_length_mm, record.shoot_emerged_at, guide.min_shoot_length_mm),
+        curve(
+            |o| o.true_leaf_count.map(|count| count as f32),
+            record.true_leaf_emerged_at,
+            guide.min_true_leaf_count as f32,
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .max_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/// Fit `value = a + b * day` by least squares and invert it at `required`:
+/// the day it is reached, with the standard error of that estimate
+fn project(points: &[(f64, f64)], required: f64) -> Option<(f64, f64)> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    if sxx <= 0.0 {
+        return None;
+    }
+
+    let slope = sxy / sxx;
+    if slope <= 0.0 {
+        return None;
+    }
+    let intercept = mean_y - slope * mean_x;
+    let day = (required - intercept) / slope;
+
+    let residual_sd = if points.len() > 2 {
+        let sse: f64 = points.iter().map(|p| (p.1 - intercept - slope * p.0).powi(2)).sum();
+        (sse / (n - 2.0)).sqrt()
+    } else {
+        0.0
+    };
+    let sd = residual_sd / slope * (1.0 / n + (day - mean_x).powi(2) / sxx).sqrt();
+    Some((day, sd.max(MIN_SD_DAYS)))
+}
+
+fn days_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_microseconds().unwrap_or(i64::MAX) as f64 / 86_400_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_growth_curve_projects_when_requirement_is_reached() {
+        // 2 mm a day from day 2, needing 20 mm: day 12
+        let (day, sd) = project(&[(2.0, 0.0), (4.0, 4.0), (6.0, 8.0), (8.0, 12.0)], 20.0).unwrap();
+        assert!((day - 12.0).abs() < 1e-9);
+        assert_eq!(sd, MIN_SD_DAYS);
+
+        // Noise widens the interval the further out the projection is
+        let (_, noisy) = project(&[(2.0, 0.0), (4.0, 5.0), (6.0, 7.0), (8.0, 12.5)], 20.0).unwrap();
+        let (_, noisier) = project(&[(2.0, 0.0), (4.0, 5.0), (6.0, 7.0), (8.0, 12.5)], 40.0).unwrap();
+        assert!(MIN_SD_DAYS < noisy && noisy < noisier);
+
+        assert!(project(&[(2.0, 5.0), (4.0, 5.0)], 20.0).is_none());
+        assert!(project(&[(2.0, 5.0)], 20.0).is_none());
+    }
+
+    #[test]
+    fn test_days_after_stays_within_representable_dates() {
+        let start = Utc::now();
+        assert_eq!(days_after(start, 1.5), Some(start + Duration::hours(36)));
+        // A crawl projected from a tiny growth rate
+        assert_eq!(days_after(start, 1e300), None);
+        assert_eq!(days_after(start, -1e300), None);
+    }
+}
@@ -0,0 +1,1871 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{Error, LambdaEvent};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use api::{response, ApiError, ApiResult, FieldError, Request, Role, Router};
+use database::{
+    DatabaseError, GerminationGuidesRepository, GerminationObservationsRepository, GerminationRecordsRepository,
+    GerminationTraysRepository, ManufacturingQueueRepository, SproutPackagesRepository, SproutPreferencesRepository,
+    SubscriptionsRepository,
+};
+use models::{
+    Address, EnvironmentalDeviation, GerminationGuide, GerminationObservation, GerminationPhase, GerminationRecord,
+    GrowingMedium, PackageStatus, Readiness, ShipmentType, Species, SproutHealthStatus, SproutShipmentPackage, Weekday,
+};
+
+mod allocation;
+pub mod catalog;
+mod environment;
+mod forecast;
+mod stalls;
+mod transit;
+mod trays;
+pub use allocation::{AllocationReport, SproutPreferencesRequest};
+pub use catalog::Catalog;
+pub use environment::EnvironmentalHistory;
+pub use forecast::ShipForecast;
+pub use stalls::{evaluate_stalls, StallReport};
+pub use transit::TransitEstimate;
+pub use trays::{StartTrayRequest, TrayObservationRequest, TrayObservationResponse, TrayReport, TrayResponse};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StartGerminationRequest {
+    seed_id: Uuid,
+    customer_id: Uuid,
+    species: String,
+    variety: Option<String>,
+    growing_medium: GrowingMedium,
+    shipment_type: ShipmentType,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordObservationRequest {
+    germination_record_id: Uuid,
+    root_length_mm: Option<f32>,
+    shoot_length_mm: Option<f32>,
+    leaf_count: Option<u32>,
+    #[serde(default)]
+    cotyledon_count: Option<u32>,
+    #[serde(default)]
+    true_leaf_count: Option<u32>,
+    health_status: SproutHealthStatus,
+    /// What the grower saw; each moves the record on to that stage
+    #[serde(default)]
+    radicle_visible: bool,
+    #[serde(default)]
+    shoot_visible: bool,
+    #[serde(default)]
+    cotyledons_expanded: bool,
+    #[serde(default)]
+    true_leaves_present: bool,
+    #[serde(default)]
+    appears_autotrophic: bool,
+    temperature_celsius: Option<f32>,
+    humidity_percent: Option<f32>,
+    notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePhaseRequest {
+    germination_record_id: Uuid,
+    new_phase: GerminationPhase,
+    notes: Option<String>,
+    /// Version of the record the caller last read; omit to update whatever is stored
+    #[serde(default)]
+    expected_version: Option<u64>,
+    /// Allow a move other than the next phase or `FAILED`; admins only
+    #[serde(default)]
+    admin_override: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PrepareShipmentRequest {
+    germination_record_ids: Vec<Uuid>,
+    customer_id: Uuid,
+    expedited: bool,
+    destination: Address,
+    /// Days the customer can take delivery; any day the carrier delivers when empty
+    #[serde(default)]
+    preferred_delivery_days: Vec<Weekday>,
+    /// Carrier quotes for the destination; USPS Priority Mail and Priority Mail Express when empty
+    #[serde(default)]
+    transit_estimates: Vec<TransitEstimate>,
+}
+
+/// A record's new phase; unlike the other responses its fields stay snake_case
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PhaseResponse {
+    germination_record_id: Uuid,
+    new_phase: GerminationPhase,
+    updated_at: String,
+    ready_for_shipment: bool,
+    notes: Option<String>,
+    version: u64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GerminationResponse {
+    germination_record_id: Uuid,
+    seed_id: Uuid,
+    species: String,
+    phase: GerminationPhase,
+    germination_success: bool,
+    health_status: SproutHealthStatus,
+    days_since_started: i64,
+    ready_for_shipment: bool,
+    estimated_ship_date: Option<String>,
+    /// Bounds of the forecast's confidence interval
+    earliest_ship_date: Option<String>,
+    latest_ship_date: Option<String>,
+    version: u64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessResponse {
+    germination_record_id: Uuid,
+    species: String,
+    phase: GerminationPhase,
+    readiness: Readiness,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipmentPackageResponse {
+    package_id: Uuid,
+    sprout_count: u32,
+    expedited: bool,
+    estimated_ship_date: Option<String>,
+    expected_delivery: Option<chrono::DateTime<chrono::Utc>>,
+    carrier: String,
+    service: String,
+    transit_days: u32,
+    temperature_control: bool,
+    ship_on_days: Vec<Weekday>,
+    care_instructions_url: String,
+}
+
+/// What an import did with each guide in the catalog
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    created: Vec<String>,
+    skipped: Vec<String>,
+}
+
+pub struct Repositories {
+    records: GerminationRecordsRepository,
+    observations: GerminationObservationsRepository,
+    guides: GerminationGuidesRepository,
+    trays: GerminationTraysRepository,
+    /// Stall reviews go to the manufacturing queue for staff
+    queue: ManufacturingQueueRepository,
+    packages: SproutPackagesRepository,
+    preferences: SproutPreferencesRepository,
+    /// Read for the allocator's tier order
+    subscriptions: SubscriptionsRepository,
+    priors: forecast::PriorCache,
+}
+
+impl Repositories {
+    pub fn new(client: aws_sdk_dynamodb::Client) -> Self {
+        Self {
+            records: GerminationRecordsRepository::new(client.clone()),
+            observations: GerminationObservationsRepository::new(client.clone()),
+            guides: GerminationGuidesRepository::new(client.clone()),
+            trays: GerminationTraysRepository::new(client.clone()),
+            queue: ManufacturingQueueRepository::new(client.clone()),
+            packages: SproutPackagesRepository::new(client.clone()),
+            preferences: SproutPreferencesRepository::new(client.clone()),
+            subscriptions: SubscriptionsRepository::new(client),
+            priors: forecast::PriorCache::default(),
+        }
+    }
+
+    /// Process-local tables, for tests and the dev server
+    #[cfg(any(test, feature = "in-memory"))]
+    pub fn in_memory() -> Self {
+        Self {
+            records: GerminationRecordsRepository::in_memory(),
+            observations: GerminationObservationsRepository::in_memory(),
+            guides: GerminationGuidesRepository::in_memory(),
+            trays: GerminationTraysRepository::in_memory(),
+            queue: ManufacturingQueueRepository::in_memory(),
+            packages: SproutPackagesRepository::in_memory(),
+            preferences: SproutPreferencesRepository::in_memory(),
+            subscriptions: SubscriptionsRepository::in_memory(),
+            priors: forecast::PriorCache::default(),
+        }
+    }
+}
+
+/// Literal routes are registered ahead of `/germination/{id}` so they win the match
+static ROUTER: LazyLock<Router<Repositories>> = LazyLock::new(|| {
+    Router::new()
+        .post("/germination/start", start_germination)
+        .requires(Role::Staff)
+        .post("/germination/observe", record_observation)
+        .requires(Role::Staff)
+        .put("/germination/phase", update_phase)
+        .requires(Role::Staff)
+        .post("/germination/shipment", prepare_shipment)
+        .requires(Role::Staff)
+        .get("/germination/ready", list_ready_for_shipment)
+        .requires(Role::Staff)
+        .get("/germination/stalls", get_stall_report)
+        .requires(Role::Staff)
+        .get("/germination/forecast", get_ship_forecast)
+        .requires(Role::Staff)
+        .post("/germination/stalls/evaluate", evaluate_stalls_now)
+        .requires(Role::Staff)
+        .post("/germination/allocations", allocation::allocate_now)
+        .requires(Role::Staff)
+        .get("/germination/packages", allocation::list_packages)
+        .requires(Role::Staff)
+        .put("/germination/packages/{id}/approve", allocation::approve_package)
+        .requires(Role::Staff)
+        .put("/germination/packages/{id}/reject", allocation::reject_package)
+        .requires(Role::Staff)
+        .get("/germination/preferences/{id}", allocation::get_preferences)
+        .requires(Role::Customer)
+        .put("/germination/preferences/{id}", allocation::put_preferences)
+        .requires(Role::Customer)
+        .post("/germination/trays", trays::start_tray)
+        .requires(Role::Staff)
+        .get("/germination/trays/report", trays::tray_report)
+        .requires(Role::Staff)
+        .get("/germination/trays/{id}", trays::get_tray)
+        .requires(Role::Staff)
+        .post("/germination/trays/{id}/observe", trays::observe_tray)
+        .requires(Role::Staff)
+        .get("/germination/guide/{species}", get_germination_guide)
+        .get("/germination/guides", list_guides)
+        .requires(Role::Staff)
+        .post("/germination/guides", create_guide)
+        .requires(Role::Admin)
+        .post("/germination/guides/import", import_guides)
+        .requires(Role::Admin)
+        .put("/germination/guides/{species}", update_guide)
+        .requires(Role::Admin)
+        .delete("/germination/guides/{species}", delete_guide)
+        .requires(Role::Admin)
+        .get("/germination/{id}", get_germination_record)
+        .requires(Role::Customer)
+        .get("/germination/{id}/environment", get_environmental_history)
+        .requires(Role::Customer)
+        .get("/germination/{id}/readiness", get_readiness)
+        .requires(Role::Customer)
+});
+
+pub async fn function_handler(
+    repos: &Repositories,
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    info!("Processing germination request");
+
+    Ok(ROUTER.handle(repos, event.payload).await)
+}
+
+impl From<&GerminationRecord> for GerminationResponse {
+    fn from(record: &GerminationRecord) -> Self {
+        Self {
+            germination_record_id: record.id,
+            seed_id: record.seed_id,
+            species: record.species.clone(),
+            phase: record.germination_phase.clone(),
+            germination_success: record.germination_success,
+            health_status: record.health_status.clone(),
+            days_since_started: (chrono::Utc::now() - record.started_at).num_days(),
+            ready_for_shipment: record.germination_phase.is_shippable(),
+            estimated_ship_date: record.estimated_ship_date.map(|d| d.to_rfc3339()),
+            earliest_ship_date: record.ship_date_forecast.as_ref().map(|f| f.earliest.to_rfc3339()),
+            latest_ship_date: record.ship_date_forecast.as_ref().map(|f| f.latest.to_rfc3339()),
+            version: record.version,
+        }
+    }
+}
+
+async fn start_germination(repos: &Repositories, request: Request) -> ApiResult {
+    let req: StartGerminationRequest = request.json()?;
+
+    let (species, guide) = starting_guide(repos, &req.species, req.variety.as_deref()).await?;
+    let record = new_record(repos, req, species, &guide, chrono::Utc::now()).await?;
+
+    info!("Started germination record {} for seed {} ({})", record.id, record.seed_id, record.species);
+
+    repos.records.create(&record).await?;
+
+    let response = GerminationResponse::from(&record);
+
+    response::created(&response)
+}
+
+/// The species a new record is for and its guide, with the variety's
+/// overrides applied; an unknown species or one without a guide is a field error
+async fn starting_guide(
+    repos: &Repositories,
+    species: &str,
+    variety: Option<&str>,
+) -> Result<(&'static Species, GerminationGuide), ApiError> {
+    let resolved = Species::resolve(species).ok_or_else(|| ApiError::unknown_species("species", species))?;
+    let guide = match find_guide(&repos.guides, resolved.id).await {
+        Err(ApiError::NotFound(message)) => return Err(ApiError::invalid_field("species", message)),
+        guide => catalog::resolve(&guide?, variety)?,
+    };
+    Ok((resolved, guide))
+}
+
+/// An unsaved record for the seed, with a ship date forecast from its guide
+async fn new_record(
+    repos: &Repositories,
+    req: StartGerminationRequest,
+    species: &Species,
+    guide: &GerminationGuide,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<GerminationRecord, ApiError> {
+    let mut record = GerminationRecord {
+        id: Uuid::new_v4(),
+        seed_id: req.seed_id,
+        plant_id: None,
+        customer_id: req.customer_id,
+        species: species.id.to_string(),
+        variety: req.variety,
+        germination_phase: GerminationPhase::Imbibition,
+        started_at: now,
+        imbibition_started_at: now,
+        radicle_emerged_at: None,
+        shoot_emerged_at: None,
+        cotyledon_expanded_at: None,
+        true_leaf_emerged_at: None,
+        photosynthesis_started_at: None,
+        ready_for_shipment_at: None,
+        growing_medium: req.growing_medium,
+        temperature_celsius: None,
+        humidity_percent: None,
+        light_hours_per_day: None,
+        germination_success: false, // Will be updated when radicle emerges
+        health_status: SproutHealthStatus::Good,
+        root_length_mm: None,
+        shoot_length_mm: None,
+        cotyledon_count: None,
+        true_leaf_count: None,
+        total_leaf_count: None,
+        is_true_plant: false,
+        is_autotrophic: false,
+        has_edible_fruit_potential: Some(species.bears_fruit()),
+        has_edible_leaves_potential: Some(species.has_edible_leaves()),
+        has_edible_stalks_potential: Some(species.has_edible_stalks()),
+        shipment_type: req.shipment_type,
+        estimated_ship_date: None,
+        actual_ship_date: None,
+        customer_instructions: Some(guide.customer_care_instructions.clone()),
+        notes: None,
+        stalled_phase: None,
+        environmental_alerts: vec![],
+        ship_date_forecast: None,
+        tray_id: None,
+        package_id: None,
+        version: 0,
+    };
+    forecast::reforecast(repos, &mut record, guide, &[]).await?;
+
+    Ok(record)
+}
+
+async fn record_observation(repos: &Repositories, request: Request) -> ApiResult {
+    let req: RecordObservationRequest = request.json()?;
+
+    let observation = observe(repos, req, request.principal()?.user_id.to_string()).await?;
+
+    response::created(&observation)
+}
+
+/// Store an observation: note conditions outside the guide's ranges, move the
+/// record through the phases it shows, track alerts and re-forecast the ship date
+async fn observe(
+    repos: &Repositories,
+    req: RecordObservationRequest,
+    observed_by: String,
+) -> Result<GerminationObservation, ApiError> {
+    let mut record = repos.records.get(req.germination_record_id).await?;
+
+    let guide = record_guide(repos, &record).await?;
+    let environmental_deviations = guide
+        .as_ref()
+        .map(|guide| guide.environmental_deviations(req.temperature_celsius, req.humidity_percent))
+        .unwrap_or_default();
+
+    let observation_id = Uuid::new_v4();
+    let observation = GerminationObservation {
+        id: observation_id,
+        germination_record_id: req.germination_record_id,
+        observed_at: chrono::Utc::now(),
+        observed_by,
+        root_length_mm: req.root_length_mm,
+        shoot_length_mm: req.shoot_length_mm,
+        cotyledon_count: req.cotyledon_count,
+        true_leaf_count: req.true_leaf_count,
+        total_leaf_count: req.leaf_count,
+        cotyledon_color: None,
+        true_leaf_color: None,
+        health_status: req.health_status,
+        radicle_visible: req.radicle_visible,
+        shoot_visible: req.shoot_visible,
+        cotyledons_expanded: req.cotyledons_expanded,
+        true_leaves_present: req.true_leaves_present,
+        appears_autotrophic: req.appears_autotrophic,
+        temperature_celsius: req.temperature_celsius,
+        humidity_percent: req.humidity_percent,
+        issues_noted: environmental_deviations.iter().map(EnvironmentalDeviation::describe).collect(),
+        environmental_deviations,
+        actions_taken: vec![],
+        notes: req.notes,
+        photo_url: None,
+    };
+
+    let entered = record.progress_from_observation(&observation);
+    if !entered.is_empty() {
+        info!(
+            "Observation {} moved germination record {} through {:?}",
+            observation_id, req.germination_record_id, entered
+        );
+    }
+
+    let mut history = repos.observations.list_by_record(record.id).await?;
+    history.push(observation.clone());
+    for alert in record.track_environment(&history) {
+        warn!(
+            "{:?} alert on germination record {}: {:?} out of range for {} consecutive observations",
+            alert.severity, record.id, alert.condition, alert.consecutive_observations
+        );
+    }
+    if let Some(guide) = &guide {
+        forecast::reforecast(repos, &mut record, guide, &history).await?;
+    }
+
+    // Stored before the record moves on it, so the record never gets ahead of its readings
+    repos.observations.create(&observation).await?;
+    info!("Recorded observation {} for germination record {}", observation_id, req.germination_record_id);
+
+    // A stale record comes back as 409 carrying the stored copy to retry
+    // against, without the observation, so the retry doesn't store it twice
+    if let Err(err) = repos.records.update(&record).await {
+        if let Err(undo) = repos.observations.delete(observation_id).await {
+            warn!("Couldn't remove observation {} after its record failed to update: {}", observation_id, undo);
+        }
+        return Err(err.into());
+    }
+
+    Ok(observation)
+}
+
+async fn update_phase(repos: &Repositories, request: Request) -> ApiResult {
+    let req: UpdatePhaseRequest = request.json()?;
+
+    info!("Updating germination record {} to phase {:?}", req.germination_record_id, req.new_phase);
+
+    let mut record = repos.records.get(req.germination_record_id).await?;
+
+    if !record.germination_phase.can_transition_to(&req.new_phase) {
+        if !req.admin_override {
+            let message = match record.germination_phase.next_phase() {
+                Some(next) => format!(
+                    "A record in {:?} can only move to {:?} or Failed; an admin can override",
+                    record.germination_phase, next
+                ),
+                None => {
+                    format!("A record in {:?} cannot change phase; an admin can override", record.germination_phase)
+                }
+            };
+            return Err(ApiError::invalid_field("newPhase", message));
+        }
+        let principal = request.principal()?;
+        principal.require(Role::Admin)?;
+        info!(
+            "Admin {} overrode germination record {} from {:?} to {:?}",
+            principal.user_id, req.germination_record_id, record.germination_phase, req.new_phase
+        );
+    }
+
+    if let Some(expected) = req.expected_version {
+        record.version = expected;
+    }
+
+    let now = chrono::Utc::now();
+    record.enter_phase(req.new_phase.clone(), now);
+    if req.notes.is_some() {
+        record.notes = req.notes.clone();
+    }
+    let record = repos.records.update(&record).await?;
+
+    // TODO: If phase is ReadyForShipment, trigger shipment preparation
+
+    let response = PhaseResponse {
+        germination_record_id: req.germination_record_id,
+        ready_for_shipment: record.germination_phase.is_shippable(),
+        new_phase: record.germination_phase,
+        updated_at: now.to_rfc3339(),
+        notes: record.notes,
+        version: record.version,
+    };
+
+    response::ok(&response)
+}
+
+/// Check that every record exists, is the customer's, is ready to ship and
+/// isn't already in a proposed or approved package
+async fn shipment_records(
+    repos: &Repositories,
+    ids: &[Uuid],
+    customer_id: Uuid,
+) -> Result<Vec<GerminationRecord>, ApiError> {
+    if ids.is_empty() {
+        return Err(ApiError::invalid_field("germinationRecordIds", "must name at least one record"));
+    }
+
+    let held = allocation::held_records(repos).await?;
+    let mut seen = HashSet::new();
+    let mut errors = Vec::new();
+    let mut records = Vec::new();
+    for (i, &id) in ids.iter().enumerate() {
+        let field = format!("germinationRecordIds[{}]", i);
+        if !seen.insert(id) {
+            errors.push(FieldError { field, message: "is listed more than once".to_string() });
+            continue;
+        }
+        let record = match repos.records.get(id).await {
+            Ok(record) => record,
+            Err(DatabaseError::NotFound(_)) => {
+                errors.push(FieldError { field, message: format!("no germination record {}", id) });
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        if record.customer_id != customer_id {
+            errors.push(FieldError { field, message: "belongs to another customer".to_string() });
+        } else if record.germination_phase != GerminationPhase::ReadyForShipment {
+            errors.push(FieldError { field, message: "is not ready for shipment".to_string() });
+        } else if held.contains(&id) || record.package_id.is_some() {
+            errors.push(FieldError { field, message: "is already in a proposed or approved package".to_string() });
+        } else {
+            records.push(record);
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(records);
+    }
+    Err(ApiError::Validation { message: format!("Shipment is invalid ({} problem(s))", errors.len()), errors })
+}
+
+async fn prepare_shipment(repos: &Repositories, request: Request) -> ApiResult {
+    let req: PrepareShipmentRequest = request.json()?;
+    let principal = request.principal()?;
+
+    let country = req.destination.country.trim().to_uppercase();
+    if !matches!(country.as_str(), "US" | "USA") {
+        return Err(ApiError::invalid_field("destination.country", "live sprouts only ship within the US"));
+    }
+    let estimates = if req.transit_estimates.is_empty() {
+        TransitEstimate::defaults(&req.destination)
+    } else {
+        req.transit_estimates
+    };
+    if let Some(i) = estimates.iter().position(|estimate| estimate.transit_days() == 0) {
+        return Err(ApiError::invalid_field(format!("transitEstimates[{}].transitDays", i), "must be at least 1"));
+    }
+
+    let max_transit_days = if req.expedited { transit::MAX_EXPEDITED_TRANSIT_DAYS } else { transit::MAX_TRANSIT_DAYS };
+    let today = chrono::Utc::now().date_naive();
+    let Some(plan) = transit::plan(today, &estimates, max_transit_days, &req.preferred_delivery_days) else {
+        return Err(ApiError::validation(format!(
+            "No ship date in the next {} days reaches the customer on a preferred delivery day within {} transit days without a weekend or holiday layover",
+            transit::PLANNING_HORIZON_DAYS,
+            max_transit_days
+        )));
+    };
+
+    let mut records = shipment_records(repos, &req.germination_record_ids, req.customer_id).await?;
+
+    let package_id = Uuid::new_v4();
+    let now = chrono::Utc::now();
+    if let Some(lost) = allocation::claim(repos, &mut records, package_id).await? {
+        return Err(ApiError::Conflict {
+            message: format!("Germination record {} changed while the shipment was prepared", records[lost].id),
+            current: None,
+        });
+    }
+
+    let package = SproutShipmentPackage {
+        id: package_id,
+        germination_records: req.germination_record_ids.clone(),
+        customer_id: req.customer_id,
+        shipment_cycle_id: None,
+        package_type: models::PackageType::VentilatedBox,
+        container_count: 1,
+        total_sprouts: req.germination_record_ids.len() as u32,
+        moisture_retention: true,
+        temperature_control: plan.needs_temperature_control(),
+        ventilation: true,
+        expedited_shipping_required: plan.estimate.is_expedited(),
+        ship_on_days: plan.ship_on_days.clone(),
+        max_transit_days,
+        care_instructions_included: true,
+        transplant_instructions_included: true,
+        species_info_card_included: true,
+        packed_at: None,
+        shipped_at: None,
+        expected_delivery: plan.delivery_date.and_hms_opt(0, 0, 0).map(|at| at.and_utc()),
+        // Staff prepared it, so there is nothing to review
+        status: PackageStatus::Approved,
+        allocation_reasons: Vec::new(),
+        created_at: now,
+        reviewed_by: Some(principal.user_id.to_string()),
+        reviewed_at: Some(now),
+        version: 0,
+    };
+    repos.packages.create(&package).await?;
+
+    info!(
+        "Prepared shipment package {} with {} sprouts for customer {}: ships {} by {} {}, arrives {}",
+        package_id,
+        req.germination_record_ids.len(),
+        req.customer_id,
+        plan.ship_date,
+        plan.estimate.carrier(),
+        plan.estimate.service(),
+        plan.delivery_date
+    );
+
+    // TODO: Create shipment in shipping service
+    // TODO: Generate packing list and care instructions
+
+    let response = ShipmentPackageResponse {
+        package_id: package.id,
+        sprout_count: package.total_sprouts,
+        expedited: package.expedited_shipping_required,
+        estimated_ship_date: Some(plan.ship_date.to_string()),
+        expected_delivery: package.expected_delivery,
+        carrier: plan.estimate.carrier().to_string(),
+        service: plan.estimate.service().to_string(),
+        transit_days: plan.estimate.transit_days(),
+        temperature_control: package.temperature_control,
+        ship_on_days: package.ship_on_days,
+        care_instructions_url: format!("/germination/care-instructions/{}", package.id),
+    };
+
+    response::created(&response)
+}
+
+async fn list_ready_for_shipment(repos: &Repositories, request: Request) -> ApiResult {
+    info!("Listing all sprouts ready for shipment");
+
+    let page = request.page()?;
+
+    let records = repos.records.page_by_phase(&GerminationPhase::ReadyForShipment, &page).await?;
+
+    // TODO: Group by customer
+
+    // Records moved to the phase that no longer meet the guide's criteria are held back
+    let mut ready = Vec::new();
+    let mut held = Vec::new();
+    for record in &records.items {
+        match readiness(repos, record).await? {
+            Some(response) if !response.readiness.ready => held.push(response),
+            _ => ready.push(GerminationResponse::from(record)),
+        }
+    }
+
+    let response = serde_json::json!({
+        "ready_count": ready.len(),
+        "records": ready,
+        "held": held,
+        "next_cursor": records.next_cursor,
+    });
+
+    response::ok(&response)
+}
+
+/// Shipments per day ahead, for planning packaging capacity
+async fn get_ship_forecast(repos: &Repositories, request: Request) -> ApiResult {
+    let days = match request.query("days") {
+        Some(days) => days
+            .parse::<u32>()
+            .ok()
+            .filter(|days| (1..=90).contains(days))
+            .ok_or_else(|| ApiError::invalid_field("days", format!("Invalid days: {} (1-90)", days)))?,
+        None => 14,
+    };
+
+    let forecast = forecast::ship_forecast(repos, chrono::Utc::now().date_naive(), days).await?;
+
+    response::ok(&forecast)
+}
+
+/// Dry run: which records have stalled, and stall rates by species
+async fn get_stall_report(repos: &Repositories, _: Request) -> ApiResult {
+    let report = evaluate_stalls(repos, chrono::Utc::now(), false).await?;
+
+    response::ok(&report)
+}
+
+/// What the daily schedule does, on demand: queue a review for each new stall
+async fn evaluate_stalls_now(repos: &Repositories, _: Request) -> ApiResult {
+    let report = evaluate_stalls(repos, chrono::Utc::now(), true).await?;
+
+    response::ok(&report)
+}
+
+async fn get_germination_record(repos: &Repositories, request: Request) -> ApiResult {
+    let id = request.uuid_param("id")?;
+
+    info!("Fetching germination record {}", id);
+
+    let record = repos.records.get(id).await?;
+
+    // Customers see only their own sprouts
+    let principal = request.principal()?;
+    if record.customer_id != principal.user_id && !principal.has_role(Role::Staff) {
+        return Err(ApiError::forbidden("Germination record belongs to another customer"));
+    }
+
+    response::ok(&record)
+}
+
+async fn get_readiness(repos: &Repositories, request: Request) -> ApiResult {
+    let id = request.uuid_param("id")?;
+
+    let record = repos.records.get(id).await?;
+
+    let principal = request.principal()?;
+    if record.customer_id != principal.user_id && !principal.has_role(Role::Staff) {
+        return Err(ApiError::forbidden("Germination record belongs to another customer"));
+    }
+
+    match readiness(repos, &record).await? {
+        Some(response) => response::ok(&response),
+        None => Err(ApiError::not_found(format!("No germination guide for {}", record.species))),
+    }
+}
+
+/// The record against its guide's shipment criteria; `None` without a guide
+async fn readiness(repos: &Repositories, record: &GerminationRecord) -> Result<Option<ReadinessResponse>, ApiError> {
+    let Some(guide) = record_guide(repos, record).await? else {
+        return Ok(None);
+    };
+    let observations = repos.observations.list_by_record(record.id).await?;
+
+    Ok(Some(ReadinessResponse {
+        germination_record_id: record.id,
+        species: record.species.clone(),
+        phase: record.germination_phase.clone(),
+        readiness: record.readiness(&guide, &observations, chrono::Utc::now()),
+    }))
+}
+
+async fn get_environmental_history(repos: &Repositories, request: Request) -> ApiResult {
+    let id = request.uuid_param("id")?;
+
+    let record = repos.records.get(id).await?;
+
+    let principal = request.principal()?;
+    if record.customer_id != principal.user_id && !principal.has_role(Role::Staff) {
+        return Err(ApiError::forbidden("Germination record belongs to another customer"));
+    }
+
+    response::ok(&environment::environmental_history(repos, record).await?)
+}
+
+async fn get_germination_guide(repos: &Repositories, request: Request) -> ApiResult {
+    let species = request.param("species")?;
+
+    info!("Fetching germination guide for {}", species);
+
+    let guide = find_guide(&repos.guides, species).await?;
+    let guide = catalog::resolve(&guide, request.query("variety"))?;
+
+    response::ok(&guide)
+}
+
+/// Find a guide by any name the species registry resolves
+async fn find_guide(guides: &GerminationGuidesRepository, name: &str) -> Result<GerminationGuide, ApiError> {
+    let species = catalog::species_key(name);
+    match guides.get(&species).await {
+        Err(DatabaseError::NotFound(_)) => Err(ApiError::not_found(format!("No germination guide for {}", species))),
+        guide => Ok(guide?),
+    }
+}
+
+/// The guide for the record's species and variety, or `None` when the
+/// species' guide has since been removed from the catalog
+async fn record_guide(repos: &Repositories, record: &GerminationRecord) -> Result<Option<GerminationGuide>, ApiError> {
+    match find_guide(&repos.guides, &record.species).await {
+        Ok(guide) => Ok(Some(catalog::resolve(&guide, record.variety.as_deref())?)),
+        Err(ApiError::NotFound(_)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+async fn list_guides(repos: &Repositories, _: Request) -> ApiResult {
+    let mut guides = repos.guides.list_all().await?;
+    guides.sort_by(|a, b| a.species.cmp(&b.species));
+
+    response::ok(&serde_json::json!({ "guides": guides }))
+}
+
+async fn create_guide(repos: &Repositories, request: Request) -> ApiResult {
+    let mut guide: GerminationGuide = request.json()?;
+    catalog::normalize(&mut guide);
+    catalog::validate(&guide)?;
+
+    guide.version = 0;
+    repos.guides.create(&guide).await?;
+
+    info!("Created germination guide for {}", guide.species);
+
+    response::created(&guide)
+}
+
+/// The body's `version` is the version the admin edited; a stale one is a conflict
+async fn update_guide(repos: &Repositories, request: Request) -> ApiResult {
+    let species = catalog::key(request.param("species")?);
+    let mut guide: GerminationGuide = request.json()?;
+    catalog::normalize(&mut guide);
+    if guide.species != species {
+        return Err(ApiError::invalid_field("species", format!("must match the path ({})", species)));
+    }
+    catalog::validate(&guide)?;
+
+    let updated = repos.guides.update(&guide).await?;
+
+    info!("Updated germination guide for {} to version {}", species, updated.version);
+
+    response::ok(&updated)
+}
+
+async fn delete_guide(repos: &Repositories, request: Request) -> ApiResult {
+    let species = catalog::key(request.param("species")?);
+
+    repos.guides.get(&species).await?;
+    repos.guides.delete(&species).await?;
+
+    info!("Deleted germination guide for {}", species);
+
+    response::ok(&serde_json::json!({ "status": "deleted", "species": species }))
+}
+
+/// Body is a JSON catalog, a TOML one when sent as `application/toml`, or
+/// empty for the catalog bundled with the service
+async fn import_guides(repos: &Repositories, request: Request) -> ApiResult {
+    let body = request.inner().body.as_deref().unwrap_or_default().trim();
+    let is_toml =
+        request.header("content-type").is_some_and(|content_type| content_type.starts_with("application/toml"));
+
+    let catalog = if body.is_empty() {
+        Catalog::bundled()
+    } else if is_toml {
+        Catalog::from_toml(body)?
+    } else {
+        Catalog::from_json(body)?
+    };
+
+    let report = import_catalog(repos, catalog).await?;
+
+    response::ok(&report)
+}
+
+/// Validate a catalog and store the guides the table lacks. Guides already
+/// present are skipped, so an import never undoes an admin's edits
+pub async fn import_catalog(repos: &Repositories, mut catalog: Catalog) -> Result<ImportReport, ApiError> {
+    catalog.validate()?;
+
+    let mut report = ImportReport { created: Vec::new(), skipped: Vec::new() };
+    for mut guide in catalog.guides {
+        guide.version = 0;
+        match repos.guides.create(&guide).await {
+            Ok(()) => report.created.push(guide.species),
+            Err(DatabaseError::AlreadyExists(_)) => report.skipped.push(guide.species),
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    info!("Imported germination guides: {} created, {} skipped", report.created.len(), report.skipped.len());
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_lambda_events::encodings::Body;
+    use lambda_runtime::Context;
+
+    /// Empty tables apart from the bundled guide catalog
+    async fn repos() -> Repositories {
+        let repos = Repositories::in_memory();
+        import_catalog(&repos, Catalog::bundled()).await.unwrap();
+        repos
+    }
+
+    /// A request from a staff member, as API Gateway's authorizer would pass it
+    fn event(method: &str, path: &str, body: Option<serde_json::Value>) -> LambdaEvent<ApiGatewayProxyRequest> {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let mut request = ApiGatewayProxyRequest {
+            http_method: method.parse().unwrap(),
+            path: Some(path.to_string()),
+            query_string_parameters: query.parse().unwrap_or_default(),
+            body: body.map(|b| b.to_string()),
+            ..Default::default()
+        };
+        request.request_context.authorizer.fields.insert(
+            "claims".to_string(),
+            serde_json::json!({ "sub": Uuid::new_v4().to_string(), "cognito:groups": "staff" }),
+        );
+        LambdaEvent::new(request, Context::default())
+    }
+
+    fn as_admin(mut event: LambdaEvent<ApiGatewayProxyRequest>) -> LambdaEvent<ApiGatewayProxyRequest> {
+        event.payload.request_context.authorizer.fields.insert(
+            "claims".to_string(),
+            serde_json::json!({ "sub": Uuid::new_v4().to_string(), "cognito:groups": "admin" }),
+        );
+        event
+    }
+
+    fn body(response: &ApiGatewayProxyResponse) -> serde_json::Value {
+        match &response.body {
+            Some(Body::Text(text)) => serde_json::from_str(text).unwrap(),
+            other => panic!("unexpected body {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_persists_through_observation_and_phase_change() {
+        let repos = repos().await;
+
+        let started = function_handler(
+            &repos,
+            event(
+                "POST",
+                "/germination/start",
+                Some(serde_json::json!({
+                    "seedId": Uuid::new_v4(),
+                    "customerId": Uuid::new_v4(),
+                    "species": "Tomato",
+                    "variety": null,
+                    "growingMedium": "soil",
+                    "shipmentType": "LIVE_SPROUT"
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(started.status_code, 201);
+        let record_id = body(&started)["germinationRecordId"].as_str().unwrap().to_string();
+
+        let observed = function_handler(
+            &repos,
+            event(
+                "POST",
+                "/germination/observe",
+                Some(serde_json::json!({
+                    "germinationRecordId": record_id,
+                    "observedBy": "grower",
+                    "rootLengthMm": 25.0,
+                    "shootLengthMm": 35.0,
+                    "leafCount": 4,
+                    "trueLeafCount": 2,
+                    "healthStatus": "GOOD",
+                    "appearsAutotrophic": true,
+                    "temperatureCelsius": 22.0,
+                    "humidityPercent": 70.0,
+                    "notes": null
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(observed.status_code, 201);
+
+        for phase in ["CONTINUED_GROWTH", "READY_FOR_SHIPMENT"] {
+            let updated = function_handler(&repos, phase_update(&record_id, phase, false)).await.unwrap();
+            assert_eq!(updated.status_code, 200);
+        }
+
+        let ready = body(&function_handler(&repos, event("GET", "/germination/ready", None)).await.unwrap());
+        assert_eq!(ready["ready_count"], 1);
+
+        let fetched =
+            function_handler(&repos, event("GET", &format!("/germination/{}", record_id), None)).await.unwrap();
+        let fetched = body(&fetched);
+        assert_eq!(fetched["root_length_mm"], 25.0);
+        assert_eq!(fetched["health_status"], "GOOD");
+        assert_eq!(fetched["germination_success"], true);
+        assert_eq!(fetched["is_true_plant"], true);
+        assert_eq!(fetched["is_autotrophic"], true);
+        assert!(fetched["radicle_emerged_at"].is_string());
+        assert_eq!(fetched["radicle_emerged_at"], fetched["photosynthesis_started_at"]);
+    }
+
+    fn phase_update(record_id: &str, phase: &str, admin_override: bool) -> LambdaEvent<ApiGatewayProxyRequest> {
+        event(
+            "PUT",
+            "/germination/phase",
+            Some(serde_json::json!({
+                "germinationRecordId": record_id,
+                "newPhase": phase,
+                "notes": null,
+                "adminOverride": admin_override
+            })),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_illegal_phase_jump_needs_admin_override() {
+        let repos = repos().await;
+        let started = function_handler(
+            &repos,
+            event(
+                "POST",
+                "/germination/start",
+                Some(serde_json::json!({
+                    "seedId": Uuid::new_v4(),
+                    "customerId": Uuid::new_v4(),
+                    "species": "Basil",
+                    "variety": null,
+                    "growingMedium": "coco",
+                    "shipmentType": "LIVE_SPROUT"
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        let record_id = body(&started)["germinationRecordId"].as_str().unwrap().to_string();
+
+        let skipped = function_handler(&repos, phase_update(&record_id, "READY_FOR_SHIPMENT", false)).await.unwrap();
+        assert_eq!(skipped.status_code, 400);
+        assert_eq!(body(&skipped)["errors"][0]["field"], "newPhase");
+
+        let staff = function_handler(&repos, phase_update(&record_id, "READY_FOR_SHIPMENT", true)).await.unwrap();
+        assert_eq!(staff.status_code, 403);
+
+        let admin =
+            function_handler(&repos, as_admin(phase_update(&record_id, "READY_FOR_SHIPMENT", true))).await.unwrap();
+        assert_eq!(admin.status_code, 200);
+        assert_eq!(body(&admin)["new_phase"], "READY_FOR_SHIPMENT");
+    }
+
+    #[tokio::test]
+    async fn test_stale_phase_update_is_conflict() {
+        let repos = repos().await;
+        let started = function_handler(
+            &repos,
+            event(
+                "POST",
+                "/germination/start",
+                Some(serde_json::json!({
+                    "seedId": Uuid::new_v4(),
+                    "customerId": Uuid::new_v4(),
+                    "species": "Basil",
+                    "variety": null,
+                    "growingMedium": "coco",
+                    "shipmentType": "LIVE_SPROUT"
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        let record_id = body(&started)["germinationRecordId"].as_str().unwrap().to_string();
+
+        let update = |phase: &str| {
+            event(
+                "PUT",
+                "/germination/phase",
+                Some(serde_json::json!({
+                    "germinationRecordId": record_id,
+                    "newPhase": phase,
+                    "notes": null,
+                    "expectedVersion": 0
+                })),
+            )
+        };
+
+        let first = function_handler(&repos, update("RADICLE_EMERGENCE")).await.unwrap();
+        assert_eq!(first.status_code, 200);
+        assert_eq!(body(&first)["version"], 1);
+
+        let stale = function_handler(&repos, update("FAILED")).await.unwrap();
+        assert_eq!(stale.status_code, 409);
+        let current = &body(&stale)["current"];
+        assert_eq!(current["germination_phase"], "RADICLE_EMERGENCE");
+        assert_eq!(current["version"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_record_is_queued_for_review_once() {
+        let repos = repos().await;
+        let started = function_handler(
+            &repos,
+            event(
+                "POST",
+                "/germination/start",
+                Some(serde_json::json!({
+                    "seedId": Uuid::new_v4(),
+                    "customerId": Uuid::new_v4(),
+                    "species": "tomato",
+                    "variety": null,
+                    "growingMedium": "soil",
+                    "shipmentType": "LIVE_SPROUT"
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        let record_id: Uuid = body(&started)["germinationRecordId"].as_str().unwrap().parse().unwrap();
+
+        // The tomato guide expects a radicle by day 7
+        let mut record = repos.records.get(record_id).await.unwrap();
+        record.started_at -= chrono::Duration::days(8);
+        repos.records.update(&record).await.unwrap();
+
+        let dry_run = body(&function_handler(&repos, event("GET", "/germination/stalls", None)).await.unwrap());
+        assert_eq!(dry_run["stalls"][0]["phase"], "IMBIBITION");
+        assert_eq!(dry_run["stalls"][0]["suggestedPhase"], "FAILED");
+        assert_eq!(dry_run["reviewsOpened"], 0);
+        assert!(repos.queue.list_all().await.unwrap().is_empty());
+
+        let evaluated =
+            body(&function_handler(&repos, event("POST", "/germination/stalls/evaluate", None)).await.unwrap());
+        assert_eq!(evaluated["reviewsOpened"], 1);
+        assert_eq!(evaluated["species"][0]["species"], "tomato");
+        assert_eq!(evaluated["species"][0]["stallRate"], 1.0);
+        let queued = repos.queue.list_all().await.unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(evaluated["stalls"][0]["reviewQueueId"], queued[0].id.to_string());
+        assert!(matches!(
+            queued[0].queue_type,
+            models::QueueType::GerminationReview { germination_record_id, .. } if germination_record_id == record_id
+        ));
+
+        let again = body(&function_handler(&repos, event("POST", "/germination/stalls/evaluate", None)).await.unwrap());
+        assert_eq!(again["stalls"].as_array().unwrap().len(), 1);
+        assert_eq!(again["reviewsOpened"], 0);
+        assert_eq!(repos.queue.list_all().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_conditions_out_of_range_raise_graded_alerts() {
+        let repos = repos().await;
+        let started = function_handler(
+            &repos,
+            event(
+                "POST",
+                "/germination/start",
+                Some(serde_json::json!({
+                    "seedId": Uuid::new_v4(),
+                    "customerId": Uuid::new_v4(),
+                    "species": "tomato",
+                    "variety": null,
+                    "growingMedium": "soil",
+                    "shipmentType": "LIVE_SPROUT"
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        let record_id = body(&started)["germinationRecordId"].as_str().unwrap().to_string();
+        let observe = |temperature: Option<f32>| {
+            event(
+                "POST",
+                "/germination/observe",
+                Some(serde_json::json!({
+                    "germinationRecordId": record_id,
+                    "rootLengthMm": null,
+                    "shootLengthMm": null,
+                    "leafCount": null,
+                    "healthStatus": "GOOD",
+                    "temperatureCelsius": temperature,
+                    "humidityPercent": null,
+                    "notes": null
+                })),
+            )
+        };
+        let alerts = |record: serde_json::Value| record["environmental_alerts"].as_array().unwrap().clone();
+        let record = || async {
+            body(&function_handler(&repos, event("GET", &format!("/germination/{}", record_id), None)).await.unwrap())
+        };
+
+        // Tomatoes want 18-29°C; one hot reading is only noted
+        let first = body(&function_handler(&repos, observe(Some(33.0))).await.unwrap());
+        assert_eq!(first["issues_noted"][0], "Temperature 33°C is above the guide's 18-29°C range");
+        assert!(alerts(record().await).is_empty());
+
+        function_handler(&repos, observe(Some(34.0))).await.unwrap();
+        let raised = alerts(record().await);
+        assert_eq!(raised[0]["severity"], "warning");
+        assert_eq!(raised[0]["consecutive_observations"], 2);
+
+        // A reading without a temperature neither extends nor breaks the run
+        function_handler(&repos, observe(None)).await.unwrap();
+        function_handler(&repos, observe(Some(35.0))).await.unwrap();
+        function_handler(&repos, observe(Some(35.0))).await.unwrap();
+        let escalated = alerts(record().await);
+        assert_eq!(escalated.len(), 1);
+        assert_eq!(escalated[0]["severity"], "critical");
+        assert_eq!(escalated[0]["consecutive_observations"], 4);
+
+        function_handler(&repos, observe(Some(24.0))).await.unwrap();
+        let history = body(
+            &function_handler(&repos, event("GET", &format!("/germination/{}/environment", record_id), None))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(history["readings"].as_array().unwrap().len(), 6);
+        assert_eq!(history["readings"][0]["deviations"][0]["condition"], "TEMPERATURE");
+        assert!(history["readings"][5]["deviations"].as_array().unwrap().is_empty());
+        assert_eq!(history["optimalTemperatureCelsius"]["max"], 29.0);
+        assert!(history["alerts"][0]["resolved_at"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_readiness_explains_each_criterion_and_holds_back_unready_records() {
+        let repos = repos().await;
+        let started = function_handler(
+            &repos,
+            event(
+                "POST",
+                "/germination/start",
+                Some(serde_json::json!({
+                    "seedId": Uuid::new_v4(),
+                    "customerId": Uuid::new_v4(),
+                    "species": "tomato",
+                    "variety": null,
+                    "growingMedium": "soil",
+                    "shipmentType": "LIVE_SPROUT"
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        let record_id = body(&started)["germinationRecordId"].as_str().unwrap().to_string();
+        for root_length in [5.0, 10.0] {
+            let observed = function_handler(
+                &repos,
+                event(
+                    "POST",
+                    "/germination/observe",
+                    Some(serde_json::json!({
+                        "germinationRecordId": record_id,
+                        "rootLengthMm": root_length,
+                        "shootLengthMm": null,
+                        "leafCount": null,
+                        "healthStatus": "GOOD",
+                        "temperatureCelsius": null,
+                        "humidityPercent": null,
+                        "notes": null,
+                        "radicleVisible": true
+                    })),
+                ),
+            )
+            .await
+            .unwrap();
+            assert_eq!(observed.status_code, 201);
+        }
+
+        let response = function_handler(&repos, event("GET", &format!("/germination/{}/readiness", record_id), None))
+            .await
+            .unwrap();
+        assert_eq!(response.status_code, 200);
+        let readiness = &body(&response)["readiness"];
+        assert_eq!(readiness["ready"], false);
+        let criterion = |name: &str| {
+            readiness["criteria"].as_array().unwrap().iter().find(|c| c["criterion"] == name).unwrap().clone()
+        };
+        // Tomatoes ship with 20 mm roots; the root is growing, so it projects
+        let root = criterion("ROOT_LENGTH");
+        assert_eq!((root["actual"].as_f64(), root["required"].as_f64()), (Some(10.0), Some(20.0)));
+        assert!(root["projected_pass_at"].is_string());
+        // The shoot hasn't been measured: failing, with nothing to project from
+        let shoot = criterion("SHOOT_LENGTH");
+        assert!(shoot["actual"].is_null() && shoot["projected_pass_at"].is_null());
+        assert_eq!(criterion("HEALTH")["passed"], true);
+        assert!(readiness["projected_ready_at"].is_null());
+
+        let moved =
+            function_handler(&repos, as_admin(phase_update(&record_id, "READY_FOR_SHIPMENT", true))).await.unwrap();
+        assert_eq!(moved.status_code, 200);
+        let listed = body(&function_handler(&repos, event("GET", "/germination/ready", None)).await.unwrap());
+        assert_eq!(listed["ready_count"], 0);
+        assert_eq!(listed["held"][0]["germinationRecordId"], record_id);
+    }
+
+    #[tokio::test]
+    async fn test_observations_reforecast_the_ship_date() {
+        let repos = repos().await;
+        let started = body(
+            &function_handler(
+                &repos,
+                event(
+                    "POST",
+                    "/germination/start",
+                    Some(serde_json::json!({
+                        "seedId": Uuid::new_v4(),
+                        "customerId": Uuid::new_v4(),
+                        "species": "tomato",
+                        "variety": null,
+                        "growingMedium": "soil",
+                        "shipmentType": "LIVE_SPROUT"
+                    })),
+                ),
+            )
+            .await
+            .unwrap(),
+        );
+        let record_id = started["germinationRecordId"].as_str().unwrap().to_string();
+        let date =
+            |value: &serde_json::Value| value.as_str().unwrap().parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+
+        // No history yet: the guide's 14 days, give or take
+        let initial = date(&started["estimatedShipDate"]);
+        let days_out = (initial - chrono::Utc::now()).num_days();
+        assert!((13..=14).contains(&days_out), "{} days out", days_out);
+        assert!(date(&started["earliestShipDate"]) < initial && initial < date(&started["latestShipDate"]));
+
+        // Roots racing towards 20 mm pull the forecast in
+        for root_length in [8.0, 16.0] {
+            function_handler(
+                &repos,
+                event(
+                    "POST",
+                    "/germination/observe",
+                    Some(serde_json::json!({
+                        "germinationRecordId": record_id,
+                        "rootLengthMm": root_length,
+                        "shootLengthMm": null,
+                        "leafCount": null,
+                        "healthStatus": "GOOD",
+                        "temperatureCelsius": null,
+                        "humidityPercent": null,
+                        "notes": null,
+                        "radicleVisible": true
+                    })),
+                ),
+            )
+            .await
+            .unwrap();
+        }
+        let record =
+            body(&function_handler(&repos, event("GET", &format!("/germination/{}", record_id), None)).await.unwrap());
+        assert!(date(&record["estimated_ship_date"]) < initial);
+        assert_eq!(record["ship_date_forecast"]["observations"], 2);
+        assert_eq!(record["ship_date_forecast"]["prior_records"], 0);
+
+        let calendar =
+            body(&function_handler(&repos, event("GET", "/germination/forecast?days=3", None)).await.unwrap());
+        let days = calendar["days"].as_array().unwrap();
+        assert_eq!(days.len(), 3);
+        assert_eq!(days.iter().map(|day| day["expected"].as_u64().unwrap()).sum::<u64>(), 1);
+
+        let invalid = function_handler(&repos, event("GET", "/germination/forecast?days=0", None)).await.unwrap();
+        assert_eq!(invalid.status_code, 400);
+    }
+
+    #[tokio::test]
+    async fn test_tray_starts_records_and_reports_germination_rates() {
+        let repos = repos().await;
+        let cell = |row: u32, column: u32| serde_json::json!({ "row": row, "column": column, "seedId": Uuid::new_v4(), "customerId": Uuid::new_v4() });
+        let tray = |cells: Vec<serde_json::Value>| {
+            event(
+                "POST",
+                "/germination/trays",
+                Some(serde_json::json!({
+                    "label": "Bench 3 / A",
+                    "rows": 2,
+                    "columns": 3,
+                    "species": "basil",
+                    "variety": null,
+                    "growingMedium": "coco",
+                    "shipmentType": "LIVE_SPROUT",
+                    "temperatureCelsius": 22.0,
+                    "humidityPercent": 65.0,
+                    "lightHoursPerDay": 14.0,
+                    "seedLot": "LOT-A",
+                    "cells": cells
+                })),
+            )
+        };
+
+        let mut dragonfruit = cell(0, 1);
+        dragonfruit["species"] = "dragonfruit".into();
+        let invalid =
+            function_handler(&repos, tray(vec![cell(0, 0), dragonfruit, cell(2, 0), cell(0, 0)])).await.unwrap();
+        assert_eq!(invalid.status_code, 400);
+        let fields: Vec<_> = body(&invalid)["errors"].as_array().unwrap().iter().map(|e| e["field"].clone()).collect();
+        assert_eq!(fields, ["cells[2]", "cells[3]", "cells[1].species"]);
+        assert!(repos.records.list_all().await.unwrap().is_empty());
+
+        let mut other_lot = cell(1, 0);
+        other_lot["seedLot"] = "LOT-B".into();
+        let started = function_handler(&repos, tray(vec![cell(0, 0), cell(0, 1), other_lot])).await.unwrap();
+        assert_eq!(started.status_code, 201);
+        let started = body(&started);
+        let tray_id = started["trayId"].as_str().unwrap().to_string();
+        assert_eq!(started["rate"]["sown"], 3);
+        let record = repos
+            .records
+            .get(started["cells"][0]["germinationRecordId"].as_str().unwrap().parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(record.tray_id.unwrap().to_string(), tray_id);
+        assert_eq!(record.light_hours_per_day, Some(14.0));
+
+        let observed = function_handler(
+            &repos,
+            event(
+                "POST",
+                &format!("/germination/trays/{}/observe", tray_id),
+                Some(serde_json::json!({
+                    "temperatureCelsius": 22.0,
+                    "humidityPercent": 65.0,
+                    "cells": [
+                        { "row": 0, "column": 0, "healthStatus": "GOOD", "radicleVisible": true, "rootLengthMm": 3.0 },
+                        { "row": 0, "column": 1, "healthStatus": "DEAD" }
+                    ]
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(observed.status_code, 201);
+        assert_eq!(body(&observed)["observations"].as_array().unwrap().len(), 2);
+
+        let fetched = body(
+            &function_handler(&repos, event("GET", &format!("/germination/trays/{}", tray_id), None)).await.unwrap(),
+        );
+        assert_eq!(fetched["cells"][0]["phase"], "RADICLE_EMERGENCE");
+        assert_eq!(fetched["cells"][1]["phase"], "FAILED");
+        let rate = &fetched["rate"];
+        assert_eq!(
+            (rate["germinated"].as_u64(), rate["failed"].as_u64(), rate["pending"].as_u64()),
+            (Some(1), Some(1), Some(1))
+        );
+
+        let report = body(&function_handler(&repos, event("GET", "/germination/trays/report", None)).await.unwrap());
+        assert_eq!(report["trays"][0]["trayId"], tray_id);
+        assert_eq!(report["seedLots"][0]["seedLot"], "LOT-A");
+        assert_eq!(report["seedLots"][0]["rate"]["germinationRate"], 0.5);
+        assert_eq!(report["seedLots"][1]["rate"]["pending"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_allocation_follows_preferences_and_tier() {
+        let repos = repos().await;
+        let ready = |species: &'static str| {
+            let repos = &repos;
+            async move {
+                let started = function_handler(
+                    repos,
+                    event(
+                        "POST",
+                        "/germination/start",
+                        Some(serde_json::json!({
+                            "seedId": Uuid::new_v4(),
+                            "customerId": Uuid::new_v4(),
+                            "species": species,
+                            "variety": null,
+                            "growingMedium": "soil",
+                            "shipmentType": "LIVE_SPROUT"
+                        })),
+                    ),
+                )
+                .await
+                .unwrap();
+                let record_id = body(&started)["germinationRecordId"].as_str().unwrap().to_string();
+                let observed = function_handler(
+                    repos,
+                    event(
+                        "POST",
+                        "/germination/observe",
+                        Some(serde_json::json!({
+                            "germinationRecordId": record_id,
+                            "rootLengthMm": 30.0,
+                            "shootLengthMm": 45.0,
+                            "leafCount": 5,
+                            "trueLeafCount": 3,
+                            "healthStatus": "GOOD",
+                            "appearsAutotrophic": true,
+                            "temperatureCelsius": null,
+                            "humidityPercent": null,
+                            "notes": null
+                        })),
+                    ),
+                )
+                .await
+                .unwrap();
+                assert_eq!(observed.status_code, 201);
+                for phase in ["CONTINUED_GROWTH", "READY_FOR_SHIPMENT"] {
+                    function_handler(repos, phase_update(&record_id, phase, false)).await.unwrap();
+                }
+                record_id
+            }
+        };
+        let tomatoes = [ready("tomato").await, ready("tomato").await];
+        let cannabis = ready("cannabis").await;
+
+        let customer = |tier: models::SubscriptionTier, experience: &str, excluded: &str| {
+            let repos = &repos;
+            let (experience, excluded) = (experience.to_string(), excluded.to_string());
+            async move {
+                let customer_id = Uuid::new_v4();
+                let now = chrono::Utc::now();
+                repos
+                    .subscriptions
+                    .create(&models::Subscription {
+                        id: Uuid::new_v4(),
+                        customer_id,
+                        tier,
+                        status: models::SubscriptionStatus::Active,
+                        cratejoy_subscription_id: None,
+                        created_at: now,
+                        updated_at: now,
+                        next_billing_date: now,
+                        bags_required: false,
+                    })
+                    .await
+                    .unwrap();
+                let saved = function_handler(
+                    repos,
+                    event(
+                        "PUT",
+                        &format!("/germination/preferences/{}", customer_id),
+                        Some(serde_json::json!({
+                            "excludedSpecies": [excluded],
+                            "preferredShipmentType": "LIVE_SPROUT",
+                            "deliveryFrequency": "weekly",
+                            "sproutsPerShipmentMin": 1,
+                            "sproutsPerShipmentMax": 5,
+                            "experienceLevel": experience
+                        })),
+                    ),
+                )
+                .await
+                .unwrap();
+                assert_eq!(saved.status_code, 200);
+                customer_id
+            }
+        };
+        // The expert's standard tier comes second, and they want no tomatoes
+        let expert = customer(models::SubscriptionTier::Standard, "expert", "Tomatoes").await;
+        let beginner = customer(models::SubscriptionTier::Premium, "beginner", "basil").await;
+        let saved = function_handler(&repos, event("GET", &format!("/germination/preferences/{}", expert), None))
+            .await
+            .unwrap();
+        assert_eq!(body(&saved)["excluded_species"], serde_json::json!(["tomato"]));
+
+        let allocate = || function_handler(&repos, event("POST", "/germination/allocations", None));
+        let report = body(&allocate().await.unwrap());
+        let package_for = |customer: Uuid| {
+            report["proposals"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|p| p["customer_id"] == customer.to_string())
+                .unwrap()
+                .clone()
+        };
+        // Cannabis is too hard for the beginner, who gets first pick of the rest
+        let beginners = package_for(beginner);
+        assert_eq!(beginners["status"], "proposed");
+        assert_eq!(beginners["germination_records"], serde_json::json!(tomatoes));
+        assert_eq!(package_for(expert)["germination_records"], serde_json::json!([cannabis]));
+        assert!(report["unallocated"].as_array().unwrap().is_empty());
+
+        // Proposals hold their sprouts and their customers until reviewed
+        let again = body(&allocate().await.unwrap());
+        assert!(again["proposals"].as_array().unwrap().is_empty());
+        assert_eq!(again["skipped"].as_array().unwrap().len(), 2);
+
+        let approve = || {
+            let path = format!("/germination/packages/{}/approve", beginners["id"].as_str().unwrap());
+            function_handler(&repos, event("PUT", &path, None))
+        };
+        let approved = approve().await.unwrap();
+        assert_eq!(approved.status_code, 200);
+        assert_eq!(body(&approved)["status"], "approved");
+        assert_eq!(approve().await.unwrap().status_code, 409);
+
+        // Weekly customers aren't due again for a week
+        let after = body(&allocate().await.unwrap());
+        let reasons: Vec<&str> =
+            after["skipped"].as_array().unwrap().iter().map(|s| s["reason"].as_str().unwrap()).collect();
+        assert!(reasons.iter().any(|reason| reason.starts_with("Not due until")));
+    }
+
+    #[tokio::test]
+    async fn test_shipment_is_planned_for_the_destination() {
+        use chrono::Datelike;
+
+        let repos = repos().await;
+        let customer_id = Uuid::new_v4();
+        let record_id = ready_for_shipment(&repos, customer_id).await;
+        let shipment = |country: &str, state: &str| {
+            event(
+                "POST",
+                "/germination/shipment",
+                Some(serde_json::json!({
+                    "germinationRecordIds": [record_id],
+                    "customerId": customer_id,
+                    "expedited": false,
+                    "destination": {
+                        "street1": "1 Main St",
+                        "street2": null,
+                        "city": "Hilo",
+                        "state": state,
+                        "zip": "96720",
+                        "country": country
+                    },
+                    "preferredDeliveryDays": ["WEDNESDAY", "THURSDAY"]
+                })),
+            )
+        };
+
+        // Standard Priority Mail to Hawaii takes too long for a live sprout
+        let response = function_handler(&repos, shipment("US", "HI")).await.unwrap();
+        assert_eq!(response.status_code, 201);
+        let package = body(&response);
+        assert_eq!(package["expedited"], true);
+        assert_eq!(package["service"], "usps_priority_mail_express");
+        assert_eq!(package["temperatureControl"], true);
+        assert_eq!(package["shipOnDays"], serde_json::json!(["MONDAY", "TUESDAY"]));
+        let delivery = chrono::DateTime::parse_from_rfc3339(package["expectedDelivery"].as_str().unwrap()).unwrap();
+        assert!(matches!(delivery.weekday(), chrono::Weekday::Wed | chrono::Weekday::Thu));
+
+        let response = function_handler(&repos, shipment("CA", "BC")).await.unwrap();
+        assert_eq!(response.status_code, 400);
+        assert_eq!(body(&response)["errors"][0]["field"], "destination.country");
+    }
+
+    async fn ready_for_shipment(repos: &Repositories, customer_id: Uuid) -> Uuid {
+        let started = function_handler(
+            repos,
+            event(
+                "POST",
+                "/germination/start",
+                Some(serde_json::json!({
+                    "seedId": Uuid::new_v4(),
+                    "customerId": customer_id,
+                    "species": "basil",
+                    "variety": null,
+                    "growingMedium": "soil",
+                    "shipmentType": "LIVE_SPROUT"
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        let record_id: Uuid = body(&started)["germinationRecordId"].as_str().unwrap().parse().unwrap();
+
+        let mut record = repos.records.get(record_id).await.unwrap();
+        record.germination_phase = GerminationPhase::ReadyForShipment;
+        repos.records.update(&record).await.unwrap();
+        record_id
+    }
+
+    #[tokio::test]
+    async fn test_shipment_only_packs_the_customers_ready_records() {
+        let repos = repos().await;
+        let customer_id = Uuid::new_v4();
+        let ready = ready_for_shipment(&repos, customer_id).await;
+        let someone_elses = ready_for_shipment(&repos, Uuid::new_v4()).await;
+        let mut growing = repos.records.get(ready_for_shipment(&repos, customer_id).await).await.unwrap();
+        growing.germination_phase = GerminationPhase::ShootEmergence;
+        let growing = repos.records.update(&growing).await.unwrap().id;
+
+        let shipment = |ids: Vec<Uuid>| {
+            event(
+                "POST",
+                "/germination/shipment",
+                Some(serde_json::json!({
+                    "germinationRecordIds": ids,
+                    "customerId": customer_id,
+                    "expedited": false,
+                    "destination": {
+                        "street1": "1 Main St",
+                        "street2": null,
+                        "city": "Portland",
+                        "state": "OR",
+                        "zip": "97201",
+                        "country": "US"
+                    },
+                    "preferredDeliveryDays": []
+                })),
+            )
+        };
+
+        let response = function_handler(&repos, shipment(vec![ready, ready, someone_elses, growing, Uuid::new_v4()]))
+            .await
+            .unwrap();
+        assert_eq!(response.status_code, 400);
+        let fields: Vec<String> = body(&response)["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| error["field"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "germinationRecordIds[1]",
+                "germinationRecordIds[2]",
+                "germinationRecordIds[3]",
+                "germinationRecordIds[4]"
+            ]
+        );
+
+        assert_eq!(function_handler(&repos, shipment(vec![ready])).await.unwrap().status_code, 201);
+
+        // The package now holds the record
+        let response = function_handler(&repos, shipment(vec![ready])).await.unwrap();
+        assert_eq!(response.status_code, 400);
+        assert_eq!(body(&response)["errors"][0]["message"], "is already in a proposed or approved package");
+    }
+
+    #[tokio::test]
+    async fn test_only_one_of_two_concurrent_claims_holds_a_record() {
+        let repos = repos().await;
+        let customer_id = Uuid::new_v4();
+        let record_id = ready_for_shipment(&repos, customer_id).await;
+
+        // Both runs read the record before either claims it
+        let read = repos.records.get(record_id).await.unwrap();
+        let (mut first, mut second) = (vec![read.clone()], vec![read]);
+        let package_id = Uuid::new_v4();
+        assert_eq!(allocation::claim(&repos, &mut first, package_id).await.unwrap(), None);
+        assert_eq!(allocation::claim(&repos, &mut second, Uuid::new_v4()).await.unwrap(), Some(0));
+        assert_eq!(repos.records.get(record_id).await.unwrap().package_id, Some(package_id));
+    }
+
+    #[tokio::test]
+    async fn test_observation_for_unknown_record_is_not_found() {
+        let response = function_handler(
+            &repos().await,
+            event(
+                "POST",
+                "/germination/observe",
+                Some(serde_json::json!({
+                    "germinationRecordId": Uuid::new_v4(),
+                    "observedBy": "grower",
+                    "rootLengthMm": null,
+                    "shootLengthMm": null,
+                    "leafCount": null,
+                    "healthStatus": "GOOD",
+                    "temperatureCelsius": null,
+                    "humidityPercent": null,
+                    "notes": null
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status_code, 404);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_body_is_problem_with_field() {
+        let response = function_handler(
+            &repos().await,
+            event(
+                "POST",
+                "/germination/start",
+                Some(serde_json::json!({
+                    "seedId": Uuid::new_v4(),
+                    "customerId": Uuid::new_v4(),
+                    "species": "Tomato",
+                    "variety": null,
+                    "growingMedium": "gravel",
+                    "shipmentType": "LIVE_SPROUT"
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status_code, 400);
+        assert_eq!(response.headers["content-type"], api::PROBLEM_JSON);
+        let problem = body(&response);
+        assert_eq!(problem["code"], "validation");
+        assert_eq!(problem["errors"][0]["field"], "growingMedium");
+    }
+
+    #[tokio::test]
+    async fn test_guides_are_found_by_alias_and_unknown_species_is_rejected() {
+        let repos = repos().await;
+
+        let guide = function_handler(&repos, event("GET", "/germination/guide/Cantelope", None)).await.unwrap();
+        assert_eq!(guide.status_code, 200);
+        assert_eq!(body(&guide)["species"], "cantaloupe");
+
+        let missing = function_handler(&repos, event("GET", "/germination/guide/dragonfruit", None)).await.unwrap();
+        assert_eq!(missing.status_code, 404);
+
+        let started = function_handler(
+            &repos,
+            event(
+                "POST",
+                "/germination/start",
+                Some(serde_json::json!({
+                    "seedId": Uuid::new_v4(),
+                    "customerId": Uuid::new_v4(),
+                    "species": "Dragonfruit",
+                    "variety": null,
+                    "growingMedium": "soil",
+                    "shipmentType": "LIVE_SPROUT"
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(started.status_code, 400);
+        assert_eq!(body(&started)["errors"][0]["field"], "species");
+    }
+
+    #[tokio::test]
+    async fn test_admin_edits_guide_with_variety_override() {
+        let repos = repos().await;
+        let mut tomato =
+            body(&function_handler(&repos, event("GET", "/germination/guide/tomato", None)).await.unwrap());
+        tomato["varieties"] = serde_json::json!([{ "variety": "Cherry", "overrides": { "ready_to_ship_days": 12 } }]);
+        let put = |guide: &serde_json::Value| event("PUT", "/germination/guides/tomato", Some(guide.clone()));
+
+        let forbidden = function_handler(&repos, put(&tomato)).await.unwrap();
+        assert_eq!(forbidden.status_code, 403);
+
+        let updated = function_handler(&repos, as_admin(put(&tomato))).await.unwrap();
+        assert_eq!(updated.status_code, 200);
+        assert_eq!(body(&updated)["version"], 1);
+
+        let stale = function_handler(&repos, as_admin(put(&tomato))).await.unwrap();
+        assert_eq!(stale.status_code, 409);
+
+        let cherry =
+            function_handler(&repos, event("GET", "/germination/guide/tomato?variety=cherry", None)).await.unwrap();
+        assert_eq!(body(&cherry)["ready_to_ship_days"], 12);
+        assert_eq!(body(&cherry)["variety"], "cherry");
+
+        tomato["version"] = 1.into();
+        tomato["shoot_emergence_days_min"] = 2.into();
+        let invalid = function_handler(&repos, as_admin(put(&tomato))).await.unwrap();
+        assert_eq!(invalid.status_code, 400);
+        assert_eq!(body(&invalid)["errors"][0]["field"], "shoot_emergence_days_min");
+    }
+}
@@ -0,0 +1,182 @@
+//! Stall detection
+//!
+//! Each guide gives a day window for every stage. A record still in a phase
+//! after the next stage's `*_days_max` has stalled: the evaluator suggests
+//! failing it and queues a `GerminationReview` for staff, once per stall.
+//! It runs daily from the `germination-stall-evaluator` binary and on demand
+//! from `POST /germination/stalls/evaluate`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use api::ApiError;
+use database::DatabaseError;
+use models::{GerminationGuide, GerminationPhase, ManufacturingQueue, Priority, QueueStatus, QueueType, Skill};
+
+use crate::{record_guide, Repositories};
+
+/// A record past its guide's window for the current phase
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Stall {
+    germination_record_id: Uuid,
+    species: String,
+    variety: Option<String>,
+    phase: GerminationPhase,
+    days_since_start: i64,
+    /// Day by which the guide expects the next stage
+    deadline_days: u32,
+    suggested_phase: GerminationPhase,
+    /// Review queued by this run; `None` when one was queued earlier or this was a dry run
+    review_queue_id: Option<Uuid>,
+}
+
+/// How often a species' growing records stall, for tuning its guide
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesStallRate {
+    species: String,
+    /// Growing records with a timed stage ahead of them
+    evaluated: usize,
+    stalled: usize,
+    stall_rate: f64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StallReport {
+    evaluated_at: DateTime<Utc>,
+    stalls: Vec<Stall>,
+    /// Highest stall rate first
+    species: Vec<SpeciesStallRate>,
+    reviews_opened: usize,
+}
+
+/// Compare every growing record with its guide. With `open_reviews`, each
+/// newly stalled record gets a review item in the manufacturing queue;
+/// without it the report is a dry run
+pub async fn evaluate_stalls(
+    repos: &Repositories,
+    now: DateTime<Utc>,
+    open_reviews: bool,
+) -> Result<StallReport, ApiError> {
+    let mut guides: HashMap<(String, Option<String>), Option<GerminationGuide>> = HashMap::new();
+    let mut rates: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    let mut stalls = Vec::new();
+    let mut reviews_opened = 0;
+
+    for mut record in repos.records.list_all().await? {
+        if !record.germination_phase.is_growing() {
+            continue;
+        }
+
+        let key = (record.species.clone(), record.variety.clone());
+        if !guides.contains_key(&key) {
+            guides.insert(key.clone(), record_guide(repos, &record).await?);
+        }
+        let Some(deadline_days) =
+            guides[&key].as_ref().and_then(|guide| guide.stage_deadline_days(&record.germination_phase))
+        else {
+            continue;
+        };
+
+        let rate = rates.entry(record.species.clone()).or_default();
+        rate.0 += 1;
+
+        let days_since_start = (now - record.started_at).num_days();
+        if days_since_start <= deadline_days as i64 {
+            continue;
+        }
+        rate.1 += 1;
+
+        let phase = record.germination_phase.clone();
+        let mut review_queue_id = None;
+        if open_reviews && record.stalled_phase.as_ref() != Some(&phase) {
+            // Claim the stall on the record first, so a concurrent run can't queue it twice
+            record.stalled_phase = Some(phase.clone());
+            match repos.records.update(&record).await {
+                Ok(_) => {
+                    let item = review_item(&record, &phase, days_since_start, deadline_days, now);
+                    repos.queue.create(&item).await?;
+                    review_queue_id = Some(item.id);
+                    reviews_opened += 1;
+                }
+                Err(DatabaseError::Conflict { .. }) => {
+                    warn!("Germination record {} changed during stall evaluation; retrying next run", record.id);
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        stalls.push(Stall {
+            germination_record_id: record.id,
+            species: record.species,
+            variety: record.variety,
+            phase,
+            days_since_start,
+            deadline_days,
+            suggested_phase: GerminationPhase::Failed,
+            review_queue_id,
+        });
+    }
+
+    let mut species: Vec<SpeciesStallRate> = rates
+        .into_iter()
+        .map(|(species, (evaluated, stalled))| SpeciesStallRate {
+            species,
+            evaluated,
+            stalled,
+            stall_rate: stalled as f64 / evaluated as f64,
+        })
+        .collect();
+    species.sort_by(|a, b| b.stall_rate.total_cmp(&a.stall_rate));
+
+    info!("Stall evaluation: {} stalled, {} reviews opened", stalls.len(), reviews_opened);
+
+    Ok(StallReport { evaluated_at: now, stalls, species, reviews_opened })
+}
+
+fn review_item(
+    record: &models::GerminationRecord,
+    phase: &GerminationPhase,
+    days_since_start: i64,
+    deadline_days: u32,
+    now: DateTime<Utc>,
+) -> ManufacturingQueue {
+    ManufacturingQueue {
+        id: Uuid::new_v4(),
+        queue_type: QueueType::GerminationReview {
+            germination_record_id: record.id,
+            stalled_phase: phase.clone(),
+        },
+        priority: Priority::Normal,
+        status: QueueStatus::Pending,
+        created_at: now,
+        scheduled_start: None,
+        actual_start: None,
+        completed_at: None,
+        assigned_to: None,
+        notes: Some(format!(
+            "{} sprout {} is still in {:?} on day {}; its guide expects the next stage by day {}. Suggest marking it FAILED.",
+            record.species,
+            record.id,
+            phase,
+            days_since_start,
+            deadline_days
+        )),
+        estimated_completion: None,
+        version: 0,
+        history: Vec::new(),
+        previous_step: None,
+        depends_on: Vec::new(),
+        batch_id: None,
+        required_skills: Skill::for_species(&record.species).into_iter().collect(),
+        lease_expires_at: None,
+        leased: false,
+    }
+}
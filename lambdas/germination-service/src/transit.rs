@@ -0,0 +1,257 @@
+//! Live-sprout ship-day planning
+//!
+//! A live sprout must never sit in a depot: every day it spends in transit the
+//! carrier has to be moving it. Carriers don't move parcels on Sundays or US
+//! federal holidays, and most services don't on Saturdays either, so a package
+//! that would still be on the way on one of those days has a layover. The
+//! planner walks forward from today to the first ship date where a carrier
+//! service reaches the customer on one of their delivery days, within the
+//! package's transit limit, without a layover.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday as Day};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use models::{Address, Weekday};
+
+/// Longest a live sprout may spend in transit, standard and expedited
+pub(crate) const MAX_TRANSIT_DAYS: u32 = 3;
+pub(crate) const MAX_EXPEDITED_TRANSIT_DAYS: u32 = 2;
+/// How far ahead the planner looks for a ship date
+pub(crate) const PLANNING_HORIZON_DAYS: usize = 21;
+
+/// States and territories outside the contiguous US, where ground transit runs longer
+const NONCONTIGUOUS: &[&str] = &["AK", "HI", "PR", "GU", "VI", "AS", "MP"];
+
+/// A carrier's quote for getting a package to the destination
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitEstimate {
+    carrier: String,
+    service: String,
+    /// Days from pickup to delivery, counting only days the carrier moves parcels
+    transit_days: u32,
+    /// Priced as an expedited service
+    #[serde(default)]
+    expedited: bool,
+    /// The carrier moves and delivers parcels on Saturdays
+    #[serde(default)]
+    saturday_delivery: bool,
+}
+
+impl TransitEstimate {
+    /// USPS Priority Mail and Priority Mail Express, at the slow end of their ranges
+    pub(crate) fn defaults(destination: &Address) -> Vec<Self> {
+        let noncontiguous = NONCONTIGUOUS.contains(&destination.state.trim().to_uppercase().as_str());
+        let usps = |service: &str, transit_days, expedited| Self {
+            carrier: "usps".to_string(),
+            service: service.to_string(),
+            transit_days,
+            expedited,
+            saturday_delivery: expedited,
+        };
+        if noncontiguous {
+            vec![usps("usps_priority_mail", 4, false), usps("usps_priority_mail_express", 2, true)]
+        } else {
+            vec![usps("usps_priority_mail", 3, false), usps("usps_priority_mail_express", 1, true)]
+        }
+    }
+
+    pub(crate) fn carrier(&self) -> &str {
+        &self.carrier
+    }
+
+    pub(crate) fn service(&self) -> &str {
+        &self.service
+    }
+
+    pub(crate) fn transit_days(&self) -> u32 {
+        self.transit_days
+    }
+
+    pub(crate) fn is_expedited(&self) -> bool {
+        self.expedited
+    }
+
+    /// Whether the carrier moves parcels that day
+    fn moves_on(&self, date: NaiveDate) -> bool {
+        self.moves_on_weekday(date.weekday()) && !is_us_holiday(date)
+    }
+
+    fn moves_on_weekday(&self, day: Day) -> bool {
+        match day {
+            Day::Sun => false,
+            Day::Sat => self.saturday_delivery,
+            _ => true,
+        }
+    }
+}
+
+/// When and how a package goes out
+#[derive(Debug)]
+pub(crate) struct ShipPlan {
+    pub ship_date: NaiveDate,
+    pub delivery_date: NaiveDate,
+    pub estimate: TransitEstimate,
+    /// Weekdays the chosen service can leave on and deliver on a preferred day
+    pub ship_on_days: Vec<Weekday>,
+}
+
+impl ShipPlan {
+    /// Ice or heat packs for a package that spends a night on a truck, or
+    /// arrives in summer or winter
+    pub(crate) fn needs_temperature_control(&self) -> bool {
+        self.estimate.transit_days > 1 || matches!(self.delivery_date.month(), 6..=8 | 12 | 1 | 2)
+    }
+}
+
+/// The earliest ship date from `from` on which one of `estimates` delivers
+/// within `max_transit_days` on a `preferred` day (any day the carrier
+/// delivers when empty) without a layover. On the same day, standard
+/// services win over expedited ones, then faster over slower
+pub(crate) fn plan(
+    from: NaiveDate,
+    estimates: &[TransitEstimate],
+    max_transit_days: u32,
+    preferred: &[Weekday],
+) -> Option<ShipPlan> {
+    let mut candidates: Vec<&TransitEstimate> =
+        estimates.iter().filter(|estimate| estimate.transit_days <= max_transit_days).collect();
+    candidates.sort_by_key(|estimate| (estimate.expedited, estimate.transit_days));
+
+    let delivers_on = |day: Day| preferred.is_empty() || preferred.contains(&day.into());
+
+    for ship_date in from.iter_days().take(PLANNING_HORIZON_DAYS) {
+        if !is_ship_day(ship_date) {
+            continue;
+        }
+        for estimate in &candidates {
+            let delivery_date = ship_date + Duration::days(estimate.transit_days as i64);
+            let no_layover =
+                ship_date.iter_days().skip(1).take(estimate.transit_days as usize).all(|day| estimate.moves_on(day));
+            if no_layover && delivers_on(delivery_date.weekday()) {
+                let ship_on_days = [Day::Mon, Day::Tue, Day::Wed, Day::Thu, Day::Fri]
+                    .into_iter()
+                    .filter(|&day| {
+                        let mut days = (1..=estimate.transit_days).map(|n| shift(day, n));
+                        days.all(|day| estimate.moves_on_weekday(day)) && delivers_on(shift(day, estimate.transit_days))
+                    })
+                    .map(Weekday::from)
+                    .collect();
+                return Some(ShipPlan { ship_date, delivery_date, estimate: (*estimate).clone(), ship_on_days });
+            }
+        }
+    }
+    None
+}
+
+/// We hand packages over on weekdays that aren't holidays
+fn is_ship_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Day::Sat | Day::Sun) && !is_us_holiday(date)
+}
+
+fn shift(day: Day, days: u32) -> Day {
+    (0..days).fold(day, |day, _| day.succ())
+}
+
+/// A US federal holiday, on the day it is observed
+pub(crate) fn is_us_holiday(date: NaiveDate) -> bool {
+    // New Year's Day falling on a Saturday is observed the Friday before, in the previous year
+    us_holidays(date.year()).contains(&date) || us_holidays(date.year() + 1).contains(&date)
+}
+
+/// The year's federal holidays as observed: a Saturday holiday moves to the
+/// Friday before, a Sunday one to the Monday after
+fn us_holidays(year: i32) -> Vec<NaiveDate> {
+    let fixed = |month, day| NaiveDate::from_ymd_opt(year, month, day).map(observed);
+    let nth = |month, weekday, n| NaiveDate::from_weekday_of_month_opt(year, month, weekday, n);
+    let last = |month, weekday| nth(month, weekday, 5).or_else(|| nth(month, weekday, 4));
+
+    [
+        fixed(1, 1),          // New Year's Day
+        nth(1, Day::Mon, 3),  // Birthday of Martin Luther King, Jr.
+        nth(2, Day::Mon, 3),  // Washington's Birthday
+        last(5, Day::Mon),    // Memorial Day
+        fixed(6, 19),         // Juneteenth
+        fixed(7, 4),          // Independence Day
+        nth(9, Day::Mon, 1),  // Labor Day
+        nth(10, Day::Mon, 2), // Columbus Day
+        fixed(11, 11),        // Veterans Day
+        nth(11, Day::Thu, 4), // Thanksgiving Day
+        fixed(12, 25),        // Christmas Day
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+fn observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Day::Sat => date - Duration::days(1),
+        Day::Sun => date + Duration::days(1),
+        _ => date,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn estimate(transit_days: u32, expedited: bool) -> TransitEstimate {
+        TransitEstimate {
+            carrier: "usps".to_string(),
+            service: if expedited { "express" } else { "priority" }.to_string(),
+            transit_days,
+            expedited,
+            saturday_delivery: false,
+        }
+    }
+
+    #[test]
+    fn test_holidays_are_observed_on_weekdays() {
+        assert!(is_us_holiday(date(2026, 11, 26))); // Thanksgiving
+        assert!(is_us_holiday(date(2026, 5, 25))); // Memorial Day
+        assert!(is_us_holiday(date(2026, 7, 3))); // July 4th falls on a Saturday
+        assert!(!is_us_holiday(date(2026, 7, 4)));
+        assert!(is_us_holiday(date(2021, 12, 31))); // New Year's Day 2022 falls on a Saturday
+        assert!(!is_us_holiday(date(2026, 11, 27)));
+    }
+
+    #[test]
+    fn test_plan_avoids_weekend_and_holiday_layovers() {
+        let standard = [estimate(2, false)];
+
+        // Thursday's package would wait out Saturday and Sunday; Monday's arrives Wednesday
+        let plan = plan(date(2026, 10, 15), &standard, MAX_TRANSIT_DAYS, &[]).unwrap();
+        assert_eq!(plan.ship_date, date(2026, 10, 19));
+        assert_eq!(plan.delivery_date, date(2026, 10, 21));
+        assert_eq!(plan.ship_on_days, [Weekday::Monday, Weekday::Tuesday, Weekday::Wednesday]);
+
+        // Thanksgiving blocks Tuesday and Wednesday of that week
+        let plan = super::plan(date(2026, 11, 24), &standard, MAX_TRANSIT_DAYS, &[]).unwrap();
+        assert_eq!(plan.ship_date, date(2026, 11, 30));
+
+        // Only Friday deliveries: leave on Wednesday
+        let plan = super::plan(date(2026, 10, 19), &standard, MAX_TRANSIT_DAYS, &[Weekday::Friday]).unwrap();
+        assert_eq!(plan.ship_date, date(2026, 10, 21));
+        assert_eq!(plan.ship_on_days, [Weekday::Wednesday]);
+    }
+
+    #[test]
+    fn test_plan_expedites_only_when_standard_cannot_make_it() {
+        let hawaii = [estimate(4, false), estimate(2, true)];
+        let plan = plan(date(2026, 10, 19), &hawaii, MAX_TRANSIT_DAYS, &[]).unwrap();
+        assert!(plan.estimate.is_expedited());
+        assert!(plan.needs_temperature_control());
+
+        let contiguous = [estimate(3, false), estimate(1, true)];
+        let plan = super::plan(date(2026, 10, 19), &contiguous, MAX_TRANSIT_DAYS, &[]).unwrap();
+        assert!(!plan.estimate.is_expedited());
+
+        assert!(super::plan(date(2026, 10, 19), &[estimate(4, false)], MAX_TRANSIT_DAYS, &[]).is_none());
+    }
+}
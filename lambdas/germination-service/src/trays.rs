@@ -0,0 +1,433 @@
+//! Germination trays
+//!
+//! Staff sow a tray at a time. Starting a tray creates a germination record
+//! per seeded cell, all sharing the tray's medium, conditions and start time;
+//! a tray observation records any number of its cells at once under the
+//! tray's conditions. Germination rates are reported per tray and per seed lot.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use api::{response, ApiError, ApiResult, FieldError, Request};
+use models::{
+    GerminationObservation, GerminationPhase, GerminationRecord, GerminationTray, GrowingMedium, ShipmentType,
+    SproutHealthStatus, TrayCell,
+};
+
+use crate::{new_record, observe, starting_guide, RecordObservationRequest, Repositories, StartGerminationRequest};
+
+/// Rows or columns in the largest tray we sow (a 512-cell plug tray is 16 x 32)
+const MAX_TRAY_SIDE: u32 = 32;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StartTrayRequest {
+    label: String,
+    rows: u32,
+    columns: u32,
+    /// Every cell's species unless the cell gives its own
+    species: String,
+    variety: Option<String>,
+    growing_medium: GrowingMedium,
+    shipment_type: ShipmentType,
+    temperature_celsius: Option<f32>,
+    humidity_percent: Option<f32>,
+    light_hours_per_day: Option<f32>,
+    /// Every cell's seed lot unless the cell gives its own
+    #[serde(default)]
+    seed_lot: Option<String>,
+    #[serde(default)]
+    notes: Option<String>,
+    cells: Vec<SeedCell>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedCell {
+    row: u32,
+    column: u32,
+    seed_id: Uuid,
+    customer_id: Uuid,
+    #[serde(default)]
+    species: Option<String>,
+    #[serde(default)]
+    variety: Option<String>,
+    #[serde(default)]
+    seed_lot: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayObservationRequest {
+    /// The tray's conditions, noted on every cell's observation
+    temperature_celsius: Option<f32>,
+    humidity_percent: Option<f32>,
+    cells: Vec<CellObservation>,
+}
+
+/// What the grower saw in one cell; the fields of `POST /germination/observe`
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CellObservation {
+    row: u32,
+    column: u32,
+    #[serde(default)]
+    root_length_mm: Option<f32>,
+    #[serde(default)]
+    shoot_length_mm: Option<f32>,
+    #[serde(default)]
+    leaf_count: Option<u32>,
+    #[serde(default)]
+    cotyledon_count: Option<u32>,
+    #[serde(default)]
+    true_leaf_count: Option<u32>,
+    health_status: SproutHealthStatus,
+    #[serde(default)]
+    radicle_visible: bool,
+    #[serde(default)]
+    shoot_visible: bool,
+    #[serde(default)]
+    cotyledons_expanded: bool,
+    #[serde(default)]
+    true_leaves_present: bool,
+    #[serde(default)]
+    appears_autotrophic: bool,
+    #[serde(default)]
+    notes: Option<String>,
+}
+
+/// Seeds sown, and how many came up
+#[derive(Debug, Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GerminationRate {
+    sown: usize,
+    /// A radicle emerged, whatever happened after
+    germinated: usize,
+    /// Failed without germinating
+    failed: usize,
+    /// Neither yet
+    pending: usize,
+    germination_rate: f64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CellStatus {
+    row: u32,
+    column: u32,
+    seed_id: Uuid,
+    seed_lot: Option<String>,
+    germination_record_id: Uuid,
+    species: String,
+    phase: GerminationPhase,
+    health_status: SproutHealthStatus,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayResponse {
+    tray_id: Uuid,
+    label: String,
+    rows: u32,
+    columns: u32,
+    growing_medium: GrowingMedium,
+    started_at: DateTime<Utc>,
+    cells: Vec<CellStatus>,
+    rate: GerminationRate,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayObservationResponse {
+    tray_id: Uuid,
+    observations: Vec<GerminationObservation>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayRate {
+    tray_id: Uuid,
+    label: String,
+    started_at: DateTime<Utc>,
+    rate: GerminationRate,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedLotRate {
+    seed_lot: String,
+    trays: usize,
+    rate: GerminationRate,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayReport {
+    /// Most recently started first
+    trays: Vec<TrayRate>,
+    /// By lot code; cells sown without a lot are left out
+    seed_lots: Vec<SeedLotRate>,
+}
+
+pub(crate) async fn start_tray(repos: &Repositories, request: Request) -> ApiResult {
+    let req: StartTrayRequest = request.json()?;
+    let principal = request.principal()?;
+
+    let mut errors = Vec::new();
+    let mut fail = |field: String, message: String| errors.push(FieldError { field, message });
+    if req.label.trim().is_empty() {
+        fail("label".to_string(), "is required".to_string());
+    }
+    for (field, side) in [("rows", req.rows), ("columns", req.columns)] {
+        if !(1..=MAX_TRAY_SIDE).contains(&side) {
+            fail(field.to_string(), format!("must be between 1 and {}", MAX_TRAY_SIDE));
+        }
+    }
+    if req.cells.is_empty() {
+        fail("cells".to_string(), "must seed at least one cell".to_string());
+    }
+    let mut seeded: HashMap<(u32, u32), usize> = HashMap::new();
+    for (i, cell) in req.cells.iter().enumerate() {
+        if cell.row >= req.rows || cell.column >= req.columns {
+            fail(
+                format!("cells[{}]", i),
+                format!("({}, {}) is outside the {} x {} tray", cell.row, cell.column, req.rows, req.columns),
+            );
+        } else if let Some(first) = seeded.insert((cell.row, cell.column), i) {
+            fail(
+                format!("cells[{}]", i),
+                format!("({}, {}) is already seeded by cells[{}]", cell.row, cell.column, first),
+            );
+        }
+    }
+
+    let started_at = Utc::now();
+    let tray_id = Uuid::new_v4();
+    let mut records = Vec::new();
+    let mut cells = Vec::new();
+    // Cells mostly share a species, so each species and variety is looked up once
+    let mut guides = HashMap::new();
+    for (i, cell) in req.cells.into_iter().enumerate() {
+        let start = StartGerminationRequest {
+            seed_id: cell.seed_id,
+            customer_id: cell.customer_id,
+            species: cell.species.unwrap_or_else(|| req.species.clone()),
+            variety: cell.variety.or_else(|| req.variety.clone()),
+            growing_medium: req.growing_medium.clone(),
+            shipment_type: req.shipment_type.clone(),
+        };
+        let key = (start.species.clone(), start.variety.clone());
+        if !guides.contains_key(&key) {
+            let found = match starting_guide(repos, &key.0, key.1.as_deref()).await {
+                Ok(found) => Ok(found),
+                Err(ApiError::Validation { errors, .. }) => Err(errors),
+                Err(err) => return Err(err),
+            };
+            guides.insert(key.clone(), found);
+        }
+        match &guides[&key] {
+            Ok((species, guide)) => {
+                let mut record = new_record(repos, start, species, guide, started_at).await?;
+                record.tray_id = Some(tray_id);
+                record.temperature_celsius = req.temperature_celsius;
+                record.humidity_percent = req.humidity_percent;
+                record.light_hours_per_day = req.light_hours_per_day;
+                cells.push(TrayCell {
+                    row: cell.row,
+                    column: cell.column,
+                    seed_id: cell.seed_id,
+                    seed_lot: cell.seed_lot.or_else(|| req.seed_lot.clone()),
+                    germination_record_id: record.id,
+                });
+                records.push(record);
+            }
+            Err(cell_errors) => {
+                errors.extend(cell_errors.iter().map(|error| FieldError {
+                    field: format!("cells[{}].{}", i, error.field),
+                    message: error.message.clone(),
+                }));
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::Validation { message: format!("Tray is invalid ({} problem(s))", errors.len()), errors });
+    }
+
+    let tray = GerminationTray {
+        id: tray_id,
+        label: req.label,
+        rows: req.rows,
+        columns: req.columns,
+        growing_medium: req.growing_medium,
+        temperature_celsius: req.temperature_celsius,
+        humidity_percent: req.humidity_percent,
+        light_hours_per_day: req.light_hours_per_day,
+        started_at,
+        started_by: principal.user_id.to_string(),
+        cells,
+        notes: req.notes,
+    };
+    // Records first, so the tray never points at a record that wasn't written
+    for record in &records {
+        repos.records.create(record).await?;
+    }
+    repos.trays.create(&tray).await?;
+
+    info!("Started tray {} ({}) with {} seeds", tray.id, tray.label, records.len());
+
+    response::created(&tray_response(tray, &records))
+}
+
+pub(crate) async fn get_tray(repos: &Repositories, request: Request) -> ApiResult {
+    let tray = repos.trays.get(request.uuid_param("id")?).await?;
+
+    let mut records = Vec::new();
+    for cell in &tray.cells {
+        records.push(repos.records.get(cell.germination_record_id).await?);
+    }
+
+    response::ok(&tray_response(tray, &records))
+}
+
+/// Observe any of the tray's cells in one go
+pub(crate) async fn observe_tray(repos: &Repositories, request: Request) -> ApiResult {
+    let req: TrayObservationRequest = request.json()?;
+    let tray = repos.trays.get(request.uuid_param("id")?).await?;
+    let observed_by = request.principal()?.user_id.to_string();
+
+    let records: HashMap<(u32, u32), Uuid> =
+        tray.cells.iter().map(|cell| ((cell.row, cell.column), cell.germination_record_id)).collect();
+    let errors: Vec<FieldError> = req
+        .cells
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| !records.contains_key(&(cell.row, cell.column)))
+        .map(|(i, cell)| FieldError {
+            field: format!("cells[{}]", i),
+            message: format!("({}, {}) is not a seeded cell of this tray", cell.row, cell.column),
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(ApiError::Validation { message: "Tray observation is invalid".to_string(), errors });
+    }
+
+    let mut observations = Vec::new();
+    for cell in req.cells {
+        let observation = RecordObservationRequest {
+            germination_record_id: records[&(cell.row, cell.column)],
+            root_length_mm: cell.root_length_mm,
+            shoot_length_mm: cell.shoot_length_mm,
+            leaf_count: cell.leaf_count,
+            cotyledon_count: cell.cotyledon_count,
+            true_leaf_count: cell.true_leaf_count,
+            health_status: cell.health_status,
+            radicle_visible: cell.radicle_visible,
+            shoot_visible: cell.shoot_visible,
+            cotyledons_expanded: cell.cotyledons_expanded,
+            true_leaves_present: cell.true_leaves_present,
+            appears_autotrophic: cell.appears_autotrophic,
+            temperature_celsius: req.temperature_celsius,
+            humidity_percent: req.humidity_percent,
+            notes: cell.notes,
+        };
+        observations.push(observe(repos, observation, observed_by.clone()).await?);
+    }
+
+    info!("Recorded {} observations for tray {}", observations.len(), tray.id);
+
+    response::created(&TrayObservationResponse { tray_id: tray.id, observations })
+}
+
+/// Germination rates for every tray and seed lot
+pub(crate) async fn tray_report(repos: &Repositories, _: Request) -> ApiResult {
+    let records: HashMap<Uuid, GerminationRecord> = repos
+        .records
+        .list_all()
+        .await?
+        .into_iter()
+        .filter(|record| record.tray_id.is_some())
+        .map(|record| (record.id, record))
+        .collect();
+    let mut trays = repos.trays.list_all().await?;
+    trays.sort_by_key(|tray| std::cmp::Reverse(tray.started_at));
+
+    let mut lots: BTreeMap<String, (usize, GerminationRate)> = BTreeMap::new();
+    let mut tray_rates = Vec::new();
+    for tray in trays {
+        let mut rate = GerminationRate::default();
+        let mut lots_in_tray = Vec::new();
+        for cell in &tray.cells {
+            let Some(record) = records.get(&cell.germination_record_id) else {
+                continue;
+            };
+            rate.count(record);
+            if let Some(lot) = &cell.seed_lot {
+                let (trays, lot_rate) = lots.entry(lot.clone()).or_default();
+                lot_rate.count(record);
+                if !lots_in_tray.contains(lot) {
+                    lots_in_tray.push(lot.clone());
+                    *trays += 1;
+                }
+            }
+        }
+        tray_rates.push(TrayRate { tray_id: tray.id, label: tray.label, started_at: tray.started_at, rate });
+    }
+
+    response::ok(&TrayReport {
+        trays: tray_rates,
+        seed_lots: lots.into_iter().map(|(seed_lot, (trays, rate))| SeedLotRate { seed_lot, trays, rate }).collect(),
+    })
+}
+
+impl GerminationRate {
+    fn count(&mut self, record: &GerminationRecord) {
+        self.sown += 1;
+        if record.radicle_emerged_at.is_some() {
+            self.germinated += 1;
+        } else if record.germination_phase == GerminationPhase::Failed {
+            self.failed += 1;
+        } else {
+            self.pending += 1;
+        }
+        self.germination_rate = self.germinated as f64 / self.sown as f64;
+    }
+}
+
+/// `records` in the order of the tray's cells
+fn tray_response(tray: GerminationTray, records: &[GerminationRecord]) -> TrayResponse {
+    let mut rate = GerminationRate::default();
+    let cells = tray
+        .cells
+        .iter()
+        .zip(records)
+        .map(|(cell, record)| {
+            rate.count(record);
+            CellStatus {
+                row: cell.row,
+                column: cell.column,
+                seed_id: cell.seed_id,
+                seed_lot: cell.seed_lot.clone(),
+                germination_record_id: cell.germination_record_id,
+                species: record.species.clone(),
+                phase: record.germination_phase.clone(),
+                health_status: record.health_status.clone(),
+            }
+        })
+        .collect();
+
+    TrayResponse {
+        tray_id: tray.id,
+        label: tray.label,
+        rows: tray.rows,
+        columns: tray.columns,
+        growing_medium: tray.growing_medium,
+        started_at: tray.started_at,
+        cells,
+        rate,
+    }
+}
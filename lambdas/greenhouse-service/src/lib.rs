@@ -0,0 +1,265 @@
+use std::sync::LazyLock;
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{Error, LambdaEvent};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use api::{response, ApiError, ApiResult, Request, Router};
+use database::{DatabaseError, GreenhouseZonesRepository, PlantsRepository};
+use models::{
+    ContaminationEvent, EnvironmentalConditions, GreenhouseZone, GrowthStage, Plant, PlantHealthStatus,
+    QuarantineStatus, RiskLevel, SpatialCoordinates, Species, ZoneType,
+};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateZoneRequest {
+    greenhouse_id: Uuid,
+    zone_type: ZoneType,
+    x: u32,
+    y: u32,
+    level: u32,
+    isolation_distance_meters: Option<f32>,
+    phenotype_designation: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlantSeedRequest {
+    seed_id: Uuid,
+    zone_id: Uuid,
+    species: String,
+    variety: Option<String>,
+    phenotype_notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantineRequest {
+    zone_id: Uuid,
+    reason: String,
+    contamination_type: String,
+    severity: RiskLevel,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneResponse {
+    zone_id: Uuid,
+    zone_type: ZoneType,
+    quarantine_status: QuarantineStatus,
+    plant_count: usize,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlantResponse {
+    plant_id: Uuid,
+    species: String,
+    zone_id: Uuid,
+    growth_stage: GrowthStage,
+    health_status: PlantHealthStatus,
+}
+
+/// Repositories shared across invocations
+pub struct Repositories {
+    zones: GreenhouseZonesRepository,
+    plants: PlantsRepository,
+}
+
+impl Repositories {
+    pub fn new(client: aws_sdk_dynamodb::Client) -> Self {
+        Self { zones: GreenhouseZonesRepository::new(client.clone()), plants: PlantsRepository::new(client) }
+    }
+
+    /// Process-local tables, for tests and the dev server
+    #[cfg(any(test, feature = "in-memory"))]
+    pub fn in_memory() -> Self {
+        Self { zones: GreenhouseZonesRepository::in_memory(), plants: PlantsRepository::in_memory() }
+    }
+}
+
+static ROUTER: LazyLock<Router<Repositories>> = LazyLock::new(|| {
+    Router::new()
+        .post("/greenhouse/zones", create_zone)
+        .post("/greenhouse/plants", plant_seed)
+        .post("/greenhouse/quarantine", initiate_quarantine)
+        .get("/greenhouse/zones", list_zones)
+        .get("/greenhouse/plants", list_plants)
+});
+
+pub async fn function_handler(
+    repos: &Repositories,
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    info!("Processing greenhouse request");
+
+    Ok(ROUTER.handle(repos, event.payload).await)
+}
+
+async fn create_zone(repos: &Repositories, request: Request) -> ApiResult {
+    let req: CreateZoneRequest = request.json()?;
+
+    let zone_id = Uuid::new_v4();
+
+    // Calculate contamination risk based on zone type and spatial parameters
+    let contamination_risk = match req.zone_type {
+        ZoneType::Quarantine => RiskLevel::High,
+        ZoneType::PhenotypeIsolation => RiskLevel::Medium,
+        ZoneType::Standard => RiskLevel::Low,
+        _ => RiskLevel::Low,
+    };
+
+    let existing_zones = repos.zones.list_all().await?;
+    let zone_number = existing_zones.iter().map(|z| z.zone_number).max().unwrap_or(0) + 1;
+
+    let zone = GreenhouseZone {
+        id: zone_id,
+        zone_number,
+        zone_type: req.zone_type.clone(),
+        spatial_coordinates: SpatialCoordinates {
+            x: req.x,
+            y: req.y,
+            level: req.level,
+            isolation_distance_meters: req.isolation_distance_meters,
+        },
+        current_plants: vec![],
+        quarantine_status: QuarantineStatus::None,
+        phenotype_designation: req.phenotype_designation,
+        environmental_conditions: EnvironmentalConditions {
+            temperature_celsius: None,
+            humidity_percent: None,
+            light_hours_per_day: None,
+            co2_ppm: None,
+            last_measured: chrono::Utc::now(),
+        },
+        contamination_risk_level: contamination_risk,
+    };
+
+    repos.zones.create(&zone).await?;
+
+    info!(
+        "Created zone {} of type {:?} at coordinates ({}, {}, {}) in greenhouse {}",
+        zone_id, zone.zone_type, req.x, req.y, req.level, req.greenhouse_id
+    );
+
+    // TODO: Validate spatial isolation requirements
+
+    let response = ZoneResponse {
+        zone_id: zone.id,
+        zone_type: zone.zone_type,
+        quarantine_status: zone.quarantine_status,
+        plant_count: zone.current_plants.len(),
+    };
+
+    response::created(&response)
+}
+
+async fn plant_seed(repos: &Repositories, request: Request) -> ApiResult {
+    let req: PlantSeedRequest = request.json()?;
+    let species = Species::resolve(&req.species).ok_or_else(|| ApiError::unknown_species("species", &req.species))?;
+
+    let mut zone = match repos.zones.get(req.zone_id).await {
+        Ok(zone) => zone,
+        Err(DatabaseError::NotFound(_)) => return Err(ApiError::not_found("Zone not found")),
+        Err(err) => return Err(err.into()),
+    };
+
+    let plant_id = Uuid::new_v4();
+
+    let plant = Plant {
+        id: plant_id,
+        seed_id: Some(req.seed_id),
+        species: species.id.to_string(),
+        variety: req.variety,
+        current_zone_id: req.zone_id,
+        planted_at: chrono::Utc::now(),
+        growth_stage: GrowthStage::Germination,
+        health_status: PlantHealthStatus::Healthy,
+        expected_harvest_date: None,
+        phenotype_notes: req.phenotype_notes,
+        contamination_history: vec![],
+    };
+
+    repos.plants.create(&plant).await?;
+    zone.current_plants.push(plant.id);
+    repos.zones.update(&zone).await?;
+
+    info!("Planted seed {} as plant {} in zone {}", req.seed_id, plant_id, req.zone_id);
+
+    // TODO: Check if zone requires preventive quarantine
+
+    let response = PlantResponse {
+        plant_id: plant.id,
+        species: plant.species,
+        zone_id: plant.current_zone_id,
+        growth_stage: plant.growth_stage,
+        health_status: plant.health_status,
+    };
+
+    response::created(&response)
+}
+
+async fn initiate_quarantine(repos: &Repositories, request: Request) -> ApiResult {
+    let req: QuarantineRequest = request.json()?;
+
+    info!("Initiating quarantine for zone {} due to {}", req.zone_id, req.reason);
+
+    let mut zone = match repos.zones.get(req.zone_id).await {
+        Ok(zone) => zone,
+        Err(DatabaseError::NotFound(_)) => return Err(ApiError::not_found("Zone not found")),
+        Err(err) => return Err(err.into()),
+    };
+
+    let event = ContaminationEvent {
+        detected_at: chrono::Utc::now(),
+        contamination_type: req.contamination_type,
+        severity: req.severity.clone(),
+        action_taken: format!("Zone {} quarantined: {}", req.zone_id, req.reason),
+        resolved_at: None,
+    };
+
+    zone.quarantine_status = QuarantineStatus::Active;
+    zone.contamination_risk_level = req.severity;
+    repos.zones.update(&zone).await?;
+
+    // Log the contamination event against every plant in the zone
+    for mut plant in repos.plants.list_by_zone(zone.id).await? {
+        plant.health_status = PlantHealthStatus::Quarantine;
+        plant.contamination_history.push(event.clone());
+        repos.plants.update(&plant).await?;
+    }
+
+    // TODO: Notify administrators
+    // TODO: Calculate spatial impact on adjacent zones
+
+    response::ok(&event)
+}
+
+async fn list_zones(repos: &Repositories, _: Request) -> ApiResult {
+    info!("Listing all greenhouse zones");
+
+    let mut zones = repos.zones.list_all().await?;
+    zones.sort_by_key(|zone| zone.zone_number);
+
+    response::ok(&serde_json::json!({ "zones": zones }))
+}
+
+async fn list_plants(repos: &Repositories, request: Request) -> ApiResult {
+    // Optional ?zone_id= filter uses the zone-index
+    let plants = match request.query_as::<Uuid>("zone_id")? {
+        Some(zone_id) => {
+            info!("Listing plants in zone {}", zone_id);
+            repos.plants.list_by_zone(zone_id).await?
+        }
+        None => {
+            info!("Listing all plants");
+            repos.plants.list_all().await?
+        }
+    };
+
+    response::ok(&serde_json::json!({ "plants": plants }))
+}
@@ -0,0 +1,395 @@
+use std::sync::LazyLock;
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{Error, LambdaEvent};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use api::{response, ApiError, ApiResult, Request, Role, Router};
+use database::{ImageAuditLogsRepository, PlantImagesRepository};
+use models::{ImageAction, ImageAuditLog, ImageItemType, ImageUploadMetadata, ModerationStatus, PlantImage};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestPresignedUrlRequest {
+    item_id: Uuid,
+    item_type: ImageItemType,
+    filename: String,
+    content_type: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmUploadRequest {
+    upload_id: Uuid,
+    metadata: ImageUploadMetadata,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerateImageRequest {
+    image_id: Uuid,
+    status: ModerationStatus,
+    notes: Option<String>,
+    /// Version of the image the moderator reviewed; omit to moderate whatever is stored
+    #[serde(default)]
+    expected_version: Option<u64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PresignedUrlResponse {
+    upload_id: Uuid,
+    presigned_url: String,
+    s3_key: String,
+    expires_in_seconds: u64,
+}
+
+pub struct Repositories {
+    images: PlantImagesRepository,
+    audit_logs: ImageAuditLogsRepository,
+}
+
+impl Repositories {
+    pub fn new(client: aws_sdk_dynamodb::Client) -> Self {
+        Self { images: PlantImagesRepository::new(client.clone()), audit_logs: ImageAuditLogsRepository::new(client) }
+    }
+
+    /// Process-local tables, for tests and the dev server
+    #[cfg(any(test, feature = "in-memory"))]
+    pub fn in_memory() -> Self {
+        Self { images: PlantImagesRepository::in_memory(), audit_logs: ImageAuditLogsRepository::in_memory() }
+    }
+}
+
+/// Literal `/images/...` routes are registered ahead of `/images/{id}`
+static ROUTER: LazyLock<Router<Repositories>> = LazyLock::new(|| {
+    Router::new()
+        // Upload flow
+        .post("/images/request-upload", request_presigned_url)
+        .requires(Role::Customer)
+        .post("/images/confirm-upload", confirm_upload)
+        .requires(Role::Customer)
+        // User image management
+        .get("/images/my-images", list_my_images)
+        .requires(Role::Customer)
+        // Moderation
+        .get("/images/pending-moderation", list_pending_moderation)
+        .requires(Role::Moderator)
+        .post("/images/moderate", moderate_image)
+        .requires(Role::Moderator)
+        // Audit logs
+        .get("/images/audit-log", get_audit_log)
+        .requires(Role::Moderator)
+        .get("/images/{id}/audit", get_image_audit_log)
+        .requires(Role::Moderator)
+        .delete("/images/{id}", delete_my_image)
+        .requires(Role::Customer)
+});
+
+pub async fn function_handler(
+    repos: &Repositories,
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    info!("Processing image service request");
+
+    Ok(ROUTER.handle(repos, event.payload).await)
+}
+
+async fn request_presigned_url(_: &Repositories, request: Request) -> ApiResult {
+    let req: RequestPresignedUrlRequest = request.json()?;
+
+    let upload_id = Uuid::new_v4();
+    let s3_key = format!("images/{}/{}/{}", item_type_to_string(&req.item_type), req.item_id, upload_id);
+
+    info!("Generating presigned URL for upload {} ({}, {})", upload_id, req.filename, req.content_type);
+
+    // TODO: Generate actual S3 presigned URL
+    let presigned_url = format!("https://mock-s3-presigned-url/{}", s3_key);
+
+    let response = PresignedUrlResponse {
+        upload_id,
+        presigned_url,
+        s3_key,
+        expires_in_seconds: 3600, // 1 hour
+    };
+
+    response::ok(&response)
+}
+
+async fn confirm_upload(repos: &Repositories, request: Request) -> ApiResult {
+    let req: ConfirmUploadRequest = request.json()?;
+
+    let image_id = Uuid::new_v4();
+    let user_id = request.principal()?.user_id;
+
+    let image = PlantImage {
+        id: image_id,
+        uploaded_by: user_id,
+        item_id: req.metadata.item_id,
+        item_type: req.metadata.item_type,
+        s3_bucket: "seed-box-images".to_string(),
+        s3_key: format!("images/{}", req.upload_id),
+        s3_url: format!("https://s3.amazonaws.com/seed-box-images/images/{}", req.upload_id),
+        filename: req.metadata.filename,
+        content_type: req.metadata.content_type,
+        size_bytes: 0, // TODO: Get from S3
+        width: None,
+        height: None,
+        caption: req.metadata.caption,
+        growth_stage: req.metadata.growth_stage,
+        tags: req.metadata.tags,
+        moderation_status: ModerationStatus::Pending,
+        moderation_notes: None,
+        moderated_by: None,
+        moderated_at: None,
+        uploaded_at: chrono::Utc::now(),
+        deleted_at: None,
+        deleted_by: None,
+        version: 0,
+    };
+
+    // Create audit log entry
+    let audit = ImageAuditLog {
+        id: Uuid::new_v4(),
+        image_id,
+        action: ImageAction::Uploaded,
+        performed_by: user_id,
+        performed_at: chrono::Utc::now(),
+        details: Some(format!("Uploaded {}", image.filename)),
+        ip_address: None,
+        user_agent: None,
+    };
+
+    info!("Image {} uploaded by user {}", image_id, user_id);
+
+    repos.images.create(&image).await?;
+    repos.audit_logs.create(&audit).await?;
+
+    response::created(&image)
+}
+
+async fn list_my_images(repos: &Repositories, request: Request) -> ApiResult {
+    let user_id = request.principal()?.user_id;
+
+    let mut images = repos.images.list_by_uploader(user_id).await?;
+    images.retain(|image| image.moderation_status != ModerationStatus::Deleted);
+
+    response::ok(&serde_json::json!({ "count": images.len(), "images": images }))
+}
+
+async fn delete_my_image(repos: &Repositories, request: Request) -> ApiResult {
+    let image_id = request.uuid_param("id")?;
+    let principal = request.principal()?;
+    let user_id = principal.user_id;
+
+    info!("User {} deleting image {}", user_id, image_id);
+
+    let mut image = repos.images.get(image_id).await?;
+
+    // Customers may only delete their own uploads; moderators may remove anyone's
+    let own_image = image.uploaded_by == user_id;
+    if !own_image && !principal.has_role(Role::Moderator) {
+        return Err(ApiError::forbidden("You can only delete your own images"));
+    }
+
+    // Soft delete - mark as deleted but keep in DB
+    let now = chrono::Utc::now();
+    image.moderation_status = ModerationStatus::Deleted;
+    image.deleted_at = Some(now);
+    image.deleted_by = Some(user_id);
+    repos.images.update(&image).await?;
+
+    // Create audit log entry
+    let audit = ImageAuditLog {
+        id: Uuid::new_v4(),
+        image_id,
+        action: ImageAction::Deleted,
+        performed_by: user_id,
+        performed_at: now,
+        details: Some(if own_image {
+            "User deleted their own image".to_string()
+        } else {
+            "Moderator removed image".to_string()
+        }),
+        ip_address: None,
+        user_agent: None,
+    };
+    repos.audit_logs.create(&audit).await?;
+
+    response::ok(&serde_json::json!({ "status": "deleted", "audit_logged": true }))
+}
+
+async fn list_pending_moderation(repos: &Repositories, request: Request) -> ApiResult {
+    info!("Listing images pending moderation");
+
+    let page = request.page()?;
+
+    let pending = repos.images.page_by_status(&ModerationStatus::Pending, &page).await?;
+
+    response::ok(&serde_json::json!({
+        "count": pending.items.len(),
+        "pending_images": pending.items,
+        "next_cursor": pending.next_cursor,
+    }))
+}
+
+async fn moderate_image(repos: &Repositories, request: Request) -> ApiResult {
+    let req: ModerateImageRequest = request.json()?;
+
+    let moderator_id = request.principal()?.user_id;
+
+    info!("Moderator {} setting image {} to {:?}", moderator_id, req.image_id, req.status);
+
+    let mut image = repos.images.get(req.image_id).await?;
+
+    if let Some(expected) = req.expected_version {
+        image.version = expected;
+    }
+
+    let now = chrono::Utc::now();
+    image.moderation_status = req.status.clone();
+    image.moderation_notes = req.notes.clone();
+    image.moderated_by = Some(moderator_id);
+    image.moderated_at = Some(now);
+    let image = repos.images.update(&image).await?;
+
+    // Create audit log entry
+    let action = match req.status {
+        ModerationStatus::Approved => ImageAction::Approved,
+        ModerationStatus::Rejected => ImageAction::Rejected,
+        ModerationStatus::Flagged => ImageAction::Flagged,
+        _ => ImageAction::ViewedByModerator,
+    };
+
+    let audit = ImageAuditLog {
+        id: Uuid::new_v4(),
+        image_id: req.image_id,
+        action,
+        performed_by: moderator_id,
+        performed_at: now,
+        details: req.notes.clone(),
+        ip_address: None,
+        user_agent: None,
+    };
+    repos.audit_logs.create(&audit).await?;
+
+    response::ok(&serde_json::json!({
+        "image_id": req.image_id,
+        "status": req.status,
+        "moderated_by": moderator_id,
+        "version": image.version,
+        "audit_logged": true
+    }))
+}
+
+async fn get_audit_log(repos: &Repositories, _: Request) -> ApiResult {
+    info!("Fetching audit log");
+
+    let mut logs = repos.audit_logs.list_all().await?;
+    logs.sort_by_key(|log| std::cmp::Reverse(log.performed_at));
+
+    response::ok(&serde_json::json!({
+        "count": logs.len(),
+        "audit_logs": logs,
+    }))
+}
+
+async fn get_image_audit_log(repos: &Repositories, request: Request) -> ApiResult {
+    let image_id = request.uuid_param("id")?;
+
+    info!("Fetching audit log for image {}", image_id);
+
+    let logs = repos.audit_logs.list_by_image(image_id).await?;
+
+    response::ok(&serde_json::json!({
+        "image_id": image_id,
+        "count": logs.len(),
+        "audit_logs": logs
+    }))
+}
+
+fn item_type_to_string(item_type: &ImageItemType) -> String {
+    match item_type {
+        ImageItemType::Seed => "seed".to_string(),
+        ImageItemType::Plant => "plant".to_string(),
+        ImageItemType::Greenhouse => "greenhouse".to_string(),
+        ImageItemType::General => "general".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lambda_runtime::Context;
+
+    fn repos() -> Repositories {
+        Repositories::in_memory()
+    }
+
+    /// A request from `user_id`, as API Gateway's authorizer would pass it
+    fn event(
+        method: &str,
+        path: &str,
+        user_id: Uuid,
+        role: &str,
+        body: Option<serde_json::Value>,
+    ) -> LambdaEvent<ApiGatewayProxyRequest> {
+        let mut request = ApiGatewayProxyRequest {
+            http_method: method.parse().unwrap(),
+            path: Some(path.to_string()),
+            body: body.map(|b| b.to_string()),
+            ..Default::default()
+        };
+        request
+            .request_context
+            .authorizer
+            .fields
+            .insert("claims".to_string(), serde_json::json!({ "sub": user_id.to_string(), "cognito:groups": role }));
+        LambdaEvent::new(request, Context::default())
+    }
+
+    #[tokio::test]
+    async fn test_customer_deletes_only_own_image() {
+        let repos = repos();
+        let owner = Uuid::new_v4();
+        let upload = serde_json::json!({
+            "uploadId": Uuid::new_v4(),
+            "metadata": {
+                "item_id": Uuid::new_v4(),
+                "item_type": "plant",
+                "filename": "basil.jpg",
+                "content_type": "image/jpeg",
+                "caption": null,
+                "growth_stage": null,
+                "tags": []
+            }
+        });
+        let uploaded =
+            function_handler(&repos, event("POST", "/images/confirm-upload", owner, "customer", Some(upload)))
+                .await
+                .unwrap();
+        assert_eq!(uploaded.status_code, 201);
+        let image_id = repos.images.list_by_uploader(owner).await.unwrap()[0].id;
+        let path = format!("/images/{}", image_id);
+
+        let other = function_handler(&repos, event("DELETE", &path, Uuid::new_v4(), "customer", None)).await.unwrap();
+        assert_eq!(other.status_code, 403);
+
+        let own = function_handler(&repos, event("DELETE", &path, owner, "customer", None)).await.unwrap();
+        assert_eq!(own.status_code, 200);
+        assert_eq!(repos.images.get(image_id).await.unwrap().deleted_by, Some(owner));
+    }
+
+    #[tokio::test]
+    async fn test_moderation_requires_moderator() {
+        let body = serde_json::json!({ "imageId": Uuid::new_v4(), "status": "APPROVED", "notes": null });
+        let response =
+            function_handler(&repos(), event("POST", "/images/moderate", Uuid::new_v4(), "staff", Some(body)))
+                .await
+                .unwrap();
+        assert_eq!(response.status_code, 403);
+    }
+}
@@ -0,0 +1,237 @@
+use std::sync::LazyLock;
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{Error, LambdaEvent};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use api::{response, ApiError, ApiResult, Request, Router};
+use database::{BagsRepository, SeedsRepository};
+use models::{Bag, BagCondition, BagStatus, BagType, Seed, SeedStatus, Species, StatusCount};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiveBagRequest {
+    customer_id: Option<Uuid>,
+    bag_type: BagType,
+    condition: BagCondition,
+    contains_seeds: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterSeedRequest {
+    plant_species: String,
+    variety: Option<String>,
+    source_customer_id: Option<Uuid>,
+    notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BagResponse {
+    bag_id: Uuid,
+    status: BagStatus,
+    received_at: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedResponse {
+    seed_id: Uuid,
+    species: String,
+    status: SeedStatus,
+    is_edible_fruit_bearing: Option<bool>,
+}
+
+/// Repositories shared across invocations
+pub struct Repositories {
+    bags: BagsRepository,
+    seeds: SeedsRepository,
+}
+
+impl Repositories {
+    pub fn new(client: aws_sdk_dynamodb::Client) -> Self {
+        Self { bags: BagsRepository::new(client.clone()), seeds: SeedsRepository::new(client) }
+    }
+
+    /// Process-local tables, for tests and the dev server
+    #[cfg(any(test, feature = "in-memory"))]
+    pub fn in_memory() -> Self {
+        Self { bags: BagsRepository::in_memory(), seeds: SeedsRepository::in_memory() }
+    }
+}
+
+static ROUTER: LazyLock<Router<Repositories>> = LazyLock::new(|| {
+    Router::new()
+        .post("/inventory/bags", receive_bag)
+        .post("/inventory/seeds", register_seed)
+        .get("/inventory/bags", list_bags)
+        .get("/inventory/seeds", list_seeds)
+        .get("/inventory/summary", get_inventory_summary)
+});
+
+pub async fn function_handler(
+    repos: &Repositories,
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    info!("Processing inventory request");
+
+    Ok(ROUTER.handle(repos, event.payload).await)
+}
+
+async fn receive_bag(repos: &Repositories, request: Request) -> ApiResult {
+    let req: ReceiveBagRequest = request.json()?;
+
+    let bag_id = Uuid::new_v4();
+    let bag = Bag {
+        id: bag_id,
+        original_owner_id: req.customer_id,
+        current_status: BagStatus::Received,
+        bag_type: req.bag_type,
+        condition: req.condition,
+        received_at: chrono::Utc::now(),
+        cleaned_at: None,
+        last_updated: chrono::Utc::now(),
+        contains_seeds: req.contains_seeds,
+        seed_ids: vec![],
+        version: 0,
+    };
+
+    repos.bags.create(&bag).await?;
+
+    info!("Received bag {} from customer {:?}", bag_id, req.customer_id);
+
+    let response =
+        BagResponse { bag_id: bag.id, status: bag.current_status, received_at: bag.received_at.to_rfc3339() };
+
+    response::created(&response)
+}
+
+async fn register_seed(repos: &Repositories, request: Request) -> ApiResult {
+    let req: RegisterSeedRequest = request.json()?;
+    let species = Species::resolve(&req.plant_species)
+        .ok_or_else(|| ApiError::unknown_species("plantSpecies", &req.plant_species))?;
+
+    let seed_id = Uuid::new_v4();
+    let seed = Seed {
+        id: seed_id,
+        plant_species: species.id.to_string(),
+        variety: req.variety,
+        source_customer_id: req.source_customer_id,
+        collected_at: chrono::Utc::now(),
+        status: SeedStatus::Collected,
+        germination_tested: false,
+        germination_rate: None,
+        is_edible_fruit_bearing: Some(species.bears_fruit()),
+        has_edible_leaves: Some(species.has_edible_leaves()),
+        has_edible_stalks: Some(species.has_edible_stalks()),
+        contamination_check: None,
+        notes: req.notes,
+    };
+
+    repos.seeds.create(&seed).await?;
+
+    info!("Registered seed {} for species {}", seed_id, seed.plant_species);
+
+    // TODO: Trigger contamination check
+
+    let response = SeedResponse {
+        seed_id: seed.id,
+        species: seed.plant_species,
+        status: seed.status,
+        is_edible_fruit_bearing: seed.is_edible_fruit_bearing,
+    };
+
+    response::created(&response)
+}
+
+async fn list_bags(repos: &Repositories, request: Request) -> ApiResult {
+    let page = request.page()?;
+
+    // Optional ?status=cleaned filter uses the status-index
+    let bags = match request.query_as::<BagStatus>("status")? {
+        Some(status) => {
+            info!("Listing bags with status {:?}", status);
+            repos.bags.page_by_status(&status, &page).await?
+        }
+        None => {
+            info!("Listing all bags");
+            repos.bags.page_all(&page).await?
+        }
+    };
+
+    response::ok(&serde_json::json!({
+        "bags": bags.items,
+        "next_cursor": bags.next_cursor,
+    }))
+}
+
+async fn list_seeds(repos: &Repositories, request: Request) -> ApiResult {
+    let page = request.page()?;
+
+    // Optional ?status=approved filter uses the status-index
+    let seeds = match request.query_as::<SeedStatus>("status")? {
+        Some(status) => {
+            info!("Listing seeds with status {:?}", status);
+            repos.seeds.page_by_status(&status, &page).await?
+        }
+        None => {
+            info!("Listing all seeds");
+            repos.seeds.page_all(&page).await?
+        }
+    };
+
+    response::ok(&serde_json::json!({
+        "seeds": seeds.items,
+        "next_cursor": seeds.next_cursor,
+    }))
+}
+
+async fn get_inventory_summary(repos: &Repositories, _: Request) -> ApiResult {
+    info!("Generating inventory summary");
+
+    let bags = repos.bags.list_all().await?;
+    let seeds = repos.seeds.list_all().await?;
+
+    let bags_by_status = count_by_status(bags.iter().map(|bag| &bag.current_status))?;
+    let seeds_by_status = count_by_status(seeds.iter().map(|seed| &seed.status))?;
+
+    let ready: Vec<&Bag> = bags.iter().filter(|bag| bag.current_status == BagStatus::ReadyForShipment).collect();
+
+    // Bags with a known owner are held for that owner (Premium returns only your own bags);
+    // anonymous donations feed the random sampling pool for Standard subscribers
+    let premium_customer_bags_held = ready.iter().filter(|bag| bag.original_owner_id.is_some()).count();
+    let random_sampling_pool_size = ready.len() - premium_customer_bags_held;
+
+    let summary = models::InventorySummary {
+        total_bags: bags.len() as u32,
+        bags_by_status,
+        total_seeds: seeds.len() as u32,
+        seeds_by_status,
+        bags_ready_for_shipment: ready.len() as u32,
+        premium_customer_bags_held: premium_customer_bags_held as u32,
+        random_sampling_pool_size: random_sampling_pool_size as u32,
+    };
+
+    response::ok(&summary)
+}
+
+/// Tally statuses in first-seen order, keyed by their serialized name
+fn count_by_status<'a, S, I>(statuses: I) -> database::Result<Vec<StatusCount>>
+where
+    S: Serialize + 'a,
+    I: Iterator<Item = &'a S>,
+{
+    let mut counts: Vec<StatusCount> = Vec::new();
+    for status in statuses {
+        let status = database::item::key_string(status)?;
+        match counts.iter_mut().find(|c| c.status == status) {
+            Some(count) => count.count += 1,
+            None => counts.push(StatusCount { status, count: 1 }),
+        }
+    }
+    Ok(counts)
+}
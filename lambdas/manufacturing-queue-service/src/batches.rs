@@ -0,0 +1,156 @@
+//! Batches of compatible queue items
+//!
+//! Waiting items with the same [`models::QueueType::batch_key`], such as two
+//! shipment preps for one customer or transfers between the same zones, can
+//! be grouped so one worker picks them up and starts them together.
+
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use api::{response, ApiError, ApiResult, Request};
+use database::DatabaseError;
+use models::{ManufacturingQueue, QueueStatus};
+
+use crate::{claims, dependencies, scheduler, QueueResponse, Repositories};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBatchRequest {
+    queue_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResponse {
+    batch_id: Uuid,
+    /// What the items have in common, e.g. `SHIPMENT_PREP/<customer id>`
+    batch_key: Option<String>,
+    items: Vec<QueueResponse>,
+}
+
+impl BatchResponse {
+    fn new(batch_id: Uuid, items: &[ManufacturingQueue]) -> Self {
+        Self {
+            batch_id,
+            batch_key: items.first().and_then(|item| item.queue_type.batch_key()),
+            items: items.iter().map(QueueResponse::from).collect(),
+        }
+    }
+}
+
+fn is_waiting(item: &ManufacturingQueue) -> bool {
+    matches!(item.status, QueueStatus::Pending | QueueStatus::Scheduled)
+}
+
+pub(crate) async fn create_batch(repos: &Repositories, request: Request) -> ApiResult {
+    let req: CreateBatchRequest = request.json()?;
+
+    let ids: HashSet<Uuid> = req.queue_ids.iter().copied().collect();
+    if ids.len() < 2 {
+        return Err(ApiError::invalid_field("queueIds", "A batch needs at least two items"));
+    }
+
+    let mut items = Vec::new();
+    for &id in &req.queue_ids {
+        if items.iter().any(|item: &ManufacturingQueue| item.id == id) {
+            continue;
+        }
+        let item = match repos.queue.get(id).await {
+            Ok(item) => item,
+            Err(DatabaseError::NotFound(_)) => {
+                return Err(ApiError::invalid_field("queueIds", format!("No queue item {}", id)))
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let problem = if !is_waiting(&item) {
+            Some(format!("Queue item {} is {:?}", id, item.status))
+        } else if let Some(batch_id) = item.batch_id {
+            Some(format!("Queue item {} is already in batch {}", id, batch_id))
+        } else if item.depends_on.iter().any(|dep| ids.contains(dep)) {
+            Some(format!("Queue item {} waits on another item in the batch", id))
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            return Err(ApiError::invalid_field("queueIds", problem));
+        }
+        items.push(item);
+    }
+
+    let keys: HashSet<Option<String>> = items.iter().map(|item| item.queue_type.batch_key()).collect();
+    if keys.len() > 1 || keys.contains(&None) {
+        return Err(ApiError::invalid_field(
+            "queueIds",
+            "Only items of one kind for the same customer, or transfers between the same zones, can be batched",
+        ));
+    }
+
+    let batch_id = Uuid::new_v4();
+    let mut batched = Vec::new();
+    for mut item in items {
+        item.batch_id = Some(batch_id);
+        batched.push(repos.queue.update(&item).await?);
+    }
+
+    info!("Batched {} queue items as {}", batched.len(), batch_id);
+
+    response::created(&BatchResponse::new(batch_id, &batched))
+}
+
+pub(crate) async fn get_batch(repos: &Repositories, request: Request) -> ApiResult {
+    let batch_id = request.uuid_param("id")?;
+
+    info!("Fetching queue batch {}", batch_id);
+
+    response::ok(&BatchResponse::new(batch_id, &batch(repos, batch_id).await?))
+}
+
+/// Start every waiting item in the batch, or none of them if any is blocked
+/// or needs a certification the caller doesn't hold
+pub(crate) async fn start_batch(repos: &Repositories, request: Request) -> ApiResult {
+    let batch_id = request.uuid_param("id")?;
+
+    info!("Starting queue batch {}", batch_id);
+
+    let items = batch(repos, batch_id).await?;
+    let waiting: Vec<&ManufacturingQueue> = items.iter().filter(|item| is_waiting(item)).collect();
+    if waiting.is_empty() {
+        return Err(ApiError::Conflict {
+            message: format!("Nothing in batch {} is waiting to start", batch_id),
+            current: serde_json::to_value(BatchResponse::new(batch_id, &items)).ok(),
+        });
+    }
+    let mut workers = Vec::new();
+    for item in &waiting {
+        let open = dependencies::open_dependencies(repos, item).await?;
+        if !open.is_empty() {
+            return Err(dependencies::waiting_on(item, &open));
+        }
+        workers.push(claims::starting_worker(repos, &request, item).await?);
+    }
+
+    let now = chrono::Utc::now();
+    let by = request.principal().ok().map(|principal| principal.user_id);
+    for (item, worker) in waiting.into_iter().zip(workers) {
+        let mut item = item.clone();
+        item.assigned_to = Some(worker.id);
+        item.transition(QueueStatus::InProgress, now, by, Some(format!("Batch {}", batch_id)));
+        repos.queue.update(&item).await?;
+    }
+    scheduler::reschedule(repos, now).await?;
+
+    response::ok(&BatchResponse::new(batch_id, &batch(repos, batch_id).await?))
+}
+
+async fn batch(repos: &Repositories, batch_id: Uuid) -> Result<Vec<ManufacturingQueue>, ApiError> {
+    let mut items = repos.queue.list_by_batch(batch_id).await?;
+    if items.is_empty() {
+        return Err(ApiError::not_found(format!("No queue batch {}", batch_id)));
+    }
+    items.sort_by_key(|item| item.created_at);
+    Ok(items)
+}
@@ -0,0 +1,19 @@
+//! Returns abandoned claims to the queue; EventBridge invokes it every five
+//! minutes (see template.yaml)
+
+use lambda_runtime::{run, service_fn, Error, LambdaEvent};
+
+use manufacturing_queue_service::{release_abandoned, Repositories};
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).with_target(false).without_time().init();
+
+    let repos = Repositories::new(database::get_client().await);
+
+    run(service_fn(|_: LambdaEvent<serde_json::Value>| async {
+        let released = release_abandoned(&repos, chrono::Utc::now()).await?;
+        Ok::<_, Error>(serde_json::json!({ "released": released }))
+    }))
+    .await
+}
@@ -0,0 +1,428 @@
+//! Claiming queue items
+//!
+//! A staff member, or a station they work at, asks for work rather than
+//! picking it: a claim starts the next item in schedule order that is due,
+//! free of open dependencies and within the worker's certifications, along
+//! with the rest of its batch. The claim is a lease of [`LEASE_MINUTES`] that
+//! each heartbeat renews. When a lease runs out the item is taken to be
+//! abandoned and goes back to the queue for someone else; the
+//! `queue-lease-sweeper` binary does that every five minutes, and every claim
+//! does it first.
+
+use std::cmp::Reverse;
+
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use api::{response, ApiError, ApiResult, Request, Role};
+use database::DatabaseError;
+use models::{
+    BagStatus, ManufacturingQueue, QuarantineStatus, QueueStatus, QueueType, QueueWorker, SeedStatus, Skill,
+    WorkerKind, ZoneType,
+};
+
+use crate::{dependencies, optional_json, scheduler, QueueResponse, Repositories};
+
+/// How long a claim holds an item without a heartbeat
+pub const LEASE_MINUTES: i64 = 15;
+
+/// Optional body for claims, heartbeats and releases
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimRequest {
+    /// A station to work for; the caller themselves when left out
+    worker_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimResponse {
+    worker_id: String,
+    /// The claimed item followed by the rest of its batch; empty when nothing
+    /// is ready for the worker
+    items: Vec<QueueResponse>,
+    lease_expires_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerRequest {
+    kind: WorkerKind,
+    name: Option<String>,
+    /// Replaces the worker's certifications
+    skills: Vec<Skill>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerResponse {
+    worker_id: String,
+    kind: WorkerKind,
+    name: Option<String>,
+    skills: Vec<Skill>,
+    /// Claimed or started and not finished, most recently started first
+    current: Vec<QueueResponse>,
+    /// Most recently completed first
+    completed: Vec<QueueResponse>,
+}
+
+/// Staff who were never registered hold no certifications
+fn unregistered(id: String) -> QueueWorker {
+    QueueWorker { id, kind: WorkerKind::Staff, name: None, skills: Vec::new(), updated_at: Utc::now() }
+}
+
+fn found<T>(result: Result<T, DatabaseError>) -> Result<Option<T>, ApiError> {
+    match result {
+        Ok(found) => Ok(Some(found)),
+        Err(DatabaseError::NotFound(_)) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// What handling the item's seeds, plants, sprouts or bags needs
+/// certification for: regulated species, and anything held in quarantine.
+/// Things not on record need nothing
+pub(crate) async fn required_skills(repos: &Repositories, queue_type: &QueueType) -> Result<Vec<Skill>, ApiError> {
+    let mut species = Vec::new();
+    let mut quarantined = false;
+    match queue_type {
+        QueueType::SeedIntake { seed_ids } | QueueType::GerminationScheduled { seed_ids, .. } => {
+            for &id in seed_ids {
+                if let Some(seed) = found(repos.seeds.get(id).await)? {
+                    quarantined |= seed.status == SeedStatus::Quarantine;
+                    species.push(seed.plant_species);
+                }
+            }
+        }
+        QueueType::GreenhouseTransfer { plant_ids, from_zone_id, to_zone_id } => {
+            for &id in plant_ids {
+                if let Some(plant) = found(repos.plants.get(id).await)? {
+                    species.push(plant.species);
+                }
+            }
+            for &id in [from_zone_id, to_zone_id] {
+                if let Some(zone) = found(repos.zones.get(id).await)? {
+                    quarantined |= matches!(zone.zone_type, ZoneType::Quarantine)
+                        || !matches!(zone.quarantine_status, QuarantineStatus::None | QuarantineStatus::Cleared);
+                }
+            }
+        }
+        QueueType::ShipmentPrep { germination_record_ids, .. } => {
+            for &id in germination_record_ids {
+                if let Some(record) = found(repos.records.get(id).await)? {
+                    species.push(record.species);
+                }
+            }
+        }
+        QueueType::GerminationReview { germination_record_id, .. } => {
+            if let Some(record) = found(repos.records.get(*germination_record_id).await)? {
+                species.push(record.species);
+            }
+        }
+        QueueType::BagCleaning { bag_ids } | QueueType::BagShipmentPrep { bag_ids, .. } => {
+            for &id in bag_ids {
+                if let Some(bag) = found(repos.bags.get(id).await)? {
+                    quarantined |= bag.current_status == BagStatus::Quarantine;
+                }
+            }
+        }
+    }
+
+    let mut skills: Vec<Skill> = species.iter().filter_map(|name| Skill::for_species(name)).collect();
+    if quarantined {
+        skills.push(Skill::Quarantine);
+    }
+    skills.sort();
+    skills.dedup();
+    Ok(skills)
+}
+
+/// 403 unless `worker` holds every certification `item` needs
+fn certify(worker: &QueueWorker, item: &ManufacturingQueue) -> Result<(), ApiError> {
+    let missing: Vec<String> = item
+        .required_skills
+        .iter()
+        .filter(|skill| !worker.skills.contains(skill))
+        .map(|skill| format!("{:?}", skill).to_lowercase())
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::forbidden(format!(
+            "{} isn't certified for {} work on queue item {}",
+            worker.id,
+            missing.join(" and "),
+            item.id
+        )))
+    }
+}
+
+/// Who is starting `item` by hand, to assign it to: the caller, who must be
+/// staff and certified for it
+pub(crate) async fn starting_worker(
+    repos: &Repositories,
+    request: &Request,
+    item: &ManufacturingQueue,
+) -> Result<QueueWorker, ApiError> {
+    let principal = request.principal()?;
+    principal.require(Role::Staff)?;
+    let id = principal.user_id.to_string();
+    let worker = found(repos.workers.get(&id).await)?.unwrap_or_else(|| unregistered(id));
+    certify(&worker, item)?;
+    Ok(worker)
+}
+
+/// The worker a request acts as: the caller, or a station they name. A
+/// station only counts as certified for what the caller is certified for too
+async fn acting_worker(repos: &Repositories, request: &Request) -> Result<QueueWorker, ApiError> {
+    let req: ClaimRequest = optional_json(request)?;
+    acting_as(repos, request, req.worker_id).await
+}
+
+async fn acting_as(repos: &Repositories, request: &Request, station: Option<String>) -> Result<QueueWorker, ApiError> {
+    let caller = request.principal()?.user_id.to_string();
+    let worker_id = station.unwrap_or_else(|| caller.clone());
+
+    let worker = found(repos.workers.get(&worker_id).await)?;
+    if worker_id == caller {
+        return Ok(worker.unwrap_or_else(|| unregistered(caller)));
+    }
+    match worker {
+        Some(mut station) if station.kind == WorkerKind::Station => {
+            let caller = found(repos.workers.get(&caller).await)?.unwrap_or_else(|| unregistered(caller));
+            station.skills.retain(|skill| caller.skills.contains(skill));
+            Ok(station)
+        }
+        Some(_) => Err(ApiError::forbidden(format!("Only stations can be worked for; {} is staff", worker_id))),
+        None => Err(ApiError::invalid_field("workerId", format!("No worker {}", worker_id))),
+    }
+}
+
+/// Waiting, due, certified for and not waiting on anything
+async fn claimable(
+    repos: &Repositories,
+    worker: &QueueWorker,
+    item: &ManufacturingQueue,
+    now: DateTime<Utc>,
+) -> Result<bool, ApiError> {
+    Ok(matches!(item.status, QueueStatus::Pending | QueueStatus::Scheduled)
+        && item.scheduled_start.is_none_or(|start| start <= now)
+        && certify(worker, item).is_ok()
+        && dependencies::open_dependencies(repos, item).await?.is_empty())
+}
+
+/// Start `item` for `worker` under a fresh lease; `None` when someone else
+/// changed it first
+async fn lease(
+    repos: &Repositories,
+    worker: &QueueWorker,
+    mut item: ManufacturingQueue,
+    now: DateTime<Utc>,
+    by: Option<Uuid>,
+) -> Result<Option<ManufacturingQueue>, ApiError> {
+    item.transition(QueueStatus::InProgress, now, by, Some(format!("Claimed by {}", worker.id)));
+    item.assigned_to = Some(worker.id.clone());
+    item.lease_expires_at = Some(now + Duration::minutes(LEASE_MINUTES));
+    item.leased = true;
+    match repos.queue.update(&item).await {
+        Ok(item) => Ok(Some(item)),
+        Err(DatabaseError::Conflict { .. }) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub(crate) async fn claim(repos: &Repositories, request: Request) -> ApiResult {
+    let worker = acting_worker(repos, &request).await?;
+    let by = request.principal().ok().map(|principal| principal.user_id);
+    let now = Utc::now();
+
+    info!("Finding queue work for {}", worker.id);
+
+    release_abandoned(repos, now).await?;
+
+    let mut claimed = Vec::new();
+    for id in scheduler::schedule(repos, now).await?.run_order() {
+        let Some(item) = found(repos.queue.get(id).await)? else {
+            continue;
+        };
+        if !claimable(repos, &worker, &item, now).await? {
+            continue;
+        }
+        let batch_id = item.batch_id;
+        let Some(item) = lease(repos, &worker, item, now, by).await? else {
+            continue;
+        };
+        claimed.push(item);
+
+        // The rest of the batch comes along, as far as the worker can take it
+        if let Some(batch_id) = batch_id {
+            for other in repos.queue.list_by_batch(batch_id).await? {
+                if other.id != id && claimable(repos, &worker, &other, now).await? {
+                    claimed.extend(lease(repos, &worker, other, now, by).await?);
+                }
+            }
+        }
+        break;
+    }
+
+    if claimed.is_empty() {
+        info!("Nothing in the queue for {}", worker.id);
+        return response::ok(&ClaimResponse { worker_id: worker.id, items: Vec::new(), lease_expires_at: None });
+    }
+
+    info!("{} claimed {} queue items", worker.id, claimed.len());
+    scheduler::reschedule(repos, now).await?;
+
+    let mut items = Vec::new();
+    for item in &claimed {
+        items.push(QueueResponse::from(&repos.queue.get(item.id).await?));
+    }
+    let lease_expires_at = claimed[0].lease_expires_at.map(|at| at.to_rfc3339());
+    response::ok(&ClaimResponse { worker_id: worker.id, items, lease_expires_at })
+}
+
+/// 403 unless the caller, or the station they name, holds `item`; admins may
+/// change anyone's work. An item in progress or paused is held by whoever it's
+/// assigned to, anything else is open to all staff
+pub(crate) async fn check_holder(
+    repos: &Repositories,
+    request: &Request,
+    station: Option<String>,
+    item: &ManufacturingQueue,
+) -> Result<(), ApiError> {
+    let Some(holder) = item.assigned_to.as_deref() else {
+        return Ok(());
+    };
+    if !matches!(item.status, QueueStatus::InProgress | QueueStatus::Paused)
+        || request.principal()?.has_role(Role::Admin)
+    {
+        return Ok(());
+    }
+    let worker = acting_as(repos, request, station).await?;
+    if worker.id == holder {
+        Ok(())
+    } else {
+        Err(ApiError::forbidden(format!("Queue item {} is held by {}", item.id, holder)))
+    }
+}
+
+/// The item, 409 unless it is in progress for `worker`
+async fn held_by(repos: &Repositories, id: Uuid, worker: &QueueWorker) -> Result<ManufacturingQueue, ApiError> {
+    let item = repos.queue.get(id).await?;
+    if item.status != QueueStatus::InProgress || item.assigned_to.as_deref() != Some(worker.id.as_str()) {
+        return Err(ApiError::Conflict {
+            message: format!("Queue item {} isn't in progress for {}", id, worker.id),
+            current: serde_json::to_value(&item).ok(),
+        });
+    }
+    Ok(item)
+}
+
+/// Renew the lease; one that ran out still renews until the item is released
+pub(crate) async fn heartbeat(repos: &Repositories, request: Request) -> ApiResult {
+    let id = request.uuid_param("id")?;
+    let worker = acting_worker(repos, &request).await?;
+
+    info!("Heartbeat from {} on queue item {}", worker.id, id);
+
+    let mut item = held_by(repos, id, &worker).await?;
+    item.lease_expires_at = Some(Utc::now() + Duration::minutes(LEASE_MINUTES));
+    let item = repos.queue.update(&item).await?;
+
+    response::ok(&QueueResponse::from(&item))
+}
+
+/// Give the item back to the queue unfinished
+pub(crate) async fn release(repos: &Repositories, request: Request) -> ApiResult {
+    let id = request.uuid_param("id")?;
+    let worker = acting_worker(repos, &request).await?;
+
+    info!("{} releasing queue item {}", worker.id, id);
+
+    let mut item = held_by(repos, id, &worker).await?;
+    let now = Utc::now();
+    let by = request.principal().ok().map(|principal| principal.user_id);
+    item.transition(QueueStatus::Pending, now, by, Some(format!("Released by {}", worker.id)));
+    repos.queue.update(&item).await?;
+    scheduler::reschedule(repos, now).await?;
+
+    response::ok(&QueueResponse::from(&repos.queue.get(id).await?))
+}
+
+/// Put in-progress items whose lease ran out back in the queue, returning them
+pub async fn release_abandoned(repos: &Repositories, now: DateTime<Utc>) -> Result<Vec<Uuid>, ApiError> {
+    let mut released = Vec::new();
+    for mut item in repos.queue.list_by_status(&QueueStatus::InProgress).await? {
+        if item.lease_expires_at.is_none_or(|expires| expires > now) {
+            continue;
+        }
+        let worker = item.assigned_to.clone().unwrap_or_default();
+        item.transition(QueueStatus::Pending, now, None, Some(format!("Lease of {} expired", worker)));
+        match repos.queue.update(&item).await {
+            Ok(_) => {
+                warn!("Released queue item {} abandoned by {}", item.id, worker);
+                released.push(item.id);
+            }
+            // A heartbeat got in first
+            Err(DatabaseError::Conflict { .. }) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    if !released.is_empty() {
+        scheduler::reschedule(repos, now).await?;
+    }
+    Ok(released)
+}
+
+async fn worker_view(repos: &Repositories, worker: QueueWorker) -> Result<WorkerResponse, ApiError> {
+    let mut items = repos.queue.list_by_assignee(&worker.id).await?;
+    items.sort_by_key(|item| Reverse(item.completed_at.or(item.actual_start)));
+
+    let mut current = Vec::new();
+    let mut completed = Vec::new();
+    for item in &items {
+        match item.status {
+            QueueStatus::InProgress | QueueStatus::Paused => current.push(QueueResponse::from(item)),
+            QueueStatus::Completed => completed.push(QueueResponse::from(item)),
+            _ => {}
+        }
+    }
+
+    Ok(WorkerResponse {
+        worker_id: worker.id,
+        kind: worker.kind,
+        name: worker.name,
+        skills: worker.skills,
+        current,
+        completed,
+    })
+}
+
+pub(crate) async fn get_worker(repos: &Repositories, request: Request) -> ApiResult {
+    let id = request.param("id")?;
+
+    info!("Fetching queue worker {}", id);
+
+    let worker = found(repos.workers.get(id).await)?.unwrap_or_else(|| unregistered(id.to_string()));
+
+    response::ok(&worker_view(repos, worker).await?)
+}
+
+/// Register a staff member or station, or replace their certifications
+pub(crate) async fn put_worker(repos: &Repositories, request: Request) -> ApiResult {
+    let id = request.param("id")?.to_string();
+    let req: WorkerRequest = request.json()?;
+
+    info!("Registering queue worker {}", id);
+
+    let mut skills = req.skills;
+    skills.sort();
+    skills.dedup();
+    let worker = QueueWorker { id, kind: req.kind, name: req.name, skills, updated_at: Utc::now() };
+    let worker = repos.workers.put(&worker).await?;
+
+    response::ok(&worker_view(repos, worker).await?)
+}
@@ -0,0 +1,145 @@
+//! Dependencies between queue items
+//!
+//! An item may list others that must be completed before it can start, e.g. a
+//! customer's `BAG_SHIPMENT_PREP` after every `BAG_CLEANING` holding their
+//! bags. The edges must never form a cycle, or nothing in it could ever start.
+//! Starting by hand and the scheduler agree on what is still in the way
+//! through [`holds_back`].
+
+use std::collections::{HashMap, HashSet};
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use api::ApiError;
+use database::DatabaseError;
+use models::{ManufacturingQueue, QueueStatus};
+
+use crate::Repositories;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DependenciesRequest {
+    /// Replaces the item's dependencies; empty to clear them
+    depends_on: Vec<Uuid>,
+}
+
+impl DependenciesRequest {
+    pub(crate) fn depends_on(&self) -> &[Uuid] {
+        &self.depends_on
+    }
+}
+
+/// The path back to `id` if it came to depend on `depends_on`, following
+/// `graph` from each of them: `[id, .., id]`
+pub(crate) fn find_cycle(graph: &HashMap<Uuid, Vec<Uuid>>, id: Uuid, depends_on: &[Uuid]) -> Option<Vec<Uuid>> {
+    let mut seen = HashSet::new();
+    // Each entry is a path from `id`; depth first, so the first to come back is reported
+    let mut paths: Vec<Vec<Uuid>> = depends_on.iter().rev().map(|&dep| vec![id, dep]).collect();
+    while let Some(path) = paths.pop() {
+        let last = *path.last().unwrap();
+        if last == id {
+            return Some(path);
+        }
+        if !seen.insert(last) {
+            continue;
+        }
+        for &next in graph.get(&last).into_iter().flatten().rev() {
+            let mut longer = path.clone();
+            longer.push(next);
+            paths.push(longer);
+        }
+    }
+    None
+}
+
+/// Fails unless each of `depends_on` exists, isn't `id` itself, and none of
+/// them already depends on `id`, directly or not
+pub(crate) async fn validate(repos: &Repositories, id: Uuid, depends_on: &[Uuid]) -> Result<(), ApiError> {
+    if depends_on.is_empty() {
+        return Ok(());
+    }
+
+    // What `depends_on` waits on, loaded depth first until it leads back to
+    // `id` or runs out
+    let mut graph = HashMap::new();
+    let mut next: Vec<Uuid> = depends_on.iter().rev().copied().collect();
+    while let Some(dep) = next.pop() {
+        if dep == id {
+            break;
+        }
+        if graph.contains_key(&dep) {
+            continue;
+        }
+        let waits_on = match repos.queue.get(dep).await {
+            Ok(item) => item.depends_on,
+            Err(DatabaseError::NotFound(_)) if depends_on.contains(&dep) => {
+                return Err(ApiError::invalid_field("dependsOn", format!("No queue item {}", dep)));
+            }
+            // Gone since it was depended on, so nothing waits behind it
+            Err(DatabaseError::NotFound(_)) => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        next.extend(waits_on.iter().rev());
+        graph.insert(dep, waits_on);
+    }
+
+    if let Some(cycle) = find_cycle(&graph, id, depends_on) {
+        let path: Vec<String> = cycle.iter().map(Uuid::to_string).collect();
+        return Err(ApiError::invalid_field("dependsOn", format!("Would make a cycle: {}", path.join(" → "))));
+    }
+    Ok(())
+}
+
+/// Whether a dependency in `status` holds back the items depending on it:
+/// only while it is open. Completed, cancelled or failed work, like work no
+/// longer on record, won't get any further by waiting for it; a failure that
+/// is retried is open again
+pub(crate) fn holds_back(status: &QueueStatus) -> bool {
+    status.is_open()
+}
+
+/// The dependencies of `item` still holding it back
+pub(crate) async fn open_dependencies(
+    repos: &Repositories,
+    item: &ManufacturingQueue,
+) -> Result<Vec<ManufacturingQueue>, ApiError> {
+    let mut open = Vec::new();
+    for &dep in &item.depends_on {
+        match repos.queue.get(dep).await {
+            Ok(dep) if holds_back(&dep.status) => open.push(dep),
+            Ok(_) => {}
+            // Nothing left to wait for
+            Err(DatabaseError::NotFound(_)) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(open)
+}
+
+/// 409 for starting `item` while `open` aren't done
+pub(crate) fn waiting_on(item: &ManufacturingQueue, open: &[ManufacturingQueue]) -> ApiError {
+    let open: Vec<String> = open.iter().map(|dep| format!("{} ({:?})", dep.id, dep.status)).collect();
+    ApiError::Conflict {
+        message: format!("Queue item {} is waiting on {}", item.id, open.join(", ")),
+        current: serde_json::to_value(item).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_cycle_follows_indirect_dependencies() {
+        let [a, b, c, d] = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        // b waits on c, c on a
+        let graph = HashMap::from([(a, vec![]), (b, vec![c]), (c, vec![a]), (d, vec![])]);
+
+        assert_eq!(find_cycle(&graph, a, &[d, b]), Some(vec![a, b, c, a]));
+        assert_eq!(find_cycle(&graph, a, &[a]), Some(vec![a, a]));
+        assert_eq!(find_cycle(&graph, d, &[b, c]), None);
+        assert_eq!(find_cycle(&graph, a, &[]), None);
+    }
+}
@@ -0,0 +1,825 @@
+use std::sync::LazyLock;
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{Error, LambdaEvent};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use api::{response, ApiError, ApiResult, Request, Role, Router};
+use database::{
+    BagsRepository, DatabaseError, GerminationRecordsRepository, GreenhouseZonesRepository,
+    ManufacturingQueueRepository, PlantsRepository, QueueDurationsRepository, QueueWorkersRepository,
+    SeedStorageRepository, SeedsRepository,
+};
+use models::{
+    ManufacturingQueue, Priority, QueueStatus, QueueType, SeedStorage, SeedStorageGuide, Skill, Species,
+    StorageCondition, StorageLocation, StorageUnit,
+};
+
+mod batches;
+mod claims;
+mod dependencies;
+mod scheduler;
+mod workflow;
+pub use batches::{BatchResponse, CreateBatchRequest};
+pub use claims::{release_abandoned, ClaimRequest, ClaimResponse, WorkerRequest, WorkerResponse};
+pub use dependencies::DependenciesRequest;
+pub use scheduler::QueueSchedule;
+pub use workflow::WorkflowLink;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateQueueRequest {
+    queue_type: QueueType,
+    priority: Priority,
+    scheduled_start: Option<String>,
+    /// Items that must be completed before this one can start
+    #[serde(default)]
+    depends_on: Vec<Uuid>,
+    /// Certifications needed beyond those worked out from what the item handles
+    #[serde(default)]
+    required_skills: Vec<Skill>,
+}
+
+/// Optional body for the status changes; `fail` needs a reason
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueTransitionRequest {
+    reason: Option<String>,
+    /// The station the caller works for, when it holds the item
+    worker_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreSeedRequest {
+    seed_id: Uuid,
+    species: String,
+    facility: String,
+    room: String,
+    unit_number: u32,
+    quantity_grams: Option<f32>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueResponse {
+    queue_id: Uuid,
+    status: QueueStatus,
+    priority: Priority,
+    created_at: String,
+    estimated_completion: Option<String>,
+    depends_on: Vec<Uuid>,
+    batch_id: Option<Uuid>,
+    required_skills: Vec<Skill>,
+    /// The worker who claimed or started it
+    assigned_to: Option<String>,
+    lease_expires_at: Option<String>,
+    /// What completing this item queued next
+    next_steps: Vec<QueueResponse>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageResponse {
+    storage_id: Uuid,
+    seed_id: Uuid,
+    location: String,
+    refrigeration: bool,
+    temperature_range: String,
+    max_storage_days: Option<u32>,
+}
+
+pub struct Repositories {
+    queue: ManufacturingQueueRepository,
+    workers: QueueWorkersRepository,
+    /// Recent durations of each queue type, for the scheduler's estimates
+    durations: QueueDurationsRepository,
+    storage: SeedStorageRepository,
+    /// Read to work out each workflow step's successor
+    seeds: SeedsRepository,
+    bags: BagsRepository,
+    plants: PlantsRepository,
+    zones: GreenhouseZonesRepository,
+    records: GerminationRecordsRepository,
+}
+
+impl Repositories {
+    pub fn new(client: aws_sdk_dynamodb::Client) -> Self {
+        Self {
+            queue: ManufacturingQueueRepository::new(client.clone()),
+            workers: QueueWorkersRepository::new(client.clone()),
+            durations: QueueDurationsRepository::new(client.clone()),
+            storage: SeedStorageRepository::new(client.clone()),
+            seeds: SeedsRepository::new(client.clone()),
+            bags: BagsRepository::new(client.clone()),
+            plants: PlantsRepository::new(client.clone()),
+            zones: GreenhouseZonesRepository::new(client.clone()),
+            records: GerminationRecordsRepository::new(client),
+        }
+    }
+
+    /// Process-local tables, for tests and the dev server
+    #[cfg(any(test, feature = "in-memory"))]
+    pub fn in_memory() -> Self {
+        Self {
+            queue: ManufacturingQueueRepository::in_memory(),
+            workers: QueueWorkersRepository::in_memory(),
+            durations: QueueDurationsRepository::in_memory(),
+            storage: SeedStorageRepository::in_memory(),
+            seeds: SeedsRepository::in_memory(),
+            bags: BagsRepository::in_memory(),
+            plants: PlantsRepository::in_memory(),
+            zones: GreenhouseZonesRepository::in_memory(),
+            records: GerminationRecordsRepository::in_memory(),
+        }
+    }
+}
+
+impl From<&ManufacturingQueue> for QueueResponse {
+    fn from(item: &ManufacturingQueue) -> Self {
+        Self {
+            queue_id: item.id,
+            status: item.status.clone(),
+            priority: item.priority,
+            created_at: item.created_at.to_rfc3339(),
+            estimated_completion: item.estimated_completion.map(|at| at.to_rfc3339()),
+            depends_on: item.depends_on.clone(),
+            batch_id: item.batch_id,
+            required_skills: item.required_skills.clone(),
+            assigned_to: item.assigned_to.clone(),
+            lease_expires_at: item.lease_expires_at.map(|at| at.to_rfc3339()),
+            next_steps: Vec::new(),
+        }
+    }
+}
+
+static ROUTER: LazyLock<Router<Repositories>> = LazyLock::new(|| {
+    Router::new()
+        .post("/queue", create_queue_item)
+        .requires(Role::Staff)
+        .get("/queue", list_queue)
+        .get("/queue/schedule", get_schedule)
+        .post("/queue/claim", claims::claim)
+        .requires(Role::Staff)
+        .get("/queue/workers/{id}", claims::get_worker)
+        .requires(Role::Staff)
+        .put("/queue/workers/{id}", claims::put_worker)
+        .requires(Role::Admin)
+        .post("/queue/batches", batches::create_batch)
+        .requires(Role::Staff)
+        .get("/queue/batches/{id}", batches::get_batch)
+        .requires(Role::Staff)
+        .put("/queue/batches/{id}/start", batches::start_batch)
+        .requires(Role::Staff)
+        .get("/queue/{id}", get_queue_item)
+        .put("/queue/{id}/dependencies", set_dependencies)
+        .requires(Role::Staff)
+        .put("/queue/{id}/heartbeat", claims::heartbeat)
+        .requires(Role::Staff)
+        .put("/queue/{id}/release", claims::release)
+        .requires(Role::Staff)
+        .put("/queue/{id}/start", start_queue_item)
+        .requires(Role::Staff)
+        .put("/queue/{id}/pause", pause_queue_item)
+        .requires(Role::Staff)
+        .put("/queue/{id}/resume", resume_queue_item)
+        .requires(Role::Staff)
+        .put("/queue/{id}/complete", complete_queue_item)
+        .requires(Role::Staff)
+        .put("/queue/{id}/fail", fail_queue_item)
+        .requires(Role::Staff)
+        .put("/queue/{id}/retry", retry_queue_item)
+        .requires(Role::Staff)
+        .put("/queue/{id}/cancel", cancel_queue_item)
+        .requires(Role::Staff)
+        .post("/storage/seeds", store_seed)
+        .get("/storage/seeds", list_seed_storage)
+        .get("/storage/guide/{species}", get_storage_guide)
+        .get("/greenhouse/workflow", list_greenhouse_workflows)
+});
+
+pub async fn function_handler(
+    repos: &Repositories,
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    info!("Processing manufacturing queue request");
+
+    Ok(ROUTER.handle(repos, event.payload).await)
+}
+
+/// The request body as a `T`, or the default when there isn't one
+pub(crate) fn optional_json<T: DeserializeOwned + Default>(request: &Request) -> Result<T, ApiError> {
+    match request.inner().body.as_deref().map(str::trim) {
+        None | Some("") => Ok(T::default()),
+        Some(_) => request.json(),
+    }
+}
+
+async fn create_queue_item(repos: &Repositories, request: Request) -> ApiResult {
+    let req: CreateQueueRequest = request.json()?;
+
+    let queue_id = Uuid::new_v4();
+    let now = chrono::Utc::now();
+
+    let scheduled = req
+        .scheduled_start
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&chrono::Utc));
+
+    let mut queue = ManufacturingQueue {
+        id: queue_id,
+        queue_type: req.queue_type.clone(),
+        priority: req.priority,
+        status: QueueStatus::Pending,
+        created_at: now,
+        scheduled_start: scheduled,
+        actual_start: None,
+        completed_at: None,
+        assigned_to: None,
+        notes: None,
+        estimated_completion: None,
+        version: 0,
+        history: Vec::new(),
+        previous_step: None,
+        depends_on: req.depends_on,
+        batch_id: None,
+        required_skills: Vec::new(),
+        lease_expires_at: None,
+        leased: false,
+    };
+    dependencies::validate(repos, queue_id, &queue.depends_on).await?;
+    let mut skills = claims::required_skills(repos, &queue.queue_type).await?;
+    skills.extend(req.required_skills);
+    skills.sort();
+    skills.dedup();
+    queue.required_skills = skills;
+    if queue.scheduled_start.is_some() {
+        let by = request.principal().ok().map(|principal| principal.user_id);
+        queue.transition(QueueStatus::Scheduled, now, by, None);
+    }
+
+    info!("Created queue item {} with priority {:?}", queue_id, req.priority);
+
+    repos.queue.create(&queue).await?;
+    scheduler::reschedule(repos, now).await?;
+
+    let response = QueueResponse::from(&repos.queue.get(queue_id).await?);
+
+    response::created(&response)
+}
+
+async fn list_queue(repos: &Repositories, request: Request) -> ApiResult {
+    info!("Listing manufacturing queue");
+
+    let page = request.page()?;
+
+    let items = repos.queue.page_all(&page).await?;
+
+    // Storage order; GET /queue/schedule has them in the order they'll run
+
+    // Counts cover the whole queue, not just this page
+    let response = serde_json::json!({
+        "pending_count": repos.queue.count_by_status(&QueueStatus::Pending).await?,
+        "in_progress_count": repos.queue.count_by_status(&QueueStatus::InProgress).await?,
+        "queue_items": items.items,
+        "next_cursor": items.next_cursor,
+    });
+
+    response::ok(&response)
+}
+
+async fn get_queue_item(repos: &Repositories, request: Request) -> ApiResult {
+    let id = request.uuid_param("id")?;
+
+    info!("Fetching queue item {}", id);
+
+    response::ok(&repos.queue.get(id).await?)
+}
+
+/// Replace what the item waits on, before it has started
+async fn set_dependencies(repos: &Repositories, request: Request) -> ApiResult {
+    let id = request.uuid_param("id")?;
+    let req: DependenciesRequest = request.json()?;
+
+    info!("Setting dependencies of queue item {}", id);
+
+    let mut item = repos.queue.get(id).await?;
+    if !matches!(item.status, QueueStatus::Pending | QueueStatus::Scheduled) {
+        return Err(ApiError::Conflict {
+            message: format!("Queue item {} is {:?}; only waiting items take dependencies", id, item.status),
+            current: serde_json::to_value(&item).ok(),
+        });
+    }
+    dependencies::validate(repos, id, req.depends_on()).await?;
+
+    item.depends_on = req.depends_on().to_vec();
+    repos.queue.update(&item).await?;
+    scheduler::reschedule(repos, chrono::Utc::now()).await?;
+
+    response::ok(&QueueResponse::from(&repos.queue.get(id).await?))
+}
+
+async fn start_queue_item(repos: &Repositories, request: Request) -> ApiResult {
+    info!("Starting queue item {}", request.uuid_param("id")?);
+
+    set_queue_status(repos, &request, QueueStatus::InProgress).await
+}
+
+async fn pause_queue_item(repos: &Repositories, request: Request) -> ApiResult {
+    info!("Pausing queue item {}", request.uuid_param("id")?);
+
+    set_queue_status(repos, &request, QueueStatus::Paused).await
+}
+
+async fn resume_queue_item(repos: &Repositories, request: Request) -> ApiResult {
+    info!("Resuming queue item {}", request.uuid_param("id")?);
+
+    set_queue_status(repos, &request, QueueStatus::InProgress).await
+}
+
+async fn complete_queue_item(repos: &Repositories, request: Request) -> ApiResult {
+    info!("Completing queue item {}", request.uuid_param("id")?);
+
+    // Queues the next step of the workflow, if there is one
+    set_queue_status(repos, &request, QueueStatus::Completed).await
+}
+
+async fn fail_queue_item(repos: &Repositories, request: Request) -> ApiResult {
+    info!("Failing queue item {}", request.uuid_param("id")?);
+
+    set_queue_status(repos, &request, QueueStatus::Failed).await
+}
+
+async fn retry_queue_item(repos: &Repositories, request: Request) -> ApiResult {
+    info!("Retrying queue item {}", request.uuid_param("id")?);
+
+    set_queue_status(repos, &request, QueueStatus::Pending).await
+}
+
+async fn cancel_queue_item(repos: &Repositories, request: Request) -> ApiResult {
+    info!("Cancelling queue item {}", request.uuid_param("id")?);
+
+    set_queue_status(repos, &request, QueueStatus::Cancelled).await
+}
+
+async fn get_schedule(repos: &Repositories, _: Request) -> ApiResult {
+    info!("Scheduling manufacturing queue");
+
+    response::ok(&scheduler::schedule(repos, chrono::Utc::now()).await?)
+}
+
+/// Move the item along the transition table, 409 with the current item when
+/// it can't go there from its status
+async fn set_queue_status(repos: &Repositories, request: &Request, status: QueueStatus) -> ApiResult {
+    let id = request.uuid_param("id")?;
+    let req: QueueTransitionRequest = optional_json(request)?;
+    if status == QueueStatus::Failed && req.reason.as_deref().is_none_or(|reason| reason.trim().is_empty()) {
+        return Err(ApiError::invalid_field("reason", "Say why the item failed"));
+    }
+
+    let mut item = repos.queue.get(id).await?;
+    claims::check_holder(repos, request, req.worker_id, &item).await?;
+
+    // Resuming picks up work that was already free to start, by whoever started it
+    if status == QueueStatus::InProgress && matches!(item.status, QueueStatus::Pending | QueueStatus::Scheduled) {
+        let open = dependencies::open_dependencies(repos, &item).await?;
+        if !open.is_empty() {
+            return Err(dependencies::waiting_on(&item, &open));
+        }
+        item.assigned_to = Some(claims::starting_worker(repos, request, &item).await?.id);
+    }
+
+    let now = chrono::Utc::now();
+    let by = request.principal().ok().map(|principal| principal.user_id);
+    let resuming = item.status == QueueStatus::Paused;
+    if !item.transition(status.clone(), now, by, req.reason) {
+        return Err(ApiError::Conflict {
+            message: format!("Queue item {} is {:?} and cannot move to {:?}", id, item.status, status),
+            current: serde_json::to_value(&item).ok(),
+        });
+    }
+    // Pausing gave up the lease; a claim's holder needs a new one to carry on
+    if resuming && item.status == QueueStatus::InProgress && item.leased {
+        item.lease_expires_at = Some(now + chrono::Duration::minutes(claims::LEASE_MINUTES));
+    }
+    let next = if item.status == QueueStatus::Completed {
+        workflow::next_steps(repos, &item, now).await?
+    } else {
+        workflow::NextSteps::default()
+    };
+    // The next steps go in before the item is completed, so a completion that
+    // fails part way is sent again rather than leaving the pipeline cut short
+    for queued in &next.queued {
+        match repos.queue.create(queued).await {
+            Ok(()) => info!("Queued {} {} after {}", queued.queue_type.name(), queued.id, id),
+            Err(DatabaseError::AlreadyExists(_)) => {
+                info!("{} {} was already queued after {}", queued.queue_type.name(), queued.id, id)
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    for joined in &next.joined {
+        info!("Added the work of {} to {} {}", id, joined.queue_type.name(), joined.id);
+        repos.queue.update(joined).await?;
+    }
+    let item = repos.queue.update(&item).await?;
+    if item.status == QueueStatus::Completed {
+        // Only sharpens later estimates, so isn't worth failing a completion over
+        if let Err(err) = scheduler::record_duration(repos, &item).await {
+            warn!("Couldn't record how long queue item {} took: {}", id, err);
+        }
+    }
+
+    // Freeing or taking up staff moves everyone else's estimate
+    scheduler::reschedule(repos, now).await?;
+
+    let mut response = QueueResponse::from(&repos.queue.get(id).await?);
+    for next in next.queued.iter().chain(&next.joined) {
+        response.next_steps.push(QueueResponse::from(&repos.queue.get(next.id).await?));
+    }
+    response::ok(&response)
+}
+
+async fn store_seed(repos: &Repositories, request: Request) -> ApiResult {
+    let req: StoreSeedRequest = request.json()?;
+
+    let storage_id = Uuid::new_v4();
+
+    // Get storage requirements for species
+    let species = Species::resolve(&req.species).ok_or_else(|| ApiError::unknown_species("species", &req.species))?;
+    let guide = SeedStorageGuide::for_species(species);
+
+    let storage = SeedStorage {
+        id: storage_id,
+        seed_id: req.seed_id,
+        storage_location: StorageLocation {
+            facility: req.facility,
+            room: req.room,
+            unit: if guide.storage_requirements.refrigeration_required {
+                StorageUnit::ColdRefrigerator { unit_number: req.unit_number }
+            } else {
+                StorageUnit::RoomTemp { cabinet_number: req.unit_number }
+            },
+            shelf: None,
+            bin: None,
+        },
+        storage_requirements: guide.storage_requirements.clone(),
+        stored_at: chrono::Utc::now(),
+        last_checked: chrono::Utc::now(),
+        condition: StorageCondition::Excellent,
+        quantity_grams: req.quantity_grams,
+        viability_tested: false,
+        estimated_viability_percent: None,
+    };
+
+    info!("Stored seed {} ({}) in {:?}", req.seed_id, species.id, storage.storage_location.unit);
+
+    repos.storage.create(&storage).await?;
+
+    // Completing the seeds' SeedIntake queues their germination for after quarantine
+
+    let temp_range = format!(
+        "{}°C - {}°C (optimal: {}°C)",
+        storage.storage_requirements.temperature_celsius.min,
+        storage.storage_requirements.temperature_celsius.max,
+        storage.storage_requirements.temperature_celsius.optimal
+    );
+
+    let response = StorageResponse {
+        storage_id: storage.id,
+        seed_id: storage.seed_id,
+        location: format!(
+            "{} / {} / {:?}",
+            storage.storage_location.facility, storage.storage_location.room, storage.storage_location.unit
+        ),
+        refrigeration: storage.storage_requirements.refrigeration_required,
+        temperature_range: temp_range,
+        max_storage_days: storage.storage_requirements.max_storage_days,
+    };
+
+    response::created(&response)
+}
+
+async fn list_seed_storage(repos: &Repositories, _: Request) -> ApiResult {
+    info!("Listing seed storage");
+
+    let storage = repos.storage.list_all().await?;
+    let refrigerated = storage.iter().filter(|s| s.storage_requirements.refrigeration_required).count();
+
+    let response = serde_json::json!({
+        "total_seeds": storage.len(),
+        "refrigerated_count": refrigerated,
+        "storage_locations": storage,
+    });
+
+    response::ok(&response)
+}
+
+async fn get_storage_guide(_: &Repositories, request: Request) -> ApiResult {
+    let species = request.param("species")?;
+
+    info!("Fetching storage guide for {}", species);
+
+    let species =
+        Species::resolve(species).ok_or_else(|| ApiError::not_found(format!("Unknown species '{}'", species)))?;
+    let guide = SeedStorageGuide::for_species(species);
+
+    response::ok(&guide)
+}
+
+async fn list_greenhouse_workflows(repos: &Repositories, _: Request) -> ApiResult {
+    info!("Listing greenhouse workflows");
+
+    let mut open = Vec::new();
+    for status in [QueueStatus::Pending, QueueStatus::Scheduled, QueueStatus::InProgress, QueueStatus::Paused] {
+        open.extend(repos.queue.list_by_status(&status).await?);
+    }
+    let pending_transfers =
+        open.iter().filter(|item| matches!(item.queue_type, QueueType::GreenhouseTransfer { .. })).count();
+    // Germinations still waiting out their seeds' quarantine
+    let quarantine_releases = open
+        .iter()
+        .filter(|item| item.status == QueueStatus::Scheduled)
+        .filter(|item| matches!(item.queue_type, QueueType::GerminationScheduled { .. }))
+        .count();
+
+    let response = serde_json::json!({
+        "workflows": workflow::PIPELINE,
+        "pending_transfers": pending_transfers,
+        "quarantine_releases": quarantine_releases,
+    });
+
+    response::ok(&response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_lambda_events::encodings::Body;
+    use lambda_runtime::Context;
+
+    /// A request from a staff member, as API Gateway's authorizer would pass it
+    fn event(method: &str, path: &str, body: Option<serde_json::Value>) -> LambdaEvent<ApiGatewayProxyRequest> {
+        event_as(Uuid::new_v4(), "staff", method, path, body)
+    }
+
+    fn event_as(
+        user: Uuid,
+        groups: &str,
+        method: &str,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> LambdaEvent<ApiGatewayProxyRequest> {
+        let mut request = ApiGatewayProxyRequest {
+            http_method: method.parse().unwrap(),
+            path: Some(path.to_string()),
+            body: body.map(|b| b.to_string()),
+            ..Default::default()
+        };
+        request
+            .request_context
+            .authorizer
+            .fields
+            .insert("claims".to_string(), serde_json::json!({ "sub": user.to_string(), "cognito:groups": groups }));
+        LambdaEvent::new(request, Context::default())
+    }
+
+    fn body(response: &ApiGatewayProxyResponse) -> serde_json::Value {
+        match &response.body {
+            Some(Body::Text(text)) => serde_json::from_str(text).unwrap(),
+            other => panic!("unexpected body {:?}", other),
+        }
+    }
+
+    async fn queue(repos: &Repositories, queue_type: serde_json::Value, depends_on: &[&str]) -> String {
+        let response = function_handler(
+            repos,
+            event(
+                "POST",
+                "/queue",
+                Some(serde_json::json!({ "queueType": queue_type, "priority": "normal", "dependsOn": depends_on })),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status_code, 201, "{:?}", response.body);
+        body(&response)["queueId"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_dependencies_and_batches() {
+        let repos = Repositories::in_memory();
+        let customer_id = Uuid::new_v4();
+        let shipment = serde_json::json!({ "BAG_SHIPMENT_PREP": { "bag_ids": [], "customer_id": customer_id } });
+
+        let cleaning = queue(&repos, serde_json::json!({ "BAG_CLEANING": { "bag_ids": [] } }), &[]).await;
+        let first = queue(&repos, shipment.clone(), &[&cleaning]).await;
+        let second = queue(&repos, shipment.clone(), &[]).await;
+        // Only staff queue work, so only staff say what it waits on
+        let request = serde_json::json!({ "queueType": shipment, "priority": "urgent", "dependsOn": [cleaning] });
+        let customer = event_as(Uuid::new_v4(), "customer", "POST", "/queue", Some(request));
+        assert_eq!(function_handler(&repos, customer).await.unwrap().status_code, 403);
+
+        // The cleaning can't wait on the shipment that waits on it
+        let cycle = function_handler(
+            &repos,
+            event(
+                "PUT",
+                &format!("/queue/{}/dependencies", cleaning),
+                Some(serde_json::json!({ "dependsOn": [first] })),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(cycle.status_code, 400);
+        let dependencies = |user: Uuid, groups: &str, depends_on: serde_json::Value| {
+            let path = format!("/queue/{}/dependencies", second);
+            function_handler(&repos, event_as(user, groups, "PUT", &path, Some(depends_on)))
+        };
+        let missing = serde_json::json!({ "dependsOn": [Uuid::new_v4()] });
+        assert_eq!(dependencies(Uuid::new_v4(), "staff", missing).await.unwrap().status_code, 400);
+        let cleared = serde_json::json!({ "dependsOn": [] });
+        assert_eq!(dependencies(Uuid::new_v4(), "customer", cleared.clone()).await.unwrap().status_code, 403);
+        assert_eq!(dependencies(Uuid::new_v4(), "staff", cleared).await.unwrap().status_code, 200);
+
+        // Batched shipments start together, once the cleaning is done
+        let batches = serde_json::json!({ "queueIds": [first, second] });
+        let customer = event_as(Uuid::new_v4(), "customer", "POST", "/queue/batches", Some(batches));
+        assert_eq!(function_handler(&repos, customer).await.unwrap().status_code, 403);
+        let batch = function_handler(
+            &repos,
+            event("POST", "/queue/batches", Some(serde_json::json!({ "queueIds": [first, second] }))),
+        )
+        .await
+        .unwrap();
+        assert_eq!(batch.status_code, 201);
+        let batch_id = body(&batch)["batchId"].as_str().unwrap().to_string();
+        let start = || event("PUT", &format!("/queue/batches/{}/start", batch_id), None);
+
+        let blocked = function_handler(&repos, start()).await.unwrap();
+        assert_eq!(blocked.status_code, 409);
+        assert_eq!(repos.queue.get(second.parse().unwrap()).await.unwrap().status, QueueStatus::Pending);
+
+        let cleaner = Uuid::new_v4();
+        for step in ["start", "complete"] {
+            let step = event_as(cleaner, "staff", "PUT", &format!("/queue/{}/{}", cleaning, step), None);
+            assert_eq!(function_handler(&repos, step).await.unwrap().status_code, 200);
+        }
+        let started = function_handler(&repos, start()).await.unwrap();
+        assert_eq!(started.status_code, 200);
+        let items = body(&started)["items"].clone();
+        let statuses: Vec<&str> =
+            items.as_array().unwrap().iter().map(|item| item["status"].as_str().unwrap()).collect();
+        assert_eq!(statuses, ["IN_PROGRESS", "IN_PROGRESS"]);
+
+        // Cancelled work will never complete, so nothing waits on it
+        let dropped = queue(&repos, serde_json::json!({ "BAG_CLEANING": { "bag_ids": [] } }), &[]).await;
+        let shipment = serde_json::json!({ "BAG_SHIPMENT_PREP": { "bag_ids": [], "customer_id": customer_id } });
+        let after = queue(&repos, shipment, &[&dropped]).await;
+        let cancel = event("PUT", &format!("/queue/{}/cancel", dropped), None);
+        assert_eq!(function_handler(&repos, cancel).await.unwrap().status_code, 200);
+        let start = event("PUT", &format!("/queue/{}/start", after), None);
+        assert_eq!(function_handler(&repos, start).await.unwrap().status_code, 200);
+    }
+
+    #[tokio::test]
+    async fn test_claims_follow_skills_and_leases() {
+        let repos = Repositories::in_memory();
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let send = |user: Uuid, method: &str, path: &str, body: Option<serde_json::Value>| {
+            function_handler(&repos, event_as(user, "staff", method, path, body))
+        };
+        let create = |priority: &str, skills: &[&str]| {
+            let body = serde_json::json!({
+                "queueType": { "BAG_CLEANING": { "bag_ids": [] } },
+                "priority": priority,
+                "requiredSkills": skills,
+            });
+            function_handler(&repos, event("POST", "/queue", Some(body)))
+        };
+        let quarantined = body(&create("urgent", &["quarantine"]).await.unwrap())["queueId"].clone();
+        let plain = body(&create("normal", &[]).await.unwrap())["queueId"].clone();
+
+        // Uncertified, Alice gets the plain item although the other is more urgent
+        let claim = send(alice, "POST", "/queue/claim", None).await.unwrap();
+        assert_eq!(claim.status_code, 200);
+        let claim = body(&claim);
+        assert_eq!(claim["items"][0]["queueId"], plain);
+        assert_eq!(claim["items"][0]["assignedTo"], alice.to_string());
+        assert!(claim["leaseExpiresAt"].is_string());
+
+        let start = send(alice, "PUT", &format!("/queue/{}/start", quarantined.as_str().unwrap()), None);
+        assert_eq!(start.await.unwrap().status_code, 403);
+
+        let register = serde_json::json!({ "kind": "staff", "name": "Bob", "skills": ["quarantine"] });
+        let admin = event_as(Uuid::new_v4(), "admin", "PUT", &format!("/queue/workers/{}", bob), Some(register));
+        assert_eq!(function_handler(&repos, admin).await.unwrap().status_code, 200);
+
+        // A certified station doesn't lend its certification to Alice
+        let station = serde_json::json!({ "kind": "station", "name": "Wash bench", "skills": ["quarantine"] });
+        let admin = event_as(Uuid::new_v4(), "admin", "PUT", "/queue/workers/wash-bench", Some(station));
+        assert_eq!(function_handler(&repos, admin).await.unwrap().status_code, 200);
+        let for_station = Some(serde_json::json!({ "workerId": "wash-bench" }));
+        let claim = body(&send(alice, "POST", "/queue/claim", for_station).await.unwrap());
+        assert_eq!(claim["items"], serde_json::json!([]));
+
+        let claim = body(&send(bob, "POST", "/queue/claim", None).await.unwrap());
+        assert_eq!(claim["items"][0]["queueId"], quarantined);
+
+        // Only the holder keeps the lease alive
+        let id: Uuid = quarantined.as_str().unwrap().parse().unwrap();
+        let heartbeat = format!("/queue/{}/heartbeat", id);
+        assert_eq!(send(alice, "PUT", &heartbeat, None).await.unwrap().status_code, 409);
+        assert_eq!(send(bob, "PUT", &heartbeat, None).await.unwrap().status_code, 200);
+
+        // Pausing gives up the lease and resuming takes a new one
+        let paused = body(&send(bob, "PUT", &format!("/queue/{}/pause", id), None).await.unwrap());
+        assert!(paused["leaseExpiresAt"].is_null());
+        let resumed = body(&send(bob, "PUT", &format!("/queue/{}/resume", id), None).await.unwrap());
+        assert!(resumed["leaseExpiresAt"].is_string());
+
+        // Bob walks away; once the lease runs out the item is free again
+        let now = chrono::Utc::now();
+        let mut item = repos.queue.get(id).await.unwrap();
+        item.lease_expires_at = Some(now - chrono::Duration::minutes(1));
+        repos.queue.update(&item).await.unwrap();
+        assert_eq!(release_abandoned(&repos, now).await.unwrap(), [id]);
+        let item = repos.queue.get(id).await.unwrap();
+        assert_eq!((item.status, item.assigned_to), (QueueStatus::Pending, None));
+
+        let complete = format!("/queue/{}/complete", plain.as_str().unwrap());
+        assert_eq!(send(alice, "PUT", &complete, None).await.unwrap().status_code, 200);
+        let view = body(&send(bob, "GET", &format!("/queue/workers/{}", alice), None).await.unwrap());
+        assert_eq!(view["current"], serde_json::json!([]));
+        assert_eq!(view["completed"][0]["queueId"], plain);
+    }
+
+    #[tokio::test]
+    async fn test_completing_again_after_a_partial_failure_queues_each_step_once() {
+        let repos = Repositories::in_memory();
+        let bag = models::Bag {
+            id: Uuid::new_v4(),
+            original_owner_id: Some(Uuid::new_v4()),
+            current_status: models::BagStatus::Cleaning,
+            bag_type: models::BagType::Paper,
+            condition: models::BagCondition::Good,
+            received_at: chrono::Utc::now(),
+            cleaned_at: None,
+            last_updated: chrono::Utc::now(),
+            contains_seeds: false,
+            seed_ids: Vec::new(),
+            version: 0,
+        };
+        repos.bags.create(&bag).await.unwrap();
+        let id = queue(&repos, serde_json::json!({ "BAG_CLEANING": { "bag_ids": [bag.id] } }), &[]).await;
+        let cleaner = Uuid::new_v4();
+        let start = event_as(cleaner, "staff", "PUT", &format!("/queue/{}/start", id), None);
+        assert_eq!(function_handler(&repos, start).await.unwrap().status_code, 200);
+
+        // An earlier attempt queued the bag return, then failed before completing the cleaning
+        let now = chrono::Utc::now();
+        let mut completed = repos.queue.get(id.parse().unwrap()).await.unwrap();
+        assert!(completed.transition(QueueStatus::Completed, now, None, None));
+        let earlier = workflow::next_steps(&repos, &completed, now).await.unwrap();
+        repos.queue.create(&earlier.queued[0]).await.unwrap();
+
+        let complete = event_as(cleaner, "staff", "PUT", &format!("/queue/{}/complete", id), None);
+        let response = function_handler(&repos, complete).await.unwrap();
+        assert_eq!(response.status_code, 200);
+        assert_eq!(body(&response)["nextSteps"][0]["queueId"], earlier.queued[0].id.to_string());
+        let returns = repos.queue.list_by_status(&QueueStatus::Pending).await.unwrap();
+        assert_eq!(returns.len(), 1);
+        assert_eq!(
+            returns[0].queue_type,
+            QueueType::BagShipmentPrep { bag_ids: vec![bag.id], customer_id: bag.original_owner_id.unwrap() }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_only_the_holder_or_an_admin_changes_held_work() {
+        let repos = Repositories::in_memory();
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let id = queue(&repos, serde_json::json!({ "BAG_CLEANING": { "bag_ids": [] } }), &[]).await;
+        let send = |user: Uuid, groups: &str, step: &str| {
+            function_handler(&repos, event_as(user, groups, "PUT", &format!("/queue/{}/{}", id, step), None))
+        };
+
+        assert_eq!(send(alice, "staff", "start").await.unwrap().status_code, 200);
+        assert_eq!(send(bob, "staff", "complete").await.unwrap().status_code, 403);
+        assert_eq!(send(Uuid::new_v4(), "customer", "pause").await.unwrap().status_code, 403);
+        assert_eq!(send(Uuid::new_v4(), "admin", "pause").await.unwrap().status_code, 200);
+        assert_eq!(send(bob, "staff", "resume").await.unwrap().status_code, 403);
+
+        // Started by hand, it has no lease to renew, so it stays with Alice
+        let resumed = body(&send(alice, "staff", "resume").await.unwrap());
+        assert_eq!(resumed["assignedTo"], alice.to_string());
+        assert!(resumed["leaseExpiresAt"].is_null());
+        let later = chrono::Utc::now() + chrono::Duration::minutes(claims::LEASE_MINUTES + 1);
+        assert!(release_abandoned(&repos, later).await.unwrap().is_empty());
+        assert_eq!(send(alice, "staff", "complete").await.unwrap().status_code, 200);
+    }
+}
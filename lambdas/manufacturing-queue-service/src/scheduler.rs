@@ -0,0 +1,486 @@
+//! Queue scheduling
+//!
+//! Each staff member, as they come free, takes the highest priority item
+//! that is due, oldest first; an item is due once its scheduled start has
+//! passed (items without one are due now) and the items it depends on are
+//! done. One scheduled for later waits for its start without holding anyone
+//! before then. A `Low` item that has waited `LOW_PRIORITY_PROMOTION_HOURS`
+//! is treated as `Normal`, so a steady stream of normal work can't starve it.
+//! Estimated completions come from playing the queue out on the staff's
+//! capacity, each item taking the median of its type's recent durations.
+//! [`reschedule`] stores them on the items; it runs whenever an item is
+//! created, started, completed or cancelled.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use tracing::warn;
+use uuid::Uuid;
+
+use api::ApiError;
+use database::DatabaseError;
+use models::{ManufacturingQueue, Priority, QueueStatus, QueueType, QueueTypeDurations};
+
+use crate::{dependencies, Repositories};
+
+/// Environment variable with the number of staff working the queue at once
+pub const STAFF_CAPACITY_VAR: &str = "MANUFACTURING_STAFF_CAPACITY";
+const DEFAULT_STAFF_CAPACITY: usize = 2;
+/// How long a `Low` item waits before it is scheduled as `Normal`
+const LOW_PRIORITY_PROMOTION_HOURS: i64 = 48;
+/// Recent completions per type that estimate its duration
+const HISTORY_PER_TYPE: usize = 20;
+
+/// One item's place in the schedule
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledItem {
+    queue_id: Uuid,
+    queue_type: QueueType,
+    status: QueueStatus,
+    priority: Priority,
+    /// `priority`, or `Normal` for a `Low` item that has waited too long
+    effective_priority: Priority,
+    scheduled_start: Option<DateTime<Utc>>,
+    /// `None` while paused or blocked by a dependency that isn't on its way
+    estimated_start: Option<DateTime<Utc>>,
+    estimated_completion: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueSchedule {
+    computed_at: DateTime<Utc>,
+    staff_capacity: usize,
+    /// In progress first, then waiting items in the order they will run, then
+    /// blocked and paused ones
+    items: Vec<ScheduledItem>,
+}
+
+impl QueueSchedule {
+    /// Waiting items that have an estimated start, in the order they will run
+    pub(crate) fn run_order(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.items
+            .iter()
+            .filter(|item| matches!(item.status, QueueStatus::Pending | QueueStatus::Scheduled))
+            .filter(|item| item.estimated_start.is_some())
+            .map(|item| item.queue_id)
+    }
+}
+
+/// How long each queue type takes
+pub(crate) struct Durations(HashMap<String, Duration>);
+
+impl Durations {
+    /// The median of each type's most recent completions
+    pub(crate) fn from_recent(types: &[QueueTypeDurations]) -> Self {
+        let medians = types
+            .iter()
+            .filter(|durations| !durations.recent_seconds.is_empty())
+            .map(|durations| {
+                let mut recent: Vec<i64> = durations.recent_seconds.iter().take(HISTORY_PER_TYPE).copied().collect();
+                recent.sort();
+                (durations.queue_type.clone(), Duration::seconds(recent[recent.len() / 2]))
+            })
+            .collect();
+        Self(medians)
+    }
+
+    /// Falls back to a typical duration for types with no history yet
+    pub(crate) fn of(&self, queue_type: &QueueType) -> Duration {
+        self.0.get(queue_type.name()).copied().unwrap_or_else(|| default_duration(queue_type))
+    }
+}
+
+/// Time spent in progress on the final attempt, leaving out pauses; items
+/// from before transitions were recorded count from start to finish
+fn worked(item: &ManufacturingQueue) -> Option<Duration> {
+    if item.history.is_empty() {
+        return Some(item.completed_at? - item.actual_start?);
+    }
+
+    let attempt = item.history.iter().rposition(|change| change.to == QueueStatus::Pending).map_or(0, |i| i + 1);
+    let mut worked = Duration::zero();
+    let mut since = None;
+    for change in &item.history[attempt..] {
+        if let Some(started) = since.take() {
+            worked += change.at - started;
+        }
+        if change.to == QueueStatus::InProgress {
+            since = Some(change.at);
+        }
+    }
+    Some(worked)
+}
+
+fn default_duration(queue_type: &QueueType) -> Duration {
+    Duration::minutes(match queue_type {
+        QueueType::SeedIntake { .. } => 30,
+        QueueType::GerminationScheduled { .. } => 45,
+        QueueType::GreenhouseTransfer { .. } => 60,
+        QueueType::ShipmentPrep { .. } => 40,
+        QueueType::BagCleaning { .. } => 20,
+        QueueType::BagShipmentPrep { .. } => 25,
+        QueueType::GerminationReview { .. } => 15,
+    })
+}
+
+pub(crate) fn staff_capacity() -> usize {
+    std::env::var(STAFF_CAPACITY_VAR)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|&capacity| capacity > 0)
+        .unwrap_or(DEFAULT_STAFF_CAPACITY)
+}
+
+/// `Low` items that have waited long enough compete as `Normal`
+pub(crate) fn effective_priority(item: &ManufacturingQueue, now: DateTime<Utc>) -> Priority {
+    if item.priority == Priority::Low && now - item.created_at >= Duration::hours(LOW_PRIORITY_PROMOTION_HOURS) {
+        Priority::Normal
+    } else {
+        item.priority
+    }
+}
+
+/// Play out the open items: in-progress ones hold staff until their expected
+/// finish, then whoever is free first takes the first item in run order that
+/// is due by then, or waits for the next item to come due. An item is due no
+/// earlier than its scheduled start or its dependencies' finish; one
+/// depending on an item that is paused, or waits on one, gets no estimate.
+/// Dependencies not among `items` hold nothing back, as [`dependencies::holds_back`] has it
+pub(crate) fn plan(
+    items: Vec<ManufacturingQueue>,
+    durations: &Durations,
+    capacity: usize,
+    now: DateTime<Utc>,
+) -> QueueSchedule {
+    let holding: HashSet<Uuid> =
+        items.iter().filter(|item| dependencies::holds_back(&item.status)).map(|item| item.id).collect();
+    let (mut running, rest): (Vec<_>, Vec<_>) =
+        items.into_iter().partition(|item| item.status == QueueStatus::InProgress);
+    let (mut paused, mut waiting): (Vec<_>, Vec<_>) =
+        rest.into_iter().partition(|item| item.status == QueueStatus::Paused);
+    running.sort_by_key(|item| item.actual_start);
+    waiting.sort_by_key(|item| {
+        (
+            Reverse(effective_priority(item, now)),
+            item.scheduled_start.unwrap_or(DateTime::<Utc>::MIN_UTC),
+            item.created_at,
+        )
+    });
+    paused.sort_by_key(|item| item.created_at);
+
+    let entry =
+        |item: ManufacturingQueue, start: Option<DateTime<Utc>>, completion: Option<DateTime<Utc>>| ScheduledItem {
+            queue_id: item.id,
+            effective_priority: effective_priority(&item, now),
+            queue_type: item.queue_type,
+            status: item.status,
+            priority: item.priority,
+            scheduled_start: item.scheduled_start,
+            estimated_start: start,
+            estimated_completion: completion,
+        };
+
+    let mut items = Vec::new();
+    // Estimated finish of everything placed so far, for its dependents
+    let mut finishes: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
+    // When each staff member is next free; with more running than staff, the
+    // ones finishing last are what the queue waits on
+    let mut free_at = Vec::new();
+    for item in running {
+        let start = item.actual_start.unwrap_or(now);
+        let finish = (start + durations.of(&item.queue_type)).max(now);
+        free_at.push(finish);
+        finishes.insert(item.id, finish);
+        items.push(entry(item, Some(start), Some(finish)));
+    }
+    free_at.sort();
+    free_at.drain(..free_at.len().saturating_sub(capacity));
+    free_at.resize(capacity, now);
+
+    // Only items whose dependencies are all placed can be placed
+    let ready = |item: &ManufacturingQueue, finishes: &HashMap<Uuid, DateTime<Utc>>| {
+        item.depends_on.iter().all(|dep| !holding.contains(dep) || finishes.contains_key(dep))
+    };
+    let due = |item: &ManufacturingQueue, finishes: &HashMap<Uuid, DateTime<Utc>>| {
+        item.depends_on
+            .iter()
+            .filter_map(|dep| finishes.get(dep))
+            .copied()
+            .chain(item.scheduled_start)
+            .fold(now, DateTime::max)
+    };
+    loop {
+        let placeable: Vec<(usize, DateTime<Utc>)> = waiting
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| ready(item, &finishes))
+            .map(|(i, item)| (i, due(item, &finishes)))
+            .collect();
+        let Some(first_due) = placeable.iter().map(|(_, due)| *due).min() else {
+            break;
+        };
+        let (slot, free) = free_at.iter().copied().enumerate().min_by_key(|(_, free)| *free).unwrap();
+        let start = free.max(first_due);
+        let (next, _) = placeable.into_iter().find(|(_, due)| *due <= start).unwrap();
+
+        let item = waiting.remove(next);
+        let finish = start + durations.of(&item.queue_type);
+        free_at[slot] = finish;
+        finishes.insert(item.id, finish);
+        items.push(entry(item, Some(start), Some(finish)));
+    }
+
+    // Waiting on something paused
+    items.extend(waiting.into_iter().map(|item| entry(item, None, None)));
+    items.extend(paused.into_iter().map(|item| entry(item, None, None)));
+
+    QueueSchedule { computed_at: now, staff_capacity: capacity, items }
+}
+
+/// Add a completed item's time in progress to its type's recent durations
+pub(crate) async fn record_duration(repos: &Repositories, item: &ManufacturingQueue) -> Result<(), ApiError> {
+    let Some(worked) = worked(item).filter(|worked| worked.num_seconds() > 0) else {
+        return Ok(());
+    };
+    let name = item.queue_type.name();
+    let mut durations = match repos.durations.get(name).await {
+        Ok(durations) => durations,
+        Err(DatabaseError::NotFound(_)) => {
+            QueueTypeDurations { queue_type: name.to_string(), recent_seconds: Vec::new(), version: 0 }
+        }
+        Err(err) => return Err(err.into()),
+    };
+    durations.recent_seconds.insert(0, worked.num_seconds());
+    durations.recent_seconds.truncate(HISTORY_PER_TYPE);
+
+    match repos.durations.put(&durations).await {
+        Ok(_) => Ok(()),
+        // Losing one run to a concurrent completion only loosens the estimate
+        Err(DatabaseError::Conflict { .. } | DatabaseError::AlreadyExists(_)) => {
+            warn!("Durations of {} changed while recording queue item {}", name, item.id);
+            Ok(())
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// The open items and the recent durations of their types
+async fn load(repos: &Repositories) -> Result<(Vec<ManufacturingQueue>, Durations), ApiError> {
+    let mut open = Vec::new();
+    for status in [QueueStatus::Pending, QueueStatus::Scheduled, QueueStatus::InProgress, QueueStatus::Paused] {
+        open.extend(repos.queue.list_by_status(&status).await?);
+    }
+
+    let names: HashSet<&'static str> = open.iter().map(|item| item.queue_type.name()).collect();
+    let mut recent = Vec::new();
+    for name in names {
+        match repos.durations.get(name).await {
+            Ok(durations) => recent.push(durations),
+            Err(DatabaseError::NotFound(_)) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok((open, Durations::from_recent(&recent)))
+}
+
+/// The current schedule, without storing it
+pub(crate) async fn schedule(repos: &Repositories, now: DateTime<Utc>) -> Result<QueueSchedule, ApiError> {
+    let (open, durations) = load(repos).await?;
+
+    Ok(plan(open, &durations, staff_capacity(), now))
+}
+
+/// Recompute the schedule and store each open item's estimated completion
+/// where it changed, against the version the schedule was computed from
+pub(crate) async fn reschedule(repos: &Repositories, now: DateTime<Utc>) -> Result<QueueSchedule, ApiError> {
+    let (open, durations) = load(repos).await?;
+    let mut loaded: HashMap<Uuid, ManufacturingQueue> = open.iter().map(|item| (item.id, item.clone())).collect();
+    let schedule = plan(open, &durations, staff_capacity(), now);
+
+    for scheduled in &schedule.items {
+        let Some(mut item) = loaded.remove(&scheduled.queue_id) else {
+            continue;
+        };
+        if item.estimated_completion == scheduled.estimated_completion {
+            continue;
+        }
+        item.estimated_completion = scheduled.estimated_completion;
+        match repos.queue.update(&item).await {
+            Ok(_) => {}
+            // Whoever changed it rescheduled too
+            Err(DatabaseError::Conflict { .. }) => warn!("Queue item {} changed while rescheduling", item.id),
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(schedule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(priority: Priority, status: QueueStatus, created_hours_ago: i64, now: DateTime<Utc>) -> ManufacturingQueue {
+        ManufacturingQueue {
+            id: Uuid::new_v4(),
+            queue_type: QueueType::BagCleaning { bag_ids: Vec::new() },
+            priority,
+            status,
+            created_at: now - Duration::hours(created_hours_ago),
+            scheduled_start: None,
+            actual_start: None,
+            completed_at: None,
+            assigned_to: None,
+            notes: None,
+            estimated_completion: None,
+            version: 0,
+            history: Vec::new(),
+            previous_step: None,
+            depends_on: Vec::new(),
+            batch_id: None,
+            required_skills: Vec::new(),
+            lease_expires_at: None,
+            leased: false,
+        }
+    }
+
+    #[test]
+    fn test_plan_orders_by_priority_and_promotes_starved_low_items() {
+        let now = Utc::now();
+        let fresh_low = item(Priority::Low, QueueStatus::Pending, 1, now);
+        let starved_low = item(Priority::Low, QueueStatus::Pending, 72, now);
+        let normal = item(Priority::Normal, QueueStatus::Pending, 2, now);
+        let urgent = item(Priority::Urgent, QueueStatus::Pending, 0, now);
+        let mut later = item(Priority::Urgent, QueueStatus::Pending, 5, now);
+        later.scheduled_start = Some(now + Duration::hours(3));
+        let ids = [urgent.id, starved_low.id, normal.id, fresh_low.id, later.id];
+
+        let items = vec![fresh_low, starved_low, normal, later, urgent];
+        let schedule = plan(items, &Durations(HashMap::new()), 1, now);
+        let order: Vec<Uuid> = schedule.items.iter().map(|item| item.queue_id).collect();
+        assert_eq!(order, ids);
+        assert_eq!(schedule.items[1].effective_priority, Priority::Normal);
+        assert_eq!(schedule.items[4].estimated_start, Some(now + Duration::hours(3)));
+    }
+
+    #[test]
+    fn test_plan_fills_staff_with_due_work_before_scheduled_work() {
+        let now = Utc::now();
+        let mut scheduled = item(Priority::Urgent, QueueStatus::Pending, 1, now);
+        scheduled.scheduled_start = Some(now + Duration::hours(1));
+        let due = item(Priority::Low, QueueStatus::Pending, 1, now);
+        let ids = [due.id, scheduled.id];
+
+        // One staff member cleans the due bags now rather than idling until
+        // the urgent item's start
+        let items = vec![scheduled, due];
+        let schedule = plan(items, &Durations(HashMap::new()), 1, now);
+        let order: Vec<Uuid> = schedule.items.iter().map(|item| item.queue_id).collect();
+        assert_eq!(order, ids);
+        let starts: Vec<DateTime<Utc>> = schedule.items.iter().map(|item| item.estimated_start.unwrap()).collect();
+        assert_eq!(starts, [now, now + Duration::hours(1)]);
+    }
+
+    #[test]
+    fn test_plan_estimates_from_history_and_capacity() {
+        let now = Utc::now();
+        let durations = Durations::from_recent(&[QueueTypeDurations {
+            queue_type: QueueType::BagCleaning { bag_ids: Vec::new() }.name().to_string(),
+            recent_seconds: vec![90 * 60, 10 * 60, 30 * 60],
+            version: 1,
+        }]);
+
+        let mut running = item(Priority::Normal, QueueStatus::InProgress, 1, now);
+        running.actual_start = Some(now - Duration::minutes(10));
+        let waiting = [
+            item(Priority::Normal, QueueStatus::Pending, 1, now),
+            item(Priority::Normal, QueueStatus::Pending, 1, now),
+        ];
+
+        // Two staff: one finishes the running item in 20 minutes, the other
+        // starts the first waiting item now; the second waits for the first free
+        let items = vec![running, waiting[0].clone(), waiting[1].clone()];
+        let schedule = plan(items, &durations, 2, now);
+        let completions: Vec<DateTime<Utc>> =
+            schedule.items.iter().map(|item| item.estimated_completion.unwrap()).collect();
+        assert_eq!(
+            completions,
+            [now + Duration::minutes(20), now + Duration::minutes(30), now + Duration::minutes(50)]
+        );
+    }
+
+    #[test]
+    fn test_durations_leave_out_pauses_and_failed_attempts() {
+        let now = Utc::now();
+        let at = |minutes| now - Duration::hours(3) + Duration::minutes(minutes);
+        let mut done = item(Priority::Normal, QueueStatus::Pending, 4, now);
+
+        assert!(!done.transition(QueueStatus::Completed, at(0), None, None));
+        assert!(done.transition(QueueStatus::InProgress, at(0), None, None));
+        assert!(done.transition(QueueStatus::Failed, at(50), None, Some("Bags torn".to_string())));
+        assert!(!done.transition(QueueStatus::InProgress, at(55), None, None));
+        assert!(done.transition(QueueStatus::Pending, at(60), None, None));
+        assert_eq!((done.actual_start, done.completed_at), (None, None));
+
+        assert!(done.transition(QueueStatus::InProgress, at(70), None, None));
+        assert!(done.transition(QueueStatus::Paused, at(80), None, None));
+        assert!(done.transition(QueueStatus::InProgress, at(120), None, None));
+        assert!(done.transition(QueueStatus::Completed, at(135), None, None));
+        assert_eq!(done.actual_start, Some(at(70)));
+        assert_eq!(done.history.len(), 7);
+        assert!(!done.transition(QueueStatus::Cancelled, at(140), None, None));
+
+        assert_eq!(worked(&done), Some(Duration::minutes(25)));
+    }
+
+    #[tokio::test]
+    async fn test_durations_keep_only_the_latest_runs() {
+        let repos = Repositories::in_memory();
+        let now = Utc::now();
+        for minutes in 1..=HISTORY_PER_TYPE as i64 + 5 {
+            let mut done = item(Priority::Normal, QueueStatus::Pending, 4, now);
+            assert!(done.transition(QueueStatus::InProgress, now - Duration::minutes(minutes), None, None));
+            assert!(done.transition(QueueStatus::Completed, now, None, None));
+            record_duration(&repos, &done).await.unwrap();
+        }
+
+        let stored = repos.durations.get("BAG_CLEANING").await.unwrap();
+        assert_eq!(stored.recent_seconds.len(), HISTORY_PER_TYPE);
+        assert_eq!(stored.recent_seconds[0], (HISTORY_PER_TYPE as i64 + 5) * 60);
+
+        let waiting = item(Priority::Normal, QueueStatus::Pending, 1, now);
+        repos.queue.create(&waiting).await.unwrap();
+        let schedule = schedule(&repos, now).await.unwrap();
+        // The median of the last 20 runs, which took 6 to 25 minutes
+        assert_eq!(schedule.items[0].estimated_completion, Some(now + Duration::minutes(16)));
+    }
+
+    #[test]
+    fn test_plan_waits_for_dependencies() {
+        let now = Utc::now();
+        let cleaning = item(Priority::Low, QueueStatus::Pending, 1, now);
+        let mut shipping = item(Priority::Urgent, QueueStatus::Pending, 1, now);
+        shipping.depends_on = vec![cleaning.id];
+        let paused = item(Priority::Low, QueueStatus::Paused, 1, now);
+        let mut blocked = item(Priority::Urgent, QueueStatus::Pending, 1, now);
+        blocked.depends_on = vec![paused.id];
+        // Completed, cancelled, failed or gone: none of them are among the open items
+        let mut unblocked = item(Priority::Normal, QueueStatus::Pending, 1, now);
+        unblocked.depends_on = vec![Uuid::new_v4()];
+        let ids = [unblocked.id, cleaning.id, shipping.id, blocked.id, paused.id];
+
+        let items = vec![shipping, blocked, paused, cleaning, unblocked];
+        let schedule = plan(items, &Durations(HashMap::new()), 2, now);
+        let order: Vec<Uuid> = schedule.items.iter().map(|item| item.queue_id).collect();
+        assert_eq!(order, ids);
+        // The shipment waits for the cleaning even with a second worker free
+        assert_eq!(schedule.items[2].estimated_start, Some(now + Duration::minutes(20)));
+        assert_eq!(schedule.items[3].estimated_start, None);
+    }
+}
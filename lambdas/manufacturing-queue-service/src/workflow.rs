@@ -0,0 +1,746 @@
+//! The seed-to-doorstep pipeline
+//!
+//! Completing a queue item queues the step after it, filled in from what the
+//! completed step worked on: seeds taken in are germinated for the customer
+//! who sent them once quarantine is over, their plants move from the
+//! germination zone to a growing zone, and the customer's sprouts are then
+//! prepared for shipment. Cleaned bags go back to their owners, together
+//! while the owner's return is still waiting to start. A step whose
+//! successor can't be worked out stays in progress with a 409 saying what is
+//! missing, so nothing drops out of the pipeline unnoticed.
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use uuid::Uuid;
+
+use api::ApiError;
+use database::DatabaseError;
+use models::{ManufacturingQueue, Plant, QuarantineStatus, QueueStatus, QueueType, ZoneType};
+
+use crate::{claims, Repositories};
+
+/// Days newly stored seeds sit in quarantine before they may be germinated
+pub const SEED_QUARANTINE_DAYS: i64 = 14;
+
+/// Completing a `from` item queues a `to` item, to start no sooner than
+/// `delay_days` later
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowLink {
+    from: &'static str,
+    to: &'static str,
+    delay_days: i64,
+}
+
+pub const PIPELINE: &[WorkflowLink] = &[
+    WorkflowLink { from: "SEED_INTAKE", to: "GERMINATION_SCHEDULED", delay_days: SEED_QUARANTINE_DAYS },
+    WorkflowLink { from: "GERMINATION_SCHEDULED", to: "GREENHOUSE_TRANSFER", delay_days: 0 },
+    // Plants already in the zone they would move to
+    WorkflowLink { from: "GERMINATION_SCHEDULED", to: "SHIPMENT_PREP", delay_days: 0 },
+    WorkflowLink { from: "GREENHOUSE_TRANSFER", to: "SHIPMENT_PREP", delay_days: 0 },
+    WorkflowLink { from: "BAG_CLEANING", to: "BAG_SHIPMENT_PREP", delay_days: 0 },
+];
+
+/// What completing an item leads to, not yet stored
+#[derive(Debug, Default)]
+pub(crate) struct NextSteps {
+    /// New items: one per customer, or per zone the plants are moving from
+    pub(crate) queued: Vec<ManufacturingQueue>,
+    /// Waiting items taking on the completed item's work, e.g. a customer's
+    /// bag return that hasn't started yet
+    pub(crate) joined: Vec<ManufacturingQueue>,
+}
+
+/// The items completing `item` queues or adds to, the same ones however often
+/// it's asked. Empty at the end of the pipeline
+pub(crate) async fn next_steps(
+    repos: &Repositories,
+    item: &ManufacturingQueue,
+    now: DateTime<Utc>,
+) -> Result<NextSteps, ApiError> {
+    let links: Vec<&WorkflowLink> = PIPELINE.iter().filter(|link| link.from == item.queue_type.name()).collect();
+    if links.is_empty() {
+        return Ok(NextSteps::default());
+    }
+
+    let queue_types = match &item.queue_type {
+        QueueType::SeedIntake { seed_ids } => germinations(repos, item, seed_ids).await?,
+        QueueType::GerminationScheduled { seed_ids, customer_id } => {
+            transfers(repos, item, seed_ids, *customer_id).await?
+        }
+        QueueType::GreenhouseTransfer { plant_ids, .. } => shipment_preps(repos, item, plant_ids).await?,
+        QueueType::BagCleaning { bag_ids } => bag_returns(repos, item, bag_ids).await?,
+        _ => Vec::new(),
+    };
+
+    let mut next_steps = NextSteps::default();
+    for queue_type in queue_types {
+        if let QueueType::BagShipmentPrep { bag_ids, customer_id } = &queue_type {
+            if let Some(joined) = join_bag_return(repos, item, *customer_id, bag_ids).await? {
+                next_steps.joined.push(joined);
+                continue;
+            }
+        }
+
+        let link = links.iter().find(|link| link.to == queue_type.name());
+        debug_assert!(link.is_some(), "{} isn't in the pipeline", queue_type.name());
+        let delay_days = link.map_or(0, |link| link.delay_days);
+        let scheduled_start = (delay_days > 0).then(|| now + Duration::days(delay_days));
+        let depends_on = match &queue_type {
+            QueueType::BagShipmentPrep { customer_id, .. } => other_bag_cleanings(repos, item, *customer_id).await?,
+            _ => Vec::new(),
+        };
+        let required_skills = claims::required_skills(repos, &queue_type).await?;
+        let mut next = ManufacturingQueue {
+            id: step_id(item, &queue_type),
+            queue_type,
+            priority: item.priority,
+            status: QueueStatus::Pending,
+            created_at: now,
+            scheduled_start,
+            actual_start: None,
+            completed_at: None,
+            assigned_to: None,
+            notes: None,
+            estimated_completion: None,
+            version: 0,
+            history: Vec::new(),
+            previous_step: Some(item.id),
+            depends_on,
+            batch_id: None,
+            required_skills,
+            lease_expires_at: None,
+            leased: false,
+        };
+        if scheduled_start.is_some() {
+            next.transition(QueueStatus::Scheduled, now, None, Some("Seed quarantine".to_string()));
+        }
+        next_steps.queued.push(next);
+    }
+    Ok(next_steps)
+}
+
+/// The id of the `queue_type` step completing `item` queues, the same each
+/// time, so sending a completion again finds the steps it already queued
+fn step_id(item: &ManufacturingQueue, queue_type: &QueueType) -> Uuid {
+    let of = match queue_type {
+        QueueType::GreenhouseTransfer { from_zone_id, .. } => Some(*from_zone_id),
+        _ => queue_type.customer_id(),
+    };
+    let name = format!("{}/{}", queue_type.name(), of.map(|id| id.to_string()).unwrap_or_default());
+    Uuid::new_v5(&item.id, name.as_bytes())
+}
+
+/// 409 with the item, which stays as it was
+fn blocked(item: &ManufacturingQueue, message: String) -> ApiError {
+    ApiError::Conflict { message, current: serde_json::to_value(item).ok() }
+}
+
+/// Stored seeds, one germination per customer who sent them; house seeds
+/// without a customer wait for allocation instead
+async fn germinations(
+    repos: &Repositories,
+    item: &ManufacturingQueue,
+    seed_ids: &[Uuid],
+) -> Result<Vec<QueueType>, ApiError> {
+    let mut by_customer: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+    let mut unstored = Vec::new();
+    let mut missing = Vec::new();
+    for &seed_id in seed_ids {
+        if repos.storage.list_by_seed(seed_id).await?.is_empty() {
+            unstored.push(seed_id.to_string());
+            continue;
+        }
+        match repos.seeds.get(seed_id).await {
+            Ok(seed) => {
+                if let Some(customer_id) = seed.source_customer_id {
+                    by_customer.entry(customer_id).or_default().push(seed_id);
+                }
+            }
+            Err(DatabaseError::NotFound(_)) => missing.push(seed_id.to_string()),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    if !unstored.is_empty() {
+        return Err(blocked(item, format!("Store seeds {} before completing their intake", unstored.join(", "))));
+    }
+    if !missing.is_empty() {
+        return Err(blocked(item, format!("Seeds {} are stored but not on record", missing.join(", "))));
+    }
+
+    Ok(by_customer
+        .into_iter()
+        .map(|(customer_id, seed_ids)| QueueType::GerminationScheduled { seed_ids, customer_id })
+        .collect())
+}
+
+/// The seeds' plants, to the open standard zone with the fewest plants. Those
+/// already there need no transfer, so their sprouts go straight to shipment prep
+async fn transfers(
+    repos: &Repositories,
+    item: &ManufacturingQueue,
+    seed_ids: &[Uuid],
+    customer_id: Uuid,
+) -> Result<Vec<QueueType>, ApiError> {
+    let plants: Vec<Plant> = repos
+        .plants
+        .list_all()
+        .await?
+        .into_iter()
+        .filter(|plant| plant.seed_id.is_some_and(|seed_id| seed_ids.contains(&seed_id)))
+        .collect();
+    if plants.is_empty() {
+        return Err(blocked(item, "None of the seeds have been planted in the greenhouse yet".to_string()));
+    }
+
+    let to_zone_id = repos
+        .zones
+        .list_all()
+        .await?
+        .into_iter()
+        .filter(|zone| {
+            matches!(zone.zone_type, ZoneType::Standard)
+                && matches!(zone.quarantine_status, QuarantineStatus::None | QuarantineStatus::Cleared)
+        })
+        .min_by_key(|zone| zone.current_plants.len())
+        .map(|zone| zone.id)
+        .ok_or_else(|| blocked(item, "No standard greenhouse zone is open to take the plants".to_string()))?;
+
+    let (in_place, moving): (Vec<Plant>, Vec<Plant>) =
+        plants.into_iter().partition(|plant| plant.current_zone_id == to_zone_id);
+    let mut next = Vec::new();
+    if !in_place.is_empty() {
+        next.push(shipment_prep(repos, item, customer_id, &in_place).await?);
+    }
+
+    let mut by_zone: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+    for plant in moving {
+        by_zone.entry(plant.current_zone_id).or_default().push(plant.id);
+    }
+    next.extend(by_zone.into_iter().map(|(from_zone_id, plant_ids)| QueueType::GreenhouseTransfer {
+        plant_ids,
+        from_zone_id,
+        to_zone_id,
+    }));
+    Ok(next)
+}
+
+/// The growing sprouts of the transfer's plants, for the customer whose
+/// germination it came from. A transfer queued by hand isn't for a customer
+/// and ends there
+async fn shipment_preps(
+    repos: &Repositories,
+    item: &ManufacturingQueue,
+    plant_ids: &[Uuid],
+) -> Result<Vec<QueueType>, ApiError> {
+    let Some(previous) = item.previous_step else {
+        return Ok(Vec::new());
+    };
+    let QueueType::GerminationScheduled { customer_id, .. } = repos.queue.get(previous).await?.queue_type else {
+        return Ok(Vec::new());
+    };
+
+    let mut plants = Vec::with_capacity(plant_ids.len());
+    for &plant_id in plant_ids {
+        match repos.plants.get(plant_id).await {
+            Ok(plant) => plants.push(plant),
+            Err(DatabaseError::NotFound(_)) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(vec![shipment_prep(repos, item, customer_id, &plants).await?])
+}
+
+/// Prep of the customer's growing sprouts from `plants` alone, so a
+/// germination moved out of several zones has each sprout packed once. A
+/// record counts if it names one of the plants, or names none and its seed
+/// was planted as one
+async fn shipment_prep(
+    repos: &Repositories,
+    item: &ManufacturingQueue,
+    customer_id: Uuid,
+    plants: &[Plant],
+) -> Result<QueueType, ApiError> {
+    let plant_ids: HashSet<Uuid> = plants.iter().map(|plant| plant.id).collect();
+    let seed_ids: HashSet<Uuid> = plants.iter().filter_map(|plant| plant.seed_id).collect();
+
+    let germination_record_ids: Vec<Uuid> = repos
+        .records
+        .list_by_customer(customer_id)
+        .await?
+        .into_iter()
+        .filter(|record| record.germination_phase.is_growing())
+        .filter(|record| match record.plant_id {
+            Some(plant_id) => plant_ids.contains(&plant_id),
+            None => seed_ids.contains(&record.seed_id),
+        })
+        .map(|record| record.id)
+        .collect();
+    if germination_record_ids.is_empty() {
+        let message = format!("Customer {} has no growing germination records for these plants", customer_id);
+        return Err(blocked(item, message));
+    }
+
+    Ok(QueueType::ShipmentPrep { germination_record_ids, customer_id })
+}
+
+/// Open `BAG_CLEANING` items other than `completed` holding any of the
+/// customer's bags, which their return shipment has to wait for
+async fn other_bag_cleanings(
+    repos: &Repositories,
+    completed: &ManufacturingQueue,
+    customer_id: Uuid,
+) -> Result<Vec<Uuid>, ApiError> {
+    let owned: HashSet<Uuid> = repos.bags.list_by_owner(customer_id).await?.into_iter().map(|bag| bag.id).collect();
+
+    let mut waiting_on = Vec::new();
+    for status in [QueueStatus::Pending, QueueStatus::Scheduled, QueueStatus::InProgress, QueueStatus::Paused] {
+        for item in repos.queue.list_by_status(&status).await? {
+            let QueueType::BagCleaning { bag_ids } = &item.queue_type else {
+                continue;
+            };
+            if item.id != completed.id && bag_ids.iter().any(|bag_id| owned.contains(bag_id)) {
+                waiting_on.push(item.id);
+            }
+        }
+    }
+    Ok(waiting_on)
+}
+
+/// The customer's bag return that hasn't started yet, with `bag_ids` added and
+/// waiting on the customer's other open cleanings too, so their bags go back
+/// in one shipment. None if there is no such return to add to
+async fn join_bag_return(
+    repos: &Repositories,
+    completed: &ManufacturingQueue,
+    customer_id: Uuid,
+    bag_ids: &[Uuid],
+) -> Result<Option<ManufacturingQueue>, ApiError> {
+    let mut waiting = None;
+    for status in [QueueStatus::Pending, QueueStatus::Scheduled] {
+        waiting = repos.queue.list_by_status(&status).await?.into_iter().find(|item| {
+            matches!(&item.queue_type, QueueType::BagShipmentPrep { customer_id: owner, .. } if *owner == customer_id)
+        });
+        if waiting.is_some() {
+            break;
+        }
+    }
+    let Some(mut waiting) = waiting else {
+        return Ok(None);
+    };
+
+    if let QueueType::BagShipmentPrep { bag_ids: returning, .. } = &mut waiting.queue_type {
+        for bag_id in bag_ids {
+            if !returning.contains(bag_id) {
+                returning.push(*bag_id);
+            }
+        }
+    }
+    for cleaning in other_bag_cleanings(repos, completed, customer_id).await? {
+        if !waiting.depends_on.contains(&cleaning) {
+            waiting.depends_on.push(cleaning);
+        }
+    }
+    for skill in claims::required_skills(repos, &waiting.queue_type).await? {
+        if !waiting.required_skills.contains(&skill) {
+            waiting.required_skills.push(skill);
+        }
+    }
+    Ok(Some(waiting))
+}
+
+/// Cleaned bags back to their owners; anonymous donations stay with us
+async fn bag_returns(
+    repos: &Repositories,
+    item: &ManufacturingQueue,
+    bag_ids: &[Uuid],
+) -> Result<Vec<QueueType>, ApiError> {
+    let mut by_owner: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+    let mut missing = Vec::new();
+    for &bag_id in bag_ids {
+        match repos.bags.get(bag_id).await {
+            Ok(bag) => {
+                if let Some(owner) = bag.original_owner_id {
+                    by_owner.entry(owner).or_default().push(bag_id);
+                }
+            }
+            Err(DatabaseError::NotFound(_)) => missing.push(bag_id.to_string()),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(blocked(item, format!("Bags {} aren't on record, so can't be returned", missing.join(", "))));
+    }
+
+    Ok(by_owner.into_iter().map(|(customer_id, bag_ids)| QueueType::BagShipmentPrep { bag_ids, customer_id }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::{
+        Bag, BagCondition, BagStatus, BagType, EnvironmentalConditions, GerminationPhase, GerminationRecord,
+        GreenhouseZone, GrowingMedium, GrowthStage, PlantHealthStatus, Priority, RiskLevel, Seed, SeedStatus,
+        SeedStorage, SeedStorageGuide, ShipmentType, SpatialCoordinates, Species, SproutHealthStatus, StorageCondition,
+        StorageLocation, StorageUnit,
+    };
+
+    fn queued(queue_type: QueueType) -> ManufacturingQueue {
+        ManufacturingQueue {
+            id: Uuid::new_v4(),
+            queue_type,
+            priority: Priority::High,
+            status: QueueStatus::Completed,
+            created_at: Utc::now(),
+            scheduled_start: None,
+            actual_start: None,
+            completed_at: None,
+            assigned_to: None,
+            notes: None,
+            estimated_completion: None,
+            version: 0,
+            history: Vec::new(),
+            previous_step: None,
+            depends_on: Vec::new(),
+            batch_id: None,
+            required_skills: Vec::new(),
+            lease_expires_at: None,
+            leased: false,
+        }
+    }
+
+    async fn seed(repos: &Repositories, customer_id: Option<Uuid>, stored: bool) -> Uuid {
+        let seed = Seed {
+            id: Uuid::new_v4(),
+            plant_species: "tomato".to_string(),
+            variety: None,
+            source_customer_id: customer_id,
+            collected_at: Utc::now(),
+            status: SeedStatus::Collected,
+            germination_tested: false,
+            germination_rate: None,
+            is_edible_fruit_bearing: None,
+            has_edible_leaves: None,
+            has_edible_stalks: None,
+            contamination_check: None,
+            notes: None,
+        };
+        repos.seeds.create(&seed).await.unwrap();
+        if stored {
+            store(repos, seed.id).await;
+        }
+        seed.id
+    }
+
+    async fn store(repos: &Repositories, seed_id: Uuid) {
+        let guide = SeedStorageGuide::for_species(Species::resolve("tomato").unwrap());
+        let storage = SeedStorage {
+            id: Uuid::new_v4(),
+            seed_id,
+            storage_location: StorageLocation {
+                facility: "Portland".to_string(),
+                room: "Cold-1".to_string(),
+                unit: StorageUnit::ColdRefrigerator { unit_number: 1 },
+                shelf: None,
+                bin: None,
+            },
+            storage_requirements: guide.storage_requirements,
+            stored_at: Utc::now(),
+            last_checked: Utc::now(),
+            condition: StorageCondition::Excellent,
+            quantity_grams: None,
+            viability_tested: false,
+            estimated_viability_percent: None,
+        };
+        repos.storage.create(&storage).await.unwrap();
+    }
+
+    async fn plant(repos: &Repositories, seed_id: Uuid, zone_id: Uuid) -> Uuid {
+        let plant = Plant {
+            id: Uuid::new_v4(),
+            seed_id: Some(seed_id),
+            species: "tomato".to_string(),
+            variety: None,
+            current_zone_id: zone_id,
+            planted_at: Utc::now(),
+            growth_stage: GrowthStage::Seedling,
+            health_status: PlantHealthStatus::Healthy,
+            expected_harvest_date: None,
+            phenotype_notes: None,
+            contamination_history: Vec::new(),
+        };
+        repos.plants.create(&plant).await.unwrap();
+        plant.id
+    }
+
+    /// A growing germination record of the customer's seed, naming no plant
+    async fn record(repos: &Repositories, customer_id: Uuid, seed_id: Uuid) -> Uuid {
+        let record = GerminationRecord {
+            id: Uuid::new_v4(),
+            seed_id,
+            plant_id: None,
+            customer_id,
+            species: "tomato".to_string(),
+            variety: None,
+            germination_phase: GerminationPhase::TrueLeafEmergence,
+            started_at: Utc::now(),
+            imbibition_started_at: Utc::now(),
+            radicle_emerged_at: None,
+            shoot_emerged_at: None,
+            cotyledon_expanded_at: None,
+            true_leaf_emerged_at: None,
+            photosynthesis_started_at: None,
+            ready_for_shipment_at: None,
+            growing_medium: GrowingMedium::Soil,
+            temperature_celsius: None,
+            humidity_percent: None,
+            light_hours_per_day: None,
+            germination_success: true,
+            health_status: SproutHealthStatus::Good,
+            root_length_mm: None,
+            shoot_length_mm: None,
+            cotyledon_count: None,
+            true_leaf_count: None,
+            total_leaf_count: None,
+            is_true_plant: true,
+            is_autotrophic: false,
+            has_edible_fruit_potential: None,
+            has_edible_leaves_potential: None,
+            has_edible_stalks_potential: None,
+            shipment_type: ShipmentType::LiveSprout,
+            estimated_ship_date: None,
+            actual_ship_date: None,
+            customer_instructions: None,
+            notes: None,
+            stalled_phase: None,
+            environmental_alerts: Vec::new(),
+            ship_date_forecast: None,
+            tray_id: None,
+            package_id: None,
+            version: 0,
+        };
+        repos.records.create(&record).await.unwrap();
+        record.id
+    }
+
+    #[tokio::test]
+    async fn test_each_transfer_preps_only_its_own_plants() {
+        let repos = Repositories::in_memory();
+        let customer_id = Uuid::new_v4();
+        let (east, west, to_zone_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let seed_ids = vec![seed(&repos, Some(customer_id), true).await, seed(&repos, Some(customer_id), true).await];
+        let plants = [plant(&repos, seed_ids[0], east).await, plant(&repos, seed_ids[1], west).await];
+        let records = [record(&repos, customer_id, seed_ids[0]).await, record(&repos, customer_id, seed_ids[1]).await];
+
+        let germination = queued(QueueType::GerminationScheduled { seed_ids, customer_id });
+        repos.queue.create(&germination).await.unwrap();
+
+        for (i, from_zone_id) in [east, west].into_iter().enumerate() {
+            let mut transfer =
+                queued(QueueType::GreenhouseTransfer { plant_ids: vec![plants[i]], from_zone_id, to_zone_id });
+            transfer.previous_step = Some(germination.id);
+            let next = next_steps(&repos, &transfer, Utc::now()).await.unwrap().queued;
+            assert_eq!(next.len(), 1);
+            assert_eq!(
+                next[0].queue_type,
+                QueueType::ShipmentPrep { germination_record_ids: vec![records[i]], customer_id }
+            );
+        }
+    }
+
+    async fn zone(repos: &Repositories, zone_type: ZoneType, current_plants: usize) -> Uuid {
+        let zone = GreenhouseZone {
+            id: Uuid::new_v4(),
+            zone_number: 1,
+            zone_type,
+            spatial_coordinates: SpatialCoordinates { x: 0, y: 0, level: 0, isolation_distance_meters: None },
+            current_plants: (0..current_plants).map(|_| Uuid::new_v4()).collect(),
+            quarantine_status: QuarantineStatus::None,
+            phenotype_designation: None,
+            environmental_conditions: EnvironmentalConditions {
+                temperature_celsius: None,
+                humidity_percent: None,
+                light_hours_per_day: None,
+                co2_ppm: None,
+                last_measured: Utc::now(),
+            },
+            contamination_risk_level: RiskLevel::Low,
+        };
+        repos.zones.create(&zone).await.unwrap();
+        zone.id
+    }
+
+    #[tokio::test]
+    async fn test_plants_already_in_place_go_straight_to_shipment_prep() {
+        let repos = Repositories::in_memory();
+        let customer_id = Uuid::new_v4();
+        let emptiest = zone(&repos, ZoneType::Standard, 0).await;
+        zone(&repos, ZoneType::Standard, 5).await;
+        let nursery = zone(&repos, ZoneType::Germination, 0).await;
+
+        let in_place = seed(&repos, Some(customer_id), true).await;
+        plant(&repos, in_place, emptiest).await;
+        let in_place_record = record(&repos, customer_id, in_place).await;
+        let germination = queued(QueueType::GerminationScheduled { seed_ids: vec![in_place], customer_id });
+        let next = next_steps(&repos, &germination, Utc::now()).await.unwrap().queued;
+        assert_eq!(next.len(), 1);
+        assert_eq!(
+            next[0].queue_type,
+            QueueType::ShipmentPrep { germination_record_ids: vec![in_place_record], customer_id }
+        );
+        assert_eq!(next[0].previous_step, Some(germination.id));
+
+        // Only the plants elsewhere move
+        let sprouting = seed(&repos, Some(customer_id), true).await;
+        let moving = plant(&repos, sprouting, nursery).await;
+        record(&repos, customer_id, sprouting).await;
+        let germination = queued(QueueType::GerminationScheduled { seed_ids: vec![in_place, sprouting], customer_id });
+        let next = next_steps(&repos, &germination, Utc::now()).await.unwrap().queued;
+        assert_eq!(next.len(), 2);
+        assert_eq!(
+            next[0].queue_type,
+            QueueType::ShipmentPrep { germination_record_ids: vec![in_place_record], customer_id }
+        );
+        assert_eq!(
+            next[1].queue_type,
+            QueueType::GreenhouseTransfer { plant_ids: vec![moving], from_zone_id: nursery, to_zone_id: emptiest }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_intake_queues_germination_per_customer_after_quarantine() {
+        let repos = Repositories::in_memory();
+        let now = Utc::now();
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let seeds = vec![
+            seed(&repos, Some(alice), true).await,
+            seed(&repos, Some(bob), true).await,
+            seed(&repos, Some(alice), true).await,
+            seed(&repos, None, true).await,
+        ];
+
+        let intake = queued(QueueType::SeedIntake { seed_ids: seeds.clone() });
+        let next = next_steps(&repos, &intake, now).await.unwrap().queued;
+        assert_eq!(next.len(), 2);
+        let alices = next.iter().find(|step| step.queue_type.customer_id() == Some(alice)).unwrap();
+        assert_eq!(
+            alices.queue_type,
+            QueueType::GerminationScheduled { seed_ids: vec![seeds[0], seeds[2]], customer_id: alice }
+        );
+        assert_eq!(alices.status, QueueStatus::Scheduled);
+        assert_eq!(alices.scheduled_start, Some(now + Duration::days(SEED_QUARANTINE_DAYS)));
+        assert_eq!((alices.priority, alices.previous_step), (Priority::High, Some(intake.id)));
+        // Asked again, e.g. for a completion sent twice, it names the same items
+        let again = next_steps(&repos, &intake, now).await.unwrap().queued;
+        assert_eq!(
+            again.iter().map(|step| step.id).collect::<Vec<_>>(),
+            next.iter().map(|step| step.id).collect::<Vec<_>>()
+        );
+
+        let unstored = seed(&repos, Some(alice), false).await;
+        let intake = queued(QueueType::SeedIntake { seed_ids: vec![seeds[0], unstored] });
+        let err = next_steps(&repos, &intake, now).await.unwrap_err();
+        assert_eq!(err.status_code(), 409);
+
+        // Storage for a seed that isn't on record names it rather than 404ing
+        let unrecorded = Uuid::new_v4();
+        store(&repos, unrecorded).await;
+        let intake = queued(QueueType::SeedIntake { seed_ids: vec![seeds[0], unrecorded] });
+        let err = next_steps(&repos, &intake, now).await.unwrap_err();
+        assert_eq!(err.status_code(), 409);
+        assert!(err.to_string().contains(&unrecorded.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_cleaned_bags_go_back_to_their_owners_once_all_are_clean() {
+        let repos = Repositories::in_memory();
+        let owner = Uuid::new_v4();
+        let mut bag_ids = Vec::new();
+        for original_owner_id in [Some(owner), None, Some(owner)] {
+            let bag = Bag {
+                id: Uuid::new_v4(),
+                original_owner_id,
+                current_status: BagStatus::Cleaning,
+                bag_type: BagType::Paper,
+                condition: BagCondition::Good,
+                received_at: Utc::now(),
+                cleaned_at: None,
+                last_updated: Utc::now(),
+                contains_seeds: false,
+                seed_ids: Vec::new(),
+                version: 0,
+            };
+            repos.bags.create(&bag).await.unwrap();
+            bag_ids.push(bag.id);
+        }
+
+        // The owner's third bag is still being cleaned
+        let mut still_cleaning = queued(QueueType::BagCleaning { bag_ids: vec![bag_ids[2]] });
+        still_cleaning.status = QueueStatus::InProgress;
+        repos.queue.create(&still_cleaning).await.unwrap();
+
+        let cleaning = queued(QueueType::BagCleaning { bag_ids: bag_ids[..2].to_vec() });
+        let next = next_steps(&repos, &cleaning, Utc::now()).await.unwrap().queued;
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].queue_type, QueueType::BagShipmentPrep { bag_ids: vec![bag_ids[0]], customer_id: owner });
+        assert_eq!(next[0].status, QueueStatus::Pending);
+        assert_eq!(next[0].depends_on, [still_cleaning.id]);
+
+        let lost = Uuid::new_v4();
+        let cleaning = queued(QueueType::BagCleaning { bag_ids: vec![bag_ids[0], lost] });
+        let err = next_steps(&repos, &cleaning, Utc::now()).await.unwrap_err();
+        assert_eq!(err.status_code(), 409);
+        assert!(err.to_string().contains(&lost.to_string()));
+
+        let shipment = queued(QueueType::BagShipmentPrep { bag_ids, customer_id: owner });
+        let next = next_steps(&repos, &shipment, Utc::now()).await.unwrap();
+        assert!(next.queued.is_empty() && next.joined.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_one_owners_cleanings_share_one_bag_return() {
+        let repos = Repositories::in_memory();
+        let owner = Uuid::new_v4();
+        let mut bag_ids = Vec::new();
+        for _ in 0..2 {
+            let bag = Bag {
+                id: Uuid::new_v4(),
+                original_owner_id: Some(owner),
+                current_status: BagStatus::Cleaning,
+                bag_type: BagType::Paper,
+                condition: BagCondition::Good,
+                received_at: Utc::now(),
+                cleaned_at: None,
+                last_updated: Utc::now(),
+                contains_seeds: false,
+                seed_ids: Vec::new(),
+                version: 0,
+            };
+            repos.bags.create(&bag).await.unwrap();
+            bag_ids.push(bag.id);
+        }
+        let mut first = queued(QueueType::BagCleaning { bag_ids: vec![bag_ids[0]] });
+        let mut second = queued(QueueType::BagCleaning { bag_ids: vec![bag_ids[1]] });
+        second.status = QueueStatus::InProgress;
+        repos.queue.create(&second).await.unwrap();
+
+        first.status = QueueStatus::Completed;
+        let next = next_steps(&repos, &first, Utc::now()).await.unwrap();
+        assert!(next.joined.is_empty());
+        let [bag_return] = &next.queued[..] else { panic!("expected one return, got {:?}", next.queued) };
+        assert_eq!(bag_return.depends_on, [second.id]);
+        repos.queue.create(bag_return).await.unwrap();
+
+        second.status = QueueStatus::Completed;
+        repos.queue.update(&second).await.unwrap();
+        let next = next_steps(&repos, &second, Utc::now()).await.unwrap();
+        assert!(next.queued.is_empty());
+        let [joined] = &next.joined[..] else { panic!("expected to join the return, got {:?}", next.joined) };
+        assert_eq!(joined.id, bag_return.id);
+        assert_eq!(joined.queue_type, QueueType::BagShipmentPrep { bag_ids, customer_id: owner });
+        assert_eq!(joined.depends_on, [second.id]);
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::LazyLock;
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{Error, LambdaEvent};
+use serde_json::json;
+use tracing::info;
+
+use api::{response, ApiError, ApiResult, Request, Router};
+use models::Species;
+
+static ROUTER: LazyLock<Router<()>> = LazyLock::new(|| {
+    Router::new()
+        .get("/processing/guides", get_processing_guide)
+        .get("/processing/curing-protocols", get_curing_protocol)
+        .get("/processing/recipes", get_recipes)
+});
+
+pub async fn function_handler(event: LambdaEvent<ApiGatewayProxyRequest>) -> Result<ApiGatewayProxyResponse, Error> {
+    info!("Processing plant processing request");
+
+    Ok(ROUTER.handle(&(), event.payload).await)
+}
+
+/// The `species` query parameter, resolved through the registry
+fn species(request: &Request) -> Result<&'static Species, ApiError> {
+    let name = request.required_query("species")?;
+    Species::resolve(name).ok_or_else(|| ApiError::unknown_species("species", name))
+}
+
+async fn get_processing_guide(_: &(), request: Request) -> ApiResult {
+    let species = species(&request)?;
+
+    info!("Fetching processing guide for species: {}", species.id);
+
+    // TODO: Query DynamoDB for species-specific processing methods
+
+    response::ok(&json!({
+        "species": species.id,
+        "is_edible": species.is_edible(),
+        "edible_parts": species.edible_parts,
+        "processing_methods": []
+    }))
+}
+
+async fn get_curing_protocol(_: &(), request: Request) -> ApiResult {
+    let plant_type = request.required_query("plant_type")?;
+
+    info!("Fetching curing protocol for: {}", plant_type);
+
+    // TODO: Query DynamoDB for curing protocols
+
+    response::ok(&json!({ "plant_type": plant_type, "phases": [] }))
+}
+
+async fn get_recipes(_: &(), request: Request) -> ApiResult {
+    let species = species(&request)?;
+
+    info!("Fetching recipes for: {}", species.id);
+
+    // TODO: Query DynamoDB for recipes
+
+    response::ok(&json!({ "recipes": [] }))
+}
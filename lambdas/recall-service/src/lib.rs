@@ -0,0 +1,265 @@
+use std::sync::LazyLock;
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{Error, LambdaEvent};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tracing::info;
+use uuid::Uuid;
+
+use api::{response, ApiResult, Request, Role, Router};
+use database::{RecallNotificationsRepository, RecallReviewsRepository, RecallsRepository};
+use models::{RecallImpactAssessment, RecallReview, RecallStatus, Species};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewRecallRequest {
+    recall_id: Uuid,
+    is_relevant: bool,
+    affected_species: Vec<String>,
+    notes: String,
+    manual_check_required: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AssessImpactRequest {
+    recall_id: Uuid,
+}
+
+pub struct Repositories {
+    recalls: RecallsRepository,
+    reviews: RecallReviewsRepository,
+    notifications: RecallNotificationsRepository,
+}
+
+impl Repositories {
+    pub fn new(client: aws_sdk_dynamodb::Client) -> Self {
+        Self {
+            recalls: RecallsRepository::new(client.clone()),
+            reviews: RecallReviewsRepository::new(client.clone()),
+            notifications: RecallNotificationsRepository::new(client),
+        }
+    }
+
+    /// Process-local tables, for tests and the dev server
+    #[cfg(any(test, feature = "in-memory"))]
+    pub fn in_memory() -> Self {
+        Self {
+            recalls: RecallsRepository::in_memory(),
+            reviews: RecallReviewsRepository::in_memory(),
+            notifications: RecallNotificationsRepository::in_memory(),
+        }
+    }
+}
+
+static ROUTER: LazyLock<Router<Repositories>> = LazyLock::new(|| {
+    Router::new()
+        .post("/recalls/fetch-usda", fetch_usda_recalls)
+        .requires(Role::Staff)
+        .post("/recalls/fetch-fda", fetch_fda_recalls)
+        .requires(Role::Staff)
+        .get("/recalls/new", list_new_recalls)
+        .requires(Role::Staff)
+        .post("/recalls/review", review_recall)
+        .requires(Role::Moderator)
+        .post("/recalls/assess-impact", assess_impact)
+        .requires(Role::Staff)
+        .get("/recalls/affected-customers", list_affected_customers)
+        .requires(Role::Staff)
+        .post("/recalls/{id}/notify", notify_customers)
+        .requires(Role::Moderator)
+});
+
+pub async fn function_handler(
+    repos: &Repositories,
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    info!("Processing recall service request");
+
+    Ok(ROUTER.handle(repos, event.payload).await)
+}
+
+async fn fetch_usda_recalls(_: &Repositories, _: Request) -> ApiResult {
+    info!("Fetching recalls from USDA API");
+
+    // TODO: Call actual USDA FSIS API
+    // URL: https://www.fsis.usda.gov/fsis-content/api/recalls
+    // Documentation: https://www.fsis.usda.gov/science-data/data-sets-visualizations/recalls-api
+
+    let mock_recalls = vec![serde_json::json!({
+        "recall_number": "USDA-2024-001",
+        "recall_date": "2025-10-10",
+        "product_description": "Organic Spinach",
+        "reason_for_recall": "Potential Salmonella contamination",
+        "company_name": "Green Farms Inc.",
+        "distribution": "CA, OR, WA",
+        "classification": "Class I"
+    })];
+
+    response::ok(&serde_json::json!({
+        "source": "USDA",
+        "recalls_found": mock_recalls.len(),
+        "recalls": mock_recalls,
+        "fetched_at": chrono::Utc::now()
+    }))
+}
+
+async fn fetch_fda_recalls(_: &Repositories, _: Request) -> ApiResult {
+    info!("Fetching recalls from FDA API");
+
+    // TODO: Call actual FDA Enforcement API
+    // URL: https://api.fda.gov/food/enforcement.json
+    // Documentation: https://open.fda.gov/apis/food/enforcement/
+
+    let mock_recalls = vec![serde_json::json!({
+        "recall_number": "FDA-2024-123",
+        "recall_initiation_date": "2025-10-12",
+        "product_description": "Fresh Tomatoes",
+        "reason_for_recall": "Potential Listeria monocytogenes",
+        "recalling_firm": "Fresh Produce Co.",
+        "distribution_pattern": "Nationwide",
+        "classification": "Class II"
+    })];
+
+    response::ok(&serde_json::json!({
+        "source": "FDA",
+        "recalls_found": mock_recalls.len(),
+        "recalls": mock_recalls,
+        "fetched_at": chrono::Utc::now()
+    }))
+}
+
+async fn list_new_recalls(repos: &Repositories, request: Request) -> ApiResult {
+    info!("Listing new recalls for review");
+
+    let page = request.page()?;
+
+    let recalls = repos.recalls.page_by_status(&RecallStatus::New, &page).await?;
+
+    response::ok(&serde_json::json!({
+        "count": recalls.items.len(),
+        "new_recalls": recalls.items,
+        "next_cursor": recalls.next_cursor,
+        "reminder": "⚠️ Manual USDA/FDA website check recommended"
+    }))
+}
+
+async fn review_recall(repos: &Repositories, request: Request) -> ApiResult {
+    let req: ReviewRecallRequest = request.json()?;
+
+    let mut recall = repos.recalls.get(req.recall_id).await?;
+
+    // A recall must not be held up by a name we don't recognise: keep it as
+    // written and have someone check it by hand
+    let mut affected_species = Vec::new();
+    let mut unknown_species = Vec::new();
+    for name in &req.affected_species {
+        match Species::resolve(name) {
+            Some(species) => affected_species.push(species.id.to_string()),
+            None => {
+                unknown_species.push(name.clone());
+                affected_species.push(name.clone());
+            }
+        }
+    }
+    let notes = if unknown_species.is_empty() {
+        req.notes
+    } else {
+        format!("{}\nUnrecognised species: {}", req.notes, unknown_species.join(", ")).trim_start().to_string()
+    };
+
+    let review_id = Uuid::new_v4();
+    let review = RecallReview {
+        id: review_id,
+        recall_id: req.recall_id,
+        reviewed_by: request.principal()?.user_id.to_string(),
+        reviewed_at: chrono::Utc::now(),
+        is_relevant: req.is_relevant,
+        affects_our_products: req.is_relevant,
+        affected_species,
+        notes,
+        next_action: if req.is_relevant {
+            "Assess customer impact and notify".to_string()
+        } else {
+            "No action needed".to_string()
+        },
+        manual_check_required: req.manual_check_required || !unknown_species.is_empty(),
+        manual_check_completed: false,
+        manual_check_notes: None,
+    };
+
+    info!("Recall {} reviewed - relevant: {}", req.recall_id, req.is_relevant);
+
+    repos.reviews.create(&review).await?;
+
+    recall.status = if req.is_relevant { RecallStatus::Relevant } else { RecallStatus::NotRelevant };
+    recall.last_checked = review.reviewed_at;
+    repos.recalls.update(&recall).await?;
+
+    // TODO: If relevant, trigger impact assessment
+
+    response::created(&review)
+}
+
+async fn assess_impact(_: &Repositories, request: Request) -> ApiResult {
+    let req: AssessImpactRequest = request.json()?;
+
+    info!("Assessing customer impact for recall {}", req.recall_id);
+
+    // TODO: Fetch recall details
+    // TODO: Query customer database for matching:
+    //   - Shipping addresses in affected states
+    //   - Products matching affected species
+    //   - Shipments in affected date range
+
+    let assessment = RecallImpactAssessment {
+        id: Uuid::new_v4(),
+        recall_id: req.recall_id,
+        assessed_at: chrono::Utc::now(),
+        assessed_by: "system".to_string(),
+        potentially_affected_customers: vec![],
+        affected_by_state: vec![],
+        total_customers_affected: 0,
+        affected_seed_ids: vec![],
+        affected_plant_ids: vec![],
+        affected_shipment_ids: vec![],
+        risk_level: models::recalls::RiskLevel::Low,
+        requires_customer_notification: false,
+        requires_product_removal: false,
+        actions_taken: vec![],
+        customer_notification_sent_at: None,
+        products_quarantined_at: None,
+    };
+
+    response::ok(&assessment)
+}
+
+async fn list_affected_customers(_: &Repositories, _: Request) -> ApiResult {
+    info!("Listing affected customers across all recalls");
+
+    // TODO: Query all relevant recalls and aggregate affected customers
+
+    response::ok(&serde_json::json!({ "affected_customers": [], "count": 0 }))
+}
+
+async fn notify_customers(repos: &Repositories, request: Request) -> ApiResult {
+    let recall_id = request.uuid_param("id")?;
+
+    info!("Notifying customers about recall {}", recall_id);
+
+    let mut recall = repos.recalls.get(recall_id).await?;
+
+    // TODO: Fetch affected customers
+    // TODO: Send email notifications and create notification records
+    let notifications = repos.notifications.list_by_recall(recall_id).await?;
+
+    recall.status = RecallStatus::CustomersNotified;
+    repos.recalls.update(&recall).await?;
+
+    response::ok(&serde_json::json!({
+        "recall_id": recall_id,
+        "customers_notified": notifications.len(),
+        "notification_sent_at": chrono::Utc::now()
+    }))
+}
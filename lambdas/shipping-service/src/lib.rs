@@ -0,0 +1,210 @@
+use std::sync::LazyLock;
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{Error, LambdaEvent};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use api::{response, ApiError, ApiResult, Request, Router};
+use database::ShipmentsRepository;
+use models::{
+    BagPackagingInstructions, LegStatus, ShipStationAddress, ShipStationCreateLabelRequest, ShipmentCycle, ShipmentLeg,
+    ShipmentLegType, ShipmentStatus, Weight,
+};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateShipmentRequest {
+    customer_id: Uuid,
+    subscription_id: Uuid,
+    customer_address: models::Address,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipmentResponse {
+    shipment_id: Uuid,
+    customer_to_facility_tracking: Option<String>,
+    facility_to_customer_tracking: Option<String>,
+    status: ShipmentStatus,
+    packaging_instructions: BagPackagingInstructions,
+}
+
+/// Tracking update for a single leg, forwarded from ShipStation
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipStationWebhookRequest {
+    shipment_id: Uuid,
+    leg_number: u32,
+    status: LegStatus,
+}
+
+static ROUTER: LazyLock<Router<ShipmentsRepository>> = LazyLock::new(|| {
+    Router::new()
+        .post("/shipments", create_multi_point_shipment)
+        .get("/shipments/packaging-instructions", get_packaging_instructions)
+        .post("/shipments/webhook", handle_shipstation_webhook)
+});
+
+/// Main Lambda handler for shipping and logistics
+pub async fn function_handler(
+    shipments: &ShipmentsRepository,
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    info!("Processing shipping request");
+
+    Ok(ROUTER.handle(shipments, event.payload).await)
+}
+
+async fn create_multi_point_shipment(shipments: &ShipmentsRepository, request: Request) -> ApiResult {
+    let req: CreateShipmentRequest = request.json()?;
+
+    info!("Creating multi-point shipment for customer {}", req.customer_id);
+
+    let shipment_id = Uuid::new_v4();
+    let facility_address = models::Address {
+        street1: "123 Seed Processing Center".to_string(),
+        street2: None,
+        city: "Portland".to_string(),
+        state: "OR".to_string(),
+        zip: "97201".to_string(),
+        country: "US".to_string(),
+    };
+
+    // Leg 1: Customer to Facility (initial shipment of bags)
+    let leg1 = ShipmentLeg {
+        leg_number: 1,
+        leg_type: ShipmentLegType::CustomerToFacility,
+        from_address: req.customer_address.clone(),
+        to_address: facility_address.clone(),
+        shipstation_label_id: None,
+        tracking_number: None,
+        status: LegStatus::Pending,
+        shipped_at: None,
+        delivered_at: None,
+    };
+
+    // Leg 2: Facility to Customer (return of cleaned bags - FINAL LEG with return label logic)
+    let leg2 = ShipmentLeg {
+        leg_number: 2,
+        leg_type: ShipmentLegType::FacilityToCustomer,
+        from_address: facility_address.clone(),
+        to_address: req.customer_address.clone(),
+        shipstation_label_id: None,
+        tracking_number: None,
+        status: LegStatus::Pending,
+        shipped_at: None,
+        delivered_at: None,
+    };
+
+    let shipment = ShipmentCycle {
+        id: shipment_id,
+        customer_id: req.customer_id,
+        subscription_id: req.subscription_id,
+        legs: vec![leg1, leg2],
+        status: ShipmentStatus::Pending,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+        version: 0,
+    };
+
+    // Generate ShipStation labels for both legs
+    // The return label (leg 2) is generated upfront but used as the final leg
+    create_shipstation_label(&shipment.id, &req.customer_address, &facility_address, false)
+        .await
+        .map_err(shipstation_failure)?;
+
+    create_shipstation_label(
+        &shipment.id,
+        &facility_address,
+        &req.customer_address,
+        true, // This is the return label
+    )
+    .await
+    .map_err(shipstation_failure)?;
+
+    shipments.create(&shipment).await?;
+
+    info!("Created multi-point shipment with return label as final leg");
+
+    let response = ShipmentResponse {
+        shipment_id: shipment.id,
+        customer_to_facility_tracking: Some("MOCK_TRACKING_001".to_string()),
+        facility_to_customer_tracking: Some("MOCK_TRACKING_002".to_string()),
+        status: shipment.status,
+        packaging_instructions: BagPackagingInstructions::trapezoid_butterfly_method(),
+    };
+
+    response::created(&response)
+}
+
+fn shipstation_failure(err: Error) -> ApiError {
+    ApiError::UpstreamFailure(format!("ShipStation label request failed: {}", err))
+}
+
+async fn create_shipstation_label(
+    shipment_id: &Uuid,
+    from: &models::Address,
+    to: &models::Address,
+    is_return: bool,
+) -> Result<(), Error> {
+    info!("Creating ShipStation label for shipment {} (return: {})", shipment_id, is_return);
+
+    let label_request = ShipStationCreateLabelRequest {
+        order_id: shipment_id.to_string(),
+        carrier_code: "usps".to_string(),
+        service_code: "usps_priority_mail".to_string(),
+        confirmation: "delivery".to_string(),
+        ship_date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        weight: Weight { value: 8.0, units: "ounces".to_string() },
+        dimensions: None,
+        ship_from: ShipStationAddress {
+            name: "Seed Box Bag Box".to_string(),
+            street1: from.street1.clone(),
+            street2: from.street2.clone(),
+            city: from.city.clone(),
+            state: from.state.clone(),
+            postal_code: from.zip.clone(),
+            country: from.country.clone(),
+        },
+        ship_to: ShipStationAddress {
+            name: "Customer".to_string(),
+            street1: to.street1.clone(),
+            street2: to.street2.clone(),
+            city: to.city.clone(),
+            state: to.state.clone(),
+            postal_code: to.zip.clone(),
+            country: to.country.clone(),
+        },
+        is_return_label: is_return,
+    };
+
+    debug!("ShipStation label request: {:?}", label_request);
+
+    // TODO: Make actual API call to ShipStation
+    // let client = reqwest::Client::new();
+    // let response = client.post("https://ssapi.shipstation.com/shipments/createlabel")
+    //     .basic_auth(api_key, Some(api_secret))
+    //     .json(&label_request)
+    //     .send()
+    //     .await?;
+
+    Ok(())
+}
+
+async fn get_packaging_instructions(_: &ShipmentsRepository, _: Request) -> ApiResult {
+    response::ok(&BagPackagingInstructions::trapezoid_butterfly_method())
+}
+
+async fn handle_shipstation_webhook(shipments: &ShipmentsRepository, request: Request) -> ApiResult {
+    let req: ShipStationWebhookRequest = request.json()?;
+
+    info!("Received ShipStation webhook: shipment {} leg {} is {:?}", req.shipment_id, req.leg_number, req.status);
+
+    // A concurrent update comes back as 409, which ShipStation retries
+    shipments.update_leg_status(req.shipment_id, req.leg_number, &req.status).await?;
+
+    response::ok(&serde_json::json!({ "status": "received" }))
+}
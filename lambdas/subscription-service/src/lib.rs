@@ -0,0 +1,222 @@
+use std::sync::LazyLock;
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{Error, LambdaEvent};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use api::{response, ApiResult, Request, Router};
+use database::SubscriptionsRepository;
+use models::{Customer, Subscription, SubscriptionStatus, SubscriptionTier};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubscriptionRequest {
+    customer_email: String,
+    customer_name: String,
+    tier: SubscriptionTier,
+    shipping_address: models::Address,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSubscriptionRequest {
+    tier: Option<SubscriptionTier>,
+    status: Option<SubscriptionStatus>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionResponse {
+    subscription_id: Uuid,
+    customer_id: Uuid,
+    tier: SubscriptionTier,
+    status: SubscriptionStatus,
+    monthly_price_cents: u32,
+    bags_required: bool,
+    next_billing_date: String,
+}
+
+impl From<&Subscription> for SubscriptionResponse {
+    fn from(subscription: &Subscription) -> Self {
+        Self {
+            subscription_id: subscription.id,
+            customer_id: subscription.customer_id,
+            tier: subscription.tier.clone(),
+            status: subscription.status.clone(),
+            monthly_price_cents: subscription.tier.price_cents(),
+            bags_required: subscription.bags_required,
+            next_billing_date: subscription.next_billing_date.to_rfc3339(),
+        }
+    }
+}
+
+static ROUTER: LazyLock<Router<SubscriptionsRepository>> = LazyLock::new(|| {
+    Router::new()
+        .post("/subscriptions", create_subscription)
+        .get("/subscriptions/{id}", get_subscription)
+        .put("/subscriptions/{id}", update_subscription)
+});
+
+/// Main Lambda handler for subscription management
+pub async fn function_handler(
+    subscriptions: &SubscriptionsRepository,
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    info!("Processing subscription request");
+
+    Ok(ROUTER.handle(subscriptions, event.payload).await)
+}
+
+async fn create_subscription(subscriptions: &SubscriptionsRepository, request: Request) -> ApiResult {
+    let req: CreateSubscriptionRequest = request.json()?;
+
+    // Create customer
+    let customer_id = Uuid::new_v4();
+    let _customer = Customer {
+        id: customer_id,
+        email: req.customer_email,
+        name: req.customer_name,
+        shipping_address: req.shipping_address,
+        created_at: chrono::Utc::now(),
+        cratejoy_customer_id: None,
+    };
+
+    // Create subscription
+    let subscription_id = Uuid::new_v4();
+    let bags_required = req.tier.requires_bags();
+    let initial_status = if bags_required { SubscriptionStatus::PendingBags } else { SubscriptionStatus::Active };
+
+    let subscription = Subscription {
+        id: subscription_id,
+        customer_id,
+        tier: req.tier.clone(),
+        status: initial_status.clone(),
+        cratejoy_subscription_id: None,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+        next_billing_date: chrono::Utc::now() + chrono::Duration::days(30),
+        bags_required,
+    };
+
+    subscriptions.create(&subscription).await?;
+
+    // TODO: Save customer record
+    // TODO: Create CrateJoy subscription
+    // TODO: If bags required, trigger return label generation
+
+    info!("Created subscription {} for customer {}", subscription_id, customer_id);
+
+    response::created(&SubscriptionResponse::from(&subscription))
+}
+
+async fn get_subscription(subscriptions: &SubscriptionsRepository, request: Request) -> ApiResult {
+    let id = request.uuid_param("id")?;
+
+    info!("Fetching subscription {}", id);
+
+    let subscription = subscriptions.get(id).await?;
+
+    response::ok(&SubscriptionResponse::from(&subscription))
+}
+
+async fn update_subscription(subscriptions: &SubscriptionsRepository, request: Request) -> ApiResult {
+    let id = request.uuid_param("id")?;
+    let req: UpdateSubscriptionRequest = request.json()?;
+
+    info!("Updating subscription {}", id);
+
+    let mut subscription = subscriptions.get(id).await?;
+
+    if let Some(tier) = req.tier {
+        subscription.bags_required = tier.requires_bags();
+        subscription.tier = tier;
+    }
+    if let Some(status) = req.status {
+        subscription.status = status;
+    }
+    subscription.updated_at = chrono::Utc::now();
+
+    let subscription = subscriptions.update(&subscription).await?;
+
+    // TODO: Sync with CrateJoy
+
+    response::ok(&SubscriptionResponse::from(&subscription))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_lambda_events::encodings::Body;
+    use lambda_runtime::Context;
+
+    fn event(method: &str, path: &str, body: Option<serde_json::Value>) -> LambdaEvent<ApiGatewayProxyRequest> {
+        let request = ApiGatewayProxyRequest {
+            http_method: method.parse().unwrap(),
+            path: Some(path.to_string()),
+            body: body.map(|b| b.to_string()),
+            ..Default::default()
+        };
+        LambdaEvent::new(request, Context::default())
+    }
+
+    fn body(response: &ApiGatewayProxyResponse) -> serde_json::Value {
+        match &response.body {
+            Some(Body::Text(text)) => serde_json::from_str(text).unwrap(),
+            other => panic!("unexpected body {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_then_update_subscription() {
+        let subscriptions = SubscriptionsRepository::in_memory();
+
+        let created = function_handler(
+            &subscriptions,
+            event(
+                "POST",
+                "/subscriptions",
+                Some(serde_json::json!({
+                    "customerEmail": "grower@example.com",
+                    "customerName": "Grower",
+                    "tier": "BRING_YOUR_OWN_BAGS",
+                    "shippingAddress": {
+                        "street1": "1 Seed St",
+                        "street2": null,
+                        "city": "Portland",
+                        "state": "OR",
+                        "zip": "97201",
+                        "country": "US"
+                    }
+                })),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(created.status_code, 201);
+        let created = body(&created);
+        assert_eq!(created["status"], "pendingbags");
+
+        let path = format!("/subscriptions/{}", created["subscriptionId"].as_str().unwrap());
+        let updated =
+            function_handler(&subscriptions, event("PUT", &path, Some(serde_json::json!({ "tier": "STANDARD" }))))
+                .await
+                .unwrap();
+        assert_eq!(updated.status_code, 200);
+
+        let fetched = body(&function_handler(&subscriptions, event("GET", &path, None)).await.unwrap());
+        assert_eq!(fetched["tier"], "STANDARD");
+        assert_eq!(fetched["bagsRequired"], false);
+    }
+
+    #[tokio::test]
+    async fn test_get_unknown_subscription_is_not_found() {
+        let subscriptions = SubscriptionsRepository::in_memory();
+        let path = format!("/subscriptions/{}", Uuid::new_v4());
+
+        let response = function_handler(&subscriptions, event("GET", &path, None)).await.unwrap();
+        assert_eq!(response.status_code, 404);
+    }
+}
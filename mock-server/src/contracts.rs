@@ -0,0 +1,800 @@
+//! Consumer contracts for every lambda, generated from the wire types
+//!
+//! Each provider's contract lists the calls the web pages make and JSON Schemas
+//! for the bodies involved, produced by `schemars` from `models` and the lambdas'
+//! request/response structs. Object schemas reject properties they do not name,
+//! so a renamed field fails validation even when it is optional.
+//!
+//! The contracts are committed under `contracts/` for consumers to read. The
+//! tests below fail when those files are stale (regenerate with
+//! `UPDATE_CONTRACTS=1 cargo test -p mock-server`), and replay every committed
+//! interaction against the real handlers, validating each response.
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{Schema, SchemaObject};
+use schemars::visit::{visit_schema_object, Visitor};
+use schemars::{JsonSchema, Map};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use germination_service::{
+    AllocationReport, EnvironmentalHistory, GerminationResponse, ImportReport, PhaseResponse, PrepareShipmentRequest,
+    ReadinessResponse, RecordObservationRequest, ShipForecast, ShipmentPackageResponse, SproutPreferencesRequest,
+    StallReport, StartGerminationRequest, StartTrayRequest, TrayObservationRequest, TrayObservationResponse,
+    TrayReport, TrayResponse, UpdatePhaseRequest,
+};
+use greenhouse_service::{CreateZoneRequest, PlantResponse, PlantSeedRequest, ZoneResponse};
+use image_service::{ConfirmUploadRequest, ModerateImageRequest, PresignedUrlResponse, RequestPresignedUrlRequest};
+use inventory_service::{BagResponse, ReceiveBagRequest, RegisterSeedRequest, SeedResponse};
+use manufacturing_queue_service::{
+    ClaimRequest, ClaimResponse, CreateQueueRequest, DependenciesRequest, QueueResponse, QueueSchedule,
+    QueueTransitionRequest, StorageResponse, StoreSeedRequest, WorkerRequest, WorkerResponse,
+};
+use models::{
+    BagPackagingInstructions, CustomerSproutPreferences, GerminationGuide, GerminationObservation, GerminationRecord,
+    InventorySummary, ManufacturingQueue, PlantImage, SeedStorageGuide,
+};
+use shipping_service::{CreateShipmentRequest, ShipmentResponse};
+use subscription_service::{CreateSubscriptionRequest, SubscriptionResponse, UpdateSubscriptionRequest};
+
+use envelopes::*;
+
+/// Who reads the contracts
+const CONSUMER: &str = "web";
+
+/// One provider's contract, as committed to `contracts/{provider}.json`
+#[derive(Debug, Serialize)]
+pub struct Contract {
+    pub consumer: &'static str,
+    pub provider: &'static str,
+    pub interactions: Vec<Interaction>,
+    /// Named schemas, referenced as `#/definitions/{name}`
+    pub definitions: Map<String, Schema>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Interaction {
+    pub description: &'static str,
+    pub request: ContractRequest,
+    pub response: ContractResponse,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContractRequest {
+    pub method: &'static str,
+    /// `{name}` is filled in from a field of an earlier response in the same contract
+    pub path: &'static str,
+    /// Roles the caller holds, as the dev server's `X-Dev-Roles` header takes them
+    pub roles: &'static str,
+    /// Example body; string values of the form `{name}` are filled in like the path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Schema>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContractResponse {
+    pub status: u16,
+    pub schema: Schema,
+}
+
+struct ContractBuilder {
+    provider: &'static str,
+    generator: SchemaGenerator,
+    interactions: Vec<Interaction>,
+}
+
+impl ContractBuilder {
+    fn new(provider: &'static str) -> Self {
+        Self { provider, generator: SchemaSettings::draft07().into_generator(), interactions: Vec::new() }
+    }
+
+    /// A call without a body, answered with `Res`
+    fn call<Res: JsonSchema>(
+        mut self,
+        description: &'static str,
+        roles: &'static str,
+        method: &'static str,
+        path: &'static str,
+        status: u16,
+    ) -> Self {
+        let schema = self.generator.subschema_for::<Res>();
+        self.interactions.push(Interaction {
+            description,
+            request: ContractRequest { method, path, roles, body: None, schema: None },
+            response: ContractResponse { status, schema },
+        });
+        self
+    }
+
+    /// A call sending `body` as a `Req`, answered with `Res`
+    fn send<Req: JsonSchema, Res: JsonSchema>(
+        mut self,
+        description: &'static str,
+        roles: &'static str,
+        method: &'static str,
+        path: &'static str,
+        body: Value,
+        status: u16,
+    ) -> Self {
+        let request = self.generator.subschema_for::<Req>();
+        let schema = self.generator.subschema_for::<Res>();
+        self.interactions.push(Interaction {
+            description,
+            request: ContractRequest { method, path, roles, body: Some(body), schema: Some(request) },
+            response: ContractResponse { status, schema },
+        });
+        self
+    }
+
+    fn build(mut self) -> Contract {
+        let mut definitions = self.generator.take_definitions();
+        for schema in definitions.values_mut() {
+            DenyUnknownFields.visit_schema(schema);
+        }
+        for interaction in &mut self.interactions {
+            if let Some(schema) = &mut interaction.request.schema {
+                DenyUnknownFields.visit_schema(schema);
+            }
+            DenyUnknownFields.visit_schema(&mut interaction.response.schema);
+        }
+        Contract { consumer: CONSUMER, provider: self.provider, interactions: self.interactions, definitions }
+    }
+}
+
+/// Closes every object schema that lists its properties; maps keep theirs open
+#[derive(Debug, Clone)]
+struct DenyUnknownFields;
+
+impl Visitor for DenyUnknownFields {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        visit_schema_object(self, schema);
+        if let Some(object) = &mut schema.object {
+            if !object.properties.is_empty() && object.additional_properties.is_none() {
+                object.additional_properties = Some(Box::new(Schema::Bool(false)));
+            }
+        }
+    }
+}
+
+/// Bodies the handlers assemble with `json!`; only their schemas are used
+#[allow(dead_code)]
+mod envelopes {
+    use schemars::JsonSchema;
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use manufacturing_queue_service::WorkflowLink;
+    use models::{Bag, GreenhouseZone, ManufacturingQueue, ModerationStatus, PlantImage, PlantPart, RecallData};
+
+    #[derive(JsonSchema)]
+    pub struct BagPage {
+        pub bags: Vec<Bag>,
+        pub next_cursor: Option<String>,
+    }
+
+    #[derive(JsonSchema)]
+    pub struct ZoneList {
+        pub zones: Vec<GreenhouseZone>,
+    }
+
+    #[derive(JsonSchema)]
+    pub struct ProcessingGuide {
+        pub species: String,
+        pub is_edible: bool,
+        pub edible_parts: Vec<PlantPart>,
+        pub processing_methods: Vec<Value>,
+    }
+
+    #[derive(JsonSchema)]
+    pub struct QueuePage {
+        pub pending_count: usize,
+        pub in_progress_count: usize,
+        pub queue_items: Vec<ManufacturingQueue>,
+        pub next_cursor: Option<String>,
+    }
+
+    #[derive(JsonSchema)]
+    pub struct GreenhouseWorkflows {
+        pub workflows: Vec<WorkflowLink>,
+        pub pending_transfers: usize,
+        pub quarantine_releases: usize,
+    }
+
+    #[derive(JsonSchema)]
+    pub struct MyImages {
+        pub count: usize,
+        pub images: Vec<PlantImage>,
+    }
+
+    #[derive(JsonSchema)]
+    pub struct ModerationResult {
+        pub image_id: Uuid,
+        pub status: ModerationStatus,
+        pub moderated_by: Uuid,
+        pub version: u64,
+        pub audit_logged: bool,
+    }
+
+    #[derive(JsonSchema)]
+    pub struct NewRecalls {
+        pub count: usize,
+        pub new_recalls: Vec<RecallData>,
+        pub next_cursor: Option<String>,
+        pub reminder: String,
+    }
+
+    #[derive(JsonSchema)]
+    pub struct AffectedCustomers {
+        pub affected_customers: Vec<Value>,
+        pub count: usize,
+    }
+}
+
+fn address() -> Value {
+    json!({
+        "street1": "1 SE Main St",
+        "street2": null,
+        "city": "Portland",
+        "state": "OR",
+        "zip": "97214",
+        "country": "US"
+    })
+}
+
+/// Every provider's contract, in the order the lambdas appear in template.yaml
+pub fn contracts() -> Vec<Contract> {
+    vec![
+        ContractBuilder::new("subscription-service")
+            .send::<CreateSubscriptionRequest, SubscriptionResponse>(
+                "A customer signs up",
+                "customer",
+                "POST",
+                "/subscriptions",
+                json!({
+                    "customerEmail": "sprout@example.com",
+                    "customerName": "Sprout",
+                    "tier": "STANDARD",
+                    "shippingAddress": address()
+                }),
+                201,
+            )
+            .call::<SubscriptionResponse>(
+                "A customer views their subscription",
+                "customer",
+                "GET",
+                "/subscriptions/{subscriptionId}",
+                200,
+            )
+            .send::<UpdateSubscriptionRequest, SubscriptionResponse>(
+                "A customer upgrades their tier",
+                "customer",
+                "PUT",
+                "/subscriptions/{subscriptionId}",
+                json!({ "tier": "PREMIUM", "status": null }),
+                200,
+            )
+            .build(),
+        ContractBuilder::new("shipping-service")
+            .call::<BagPackagingInstructions>(
+                "A customer reads how to pack their bags",
+                "customer",
+                "GET",
+                "/shipments/packaging-instructions",
+                200,
+            )
+            .send::<CreateShipmentRequest, ShipmentResponse>(
+                "Staff create a round-trip shipment",
+                "staff",
+                "POST",
+                "/shipments",
+                json!({
+                    "customerId": "00000000-0000-4000-8000-000000000001",
+                    "subscriptionId": "00000000-0000-4000-8000-000000000002",
+                    "customerAddress": address()
+                }),
+                201,
+            )
+            .build(),
+        ContractBuilder::new("inventory-service")
+            .send::<ReceiveBagRequest, BagResponse>(
+                "Staff receive a returned bag",
+                "staff",
+                "POST",
+                "/inventory/bags",
+                json!({ "customerId": null, "bagType": "paper", "condition": "good", "containsSeeds": true }),
+                201,
+            )
+            .send::<RegisterSeedRequest, SeedResponse>(
+                "Staff register a seed found in a bag",
+                "staff",
+                "POST",
+                "/inventory/seeds",
+                json!({ "plantSpecies": "tomato", "variety": null, "sourceCustomerId": null, "notes": null }),
+                201,
+            )
+            .call::<BagPage>("Staff list bags", "staff", "GET", "/inventory/bags", 200)
+            .call::<InventorySummary>("Staff view the inventory summary", "staff", "GET", "/inventory/summary", 200)
+            .build(),
+        ContractBuilder::new("greenhouse-service")
+            .send::<CreateZoneRequest, ZoneResponse>(
+                "Staff lay out a greenhouse zone",
+                "staff",
+                "POST",
+                "/greenhouse/zones",
+                json!({
+                    "greenhouseId": "00000000-0000-4000-8000-000000000003",
+                    "zoneType": "STANDARD",
+                    "x": 1,
+                    "y": 2,
+                    "level": 0,
+                    "isolationDistanceMeters": null,
+                    "phenotypeDesignation": null
+                }),
+                201,
+            )
+            .send::<PlantSeedRequest, PlantResponse>(
+                "Staff plant a seed in the zone",
+                "staff",
+                "POST",
+                "/greenhouse/plants",
+                json!({
+                    "seedId": "00000000-0000-4000-8000-000000000004",
+                    "zoneId": "{zoneId}",
+                    "species": "tomato",
+                    "variety": null,
+                    "phenotypeNotes": null
+                }),
+                201,
+            )
+            .call::<ZoneList>("Staff list zones", "staff", "GET", "/greenhouse/zones", 200)
+            .build(),
+        ContractBuilder::new("plant-processing-service")
+            .call::<ProcessingGuide>(
+                "A customer looks up how to process a harvest",
+                "customer",
+                "GET",
+                "/processing/guides?species=tomato",
+                200,
+            )
+            .build(),
+        ContractBuilder::new("germination-service")
+            .call::<ImportReport>(
+                "An admin seeds the bundled guide catalog",
+                "admin",
+                "POST",
+                "/germination/guides/import",
+                200,
+            )
+            .call::<GerminationGuide>(
+                "Anyone reads a species' germination guide",
+                "customer",
+                "GET",
+                "/germination/guide/tomato",
+                200,
+            )
+            .send::<StartGerminationRequest, GerminationResponse>(
+                "Staff start germinating a customer's seed",
+                "staff",
+                "POST",
+                "/germination/start",
+                json!({
+                    "seedId": "00000000-0000-4000-8000-000000000004",
+                    "customerId": "00000000-0000-4000-8000-000000000001",
+                    "species": "tomato",
+                    "variety": null,
+                    "growingMedium": "soil",
+                    "shipmentType": "LIVE_SPROUT"
+                }),
+                201,
+            )
+            .send::<RecordObservationRequest, GerminationObservation>(
+                "Staff record an observation",
+                "staff",
+                "POST",
+                "/germination/observe",
+                json!({
+                    "germinationRecordId": "{germinationRecordId}",
+                    "rootLengthMm": 4.5,
+                    "shootLengthMm": null,
+                    "leafCount": 0,
+                    "healthStatus": "GOOD",
+                    "temperatureCelsius": 22.0,
+                    "humidityPercent": null,
+                    "notes": null
+                }),
+                201,
+            )
+            .call::<GerminationRecord>(
+                "The customer checks on their sprout",
+                "customer",
+                "GET",
+                "/germination/{germinationRecordId}",
+                200,
+            )
+            .call::<ReadinessResponse>(
+                "The customer asks when their sprout will be ready",
+                "customer",
+                "GET",
+                "/germination/{germinationRecordId}/readiness",
+                200,
+            )
+            .call::<EnvironmentalHistory>(
+                "The customer checks their sprout's growing conditions",
+                "customer",
+                "GET",
+                "/germination/{germinationRecordId}/environment",
+                200,
+            )
+            .send::<StartTrayRequest, TrayResponse>(
+                "Staff sow a tray",
+                "staff",
+                "POST",
+                "/germination/trays",
+                json!({
+                    "label": "Bench 1",
+                    "rows": 6,
+                    "columns": 12,
+                    "species": "basil",
+                    "variety": null,
+                    "growingMedium": "coco",
+                    "shipmentType": "LIVE_SPROUT",
+                    "temperatureCelsius": 22.0,
+                    "humidityPercent": 65.0,
+                    "lightHoursPerDay": 14.0,
+                    "seedLot": "LOT-2024-07",
+                    "cells": [{
+                        "row": 0,
+                        "column": 0,
+                        "seedId": "00000000-0000-4000-8000-000000000004",
+                        "customerId": "00000000-0000-4000-8000-000000000001"
+                    }]
+                }),
+                201,
+            )
+            .send::<TrayObservationRequest, TrayObservationResponse>(
+                "Staff observe the tray",
+                "staff",
+                "POST",
+                "/germination/trays/{trayId}/observe",
+                json!({
+                    "temperatureCelsius": 22.0,
+                    "humidityPercent": 65.0,
+                    "cells": [{ "row": 0, "column": 0, "healthStatus": "GOOD", "radicleVisible": true }]
+                }),
+                201,
+            )
+            .call::<TrayReport>("Staff compare trays and seed lots", "staff", "GET", "/germination/trays/report", 200)
+            .send::<UpdatePhaseRequest, PhaseResponse>(
+                "An admin moves the sprout straight to ready for shipment",
+                "admin",
+                "PUT",
+                "/germination/phase",
+                json!({
+                    "germinationRecordId": "{germinationRecordId}",
+                    "newPhase": "READY_FOR_SHIPMENT",
+                    "notes": null,
+                    "adminOverride": true
+                }),
+                200,
+            )
+            .send::<PrepareShipmentRequest, ShipmentPackageResponse>(
+                "Staff plan a sprout shipment",
+                "staff",
+                "POST",
+                "/germination/shipment",
+                json!({
+                    "germinationRecordIds": ["{germinationRecordId}"],
+                    "customerId": "00000000-0000-4000-8000-000000000001",
+                    "expedited": false,
+                    "destination": address(),
+                    "preferredDeliveryDays": ["TUESDAY", "WEDNESDAY", "THURSDAY"]
+                }),
+                201,
+            )
+            .send::<SproutPreferencesRequest, CustomerSproutPreferences>(
+                "A customer says which sprouts they want",
+                "customer",
+                "PUT",
+                "/germination/preferences/00000000-0000-4000-8000-000000000001",
+                json!({
+                    "preferredSpecies": ["basil"],
+                    "excludedSpecies": ["cannabis"],
+                    "preferredShipmentType": "LIVE_SPROUT",
+                    "deliveryFrequency": "biweekly",
+                    "preferredDeliveryDays": ["SATURDAY"],
+                    "sproutsPerShipmentMin": 2,
+                    "sproutsPerShipmentMax": 6,
+                    "experienceLevel": "beginner",
+                    "hasGrowLights": false,
+                    "hasGreenhouse": false,
+                    "hasOutdoorSpace": true
+                }),
+                200,
+            )
+            .call::<CustomerSproutPreferences>(
+                "A customer reads their sprout preferences",
+                "customer",
+                "GET",
+                "/germination/preferences/00000000-0000-4000-8000-000000000001",
+                200,
+            )
+            .call::<AllocationReport>("Staff allocate ready sprouts to customers", "staff", "POST", "/germination/allocations", 200)
+            .call::<ShipForecast>("Operations plan packaging capacity", "staff", "GET", "/germination/forecast", 200)
+            .call::<StallReport>("Staff review stalled sprouts", "staff", "GET", "/germination/stalls", 200)
+            .build(),
+        ContractBuilder::new("manufacturing-queue-service")
+            .send::<CreateQueueRequest, QueueResponse>(
+                "Staff queue bags for cleaning",
+                "staff",
+                "POST",
+                "/queue",
+                json!({ "queueType": { "BAG_CLEANING": { "bag_ids": [] } }, "priority": "high", "scheduledStart": null }),
+                201,
+            )
+            .send::<CreateQueueRequest, QueueResponse>(
+                "Staff queue work that waits on other work",
+                "staff",
+                "POST",
+                "/queue",
+                json!({
+                    "queueType": { "BAG_CLEANING": { "bag_ids": [] } },
+                    "priority": "normal",
+                    "scheduledStart": null,
+                    "dependsOn": ["{queueId}"]
+                }),
+                201,
+            )
+            .send::<DependenciesRequest, QueueResponse>(
+                "Staff clear what a queue item waits on",
+                "staff",
+                "PUT",
+                "/queue/{queueId}/dependencies",
+                json!({ "dependsOn": [] }),
+                200,
+            )
+            .call::<QueuePage>("Staff list the queue", "staff", "GET", "/queue", 200)
+            .call::<QueueSchedule>("Staff see the order the queue will run in", "staff", "GET", "/queue/schedule", 200)
+            .call::<QueueResponse>("Staff start a queue item", "staff", "PUT", "/queue/{queueId}/start", 200)
+            .call::<QueueResponse>("Staff pause a queue item", "staff", "PUT", "/queue/{queueId}/pause", 200)
+            .call::<QueueResponse>("Staff resume a queue item", "staff", "PUT", "/queue/{queueId}/resume", 200)
+            .send::<QueueTransitionRequest, QueueResponse>(
+                "Staff fail a queue item",
+                "staff",
+                "PUT",
+                "/queue/{queueId}/fail",
+                json!({ "reason": "Bags arrived torn" }),
+                200,
+            )
+            .call::<QueueResponse>("Staff retry a failed queue item", "staff", "PUT", "/queue/{queueId}/retry", 200)
+            .call::<ManufacturingQueue>("Staff read a queue item's history", "staff", "GET", "/queue/{queueId}", 200)
+            .call::<QueueResponse>("Staff cancel a queue item", "staff", "PUT", "/queue/{queueId}/cancel", 200)
+            .send::<WorkerRequest, WorkerResponse>(
+                "Admins register a station and its certifications",
+                "admin",
+                "PUT",
+                "/queue/workers/bag-station-1",
+                json!({ "kind": "station", "name": "Bag wash 1", "skills": ["quarantine"] }),
+                200,
+            )
+            .send::<ClaimRequest, ClaimResponse>(
+                "Staff claim the next queue item at a station",
+                "staff",
+                "POST",
+                "/queue/claim",
+                json!({ "workerId": "bag-station-1" }),
+                200,
+            )
+            .call::<WorkerResponse>("Staff see a station's work", "staff", "GET", "/queue/workers/bag-station-1", 200)
+            .call::<GreenhouseWorkflows>("Staff read the workflow pipeline", "staff", "GET", "/greenhouse/workflow", 200)
+            .send::<StoreSeedRequest, StorageResponse>(
+                "Staff put seeds into storage",
+                "staff",
+                "POST",
+                "/storage/seeds",
+                json!({
+                    "seedId": "00000000-0000-4000-8000-000000000004",
+                    "species": "tomato",
+                    "facility": "Portland",
+                    "room": "Cold-1",
+                    "unitNumber": 1,
+                    "quantityGrams": null
+                }),
+                201,
+            )
+            .call::<SeedStorageGuide>("Staff read a storage guide", "staff", "GET", "/storage/guide/tomato", 200)
+            .build(),
+        ContractBuilder::new("image-service")
+            .send::<RequestPresignedUrlRequest, PresignedUrlResponse>(
+                "A customer asks where to upload a photo",
+                "customer",
+                "POST",
+                "/images/request-upload",
+                json!({
+                    "itemId": "00000000-0000-4000-8000-000000000005",
+                    "itemType": "plant",
+                    "filename": "sprout.jpg",
+                    "contentType": "image/jpeg"
+                }),
+                200,
+            )
+            .send::<ConfirmUploadRequest, PlantImage>(
+                "The customer confirms the upload",
+                "customer",
+                "POST",
+                "/images/confirm-upload",
+                json!({
+                    "uploadId": "{uploadId}",
+                    "metadata": {
+                        "item_id": "00000000-0000-4000-8000-000000000005",
+                        "item_type": "plant",
+                        "filename": "sprout.jpg",
+                        "content_type": "image/jpeg",
+                        "caption": null,
+                        "growth_stage": null,
+                        "tags": []
+                    }
+                }),
+                201,
+            )
+            .call::<MyImages>("The customer lists their photos", "customer", "GET", "/images/my-images", 200)
+            .send::<ModerateImageRequest, ModerationResult>(
+                "A moderator approves the photo",
+                "moderator",
+                "POST",
+                "/images/moderate",
+                json!({ "imageId": "{id}", "status": "APPROVED", "notes": null, "expectedVersion": null }),
+                200,
+            )
+            .build(),
+        ContractBuilder::new("recall-service")
+            .call::<NewRecalls>("Staff list recalls awaiting review", "staff", "GET", "/recalls/new", 200)
+            .call::<AffectedCustomers>(
+                "Staff list customers affected by recalls",
+                "staff",
+                "GET",
+                "/recalls/affected-customers",
+                200,
+            )
+            .build(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tokio::sync::Mutex;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{app, AppState, Lambdas, MAX_BODY_BYTES};
+
+    fn contract_path(contract: &Contract) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../contracts").join(format!("{}.json", contract.provider))
+    }
+
+    /// The contract as consumers see it: the committed file, not what the types generate today
+    fn committed(contract: &Contract) -> Value {
+        let path = contract_path(contract);
+        let text = std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        serde_json::from_str(&text).unwrap()
+    }
+
+    /// A standalone schema for `schema`, resolving references against the contract's definitions
+    fn validator(contract: &Value, schema: &Value) -> jsonschema::Validator {
+        let mut document = schema.clone();
+        document["$schema"] = json!("http://json-schema.org/draft-07/schema#");
+        document["definitions"] = contract["definitions"].clone();
+        jsonschema::validator_for(&document).unwrap()
+    }
+
+    fn violations(validator: &jsonschema::Validator, instance: &Value) -> Vec<String> {
+        validator.iter_errors(instance).map(|err| format!("{}: {}", err.instance_path, err)).collect()
+    }
+
+    /// Replace `{name}` placeholders with values captured from earlier responses
+    fn fill(template: &str, captured: &HashMap<String, String>) -> String {
+        captured
+            .iter()
+            .fold(template.to_string(), |filled, (name, value)| filled.replace(&format!("{{{}}}", name), value))
+    }
+
+    fn fill_body(body: &Value, captured: &HashMap<String, String>) -> Value {
+        match body {
+            Value::String(text) => Value::String(fill(text, captured)),
+            Value::Array(items) => Value::Array(items.iter().map(|item| fill_body(item, captured)).collect()),
+            Value::Object(fields) => {
+                Value::Object(fields.iter().map(|(key, value)| (key.clone(), fill_body(value, captured))).collect())
+            }
+            other => other.clone(),
+        }
+    }
+
+    #[test]
+    fn test_committed_contracts_are_current() {
+        let update = std::env::var_os("UPDATE_CONTRACTS").is_some();
+        for contract in contracts() {
+            let path = contract_path(&contract);
+            let generated = serde_json::to_string_pretty(&contract).unwrap() + "\n";
+            if update {
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, generated).unwrap();
+                continue;
+            }
+            let committed = std::fs::read_to_string(&path).unwrap_or_default();
+            assert!(
+                committed == generated,
+                "{} is out of date with the wire types; regenerate it with \
+                 `UPDATE_CONTRACTS=1 cargo test -p mock-server` and update its consumers",
+                path.display()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_providers_honour_their_contracts() {
+        let mut failures = Vec::new();
+
+        for contract in contracts() {
+            let contract = committed(&contract);
+            let provider = contract["provider"].as_str().unwrap_or_default();
+
+            // Each provider starts from empty tables, as a fresh deployment would
+            let app =
+                app(AppState { scans: Arc::new(Mutex::new(Vec::new())), lambdas: Arc::new(Lambdas::in_memory()) });
+            let mut captured = HashMap::new();
+
+            for interaction in contract["interactions"].as_array().into_iter().flatten() {
+                let name = format!("{}: {}", provider, interaction["description"].as_str().unwrap_or_default());
+                let request = &interaction["request"];
+                let expected = &interaction["response"];
+
+                let body = request.get("body").map(|body| fill_body(body, &captured));
+                if let (Some(body), Some(schema)) = (&body, request.get("schema")) {
+                    let errors = violations(&validator(&contract, schema), body);
+                    if !errors.is_empty() {
+                        failures.push(format!("{} sends a body its own schema rejects: {:?}", name, errors));
+                        continue;
+                    }
+                }
+
+                let http = Request::builder()
+                    .method(request["method"].as_str().unwrap_or_default())
+                    .uri(fill(request["path"].as_str().unwrap_or_default(), &captured))
+                    .header("x-dev-roles", request["roles"].as_str().unwrap_or_default())
+                    .header("content-type", "application/json")
+                    .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                    .unwrap();
+                let response = app.clone().oneshot(http).await.unwrap();
+                let status = response.status().as_u16();
+                let bytes = to_bytes(response.into_body(), MAX_BODY_BYTES).await.unwrap();
+                let body: Value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+
+                if expected["status"] != status {
+                    failures.push(format!("{} returned {} instead of {}: {}", name, status, expected["status"], body));
+                    continue;
+                }
+                let errors = violations(&validator(&contract, &expected["schema"]), &body);
+                if !errors.is_empty() {
+                    failures.push(format!("{} broke the response schema: {:?}", name, errors));
+                }
+
+                if let Value::Object(fields) = &body {
+                    for (key, value) in fields {
+                        if let Value::String(value) = value {
+                            captured.insert(key.clone(), value.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        assert!(failures.is_empty(), "contract verification failed:\n{}", failures.join("\n"));
+    }
+}
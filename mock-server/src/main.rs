@@ -1,28 +1,106 @@
+//! Local dev server: every lambda's real `function_handler`, mounted behind
+//! axum with in-memory repositories
+//!
+//! HTTP requests are translated into the `ApiGatewayProxyRequest` API Gateway
+//! would deliver, so the web pages and `test-mock-api.sh` run production code.
+
+use std::sync::Arc;
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use aws_lambda_events::encodings::Body as LambdaBody;
+use aws_lambda_events::query_map::QueryMap;
 use axum::{
-    extract::{Path, Query, State},
+    body::{to_bytes, Body},
+    extract::State,
     http::StatusCode,
-    response::Json,
-    routing::{get, post, put},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
     Router,
 };
+use lambda_runtime::{Context, LambdaEvent};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Mutex;
 use tower_http::cors::CorsLayer;
 use tracing::info;
 use uuid::Uuid;
 
-// Shared state for mock data
+use api::ApiError;
+use database::{ShipmentsRepository, SubscriptionsRepository};
+
+mod contracts;
+
+/// Caller assumed when a request carries no `Authorization` header
+const DEV_USER: &str = "00000000-0000-4000-8000-000000000001";
+const DEV_ROLES: &str = "admin";
+
+/// Largest request body forwarded to a lambda (API Gateway's own limit)
+const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Each lambda's state, as its `main` would build it, but in process memory
+struct Lambdas {
+    subscriptions: SubscriptionsRepository,
+    shipments: ShipmentsRepository,
+    inventory: inventory_service::Repositories,
+    greenhouse: greenhouse_service::Repositories,
+    germination: germination_service::Repositories,
+    manufacturing: manufacturing_queue_service::Repositories,
+    images: image_service::Repositories,
+    recalls: recall_service::Repositories,
+}
+
+impl Lambdas {
+    fn in_memory() -> Self {
+        Self {
+            subscriptions: SubscriptionsRepository::in_memory(),
+            shipments: ShipmentsRepository::in_memory(),
+            inventory: inventory_service::Repositories::in_memory(),
+            greenhouse: greenhouse_service::Repositories::in_memory(),
+            germination: germination_service::Repositories::in_memory(),
+            manufacturing: manufacturing_queue_service::Repositories::in_memory(),
+            images: image_service::Repositories::in_memory(),
+            recalls: recall_service::Repositories::in_memory(),
+        }
+    }
+
+    /// Reference data a deployment loads before taking traffic
+    async fn seed(&self) {
+        germination_service::import_catalog(&self.germination, germination_service::Catalog::bundled())
+            .await
+            .expect("bundled germination guides import into empty tables");
+    }
+
+    /// Route by the path's first segment, as the events in template.yaml do.
+    /// `/greenhouse/workflow` belongs to the manufacturing queue lambda
+    async fn invoke(&self, request: ApiGatewayProxyRequest) -> Result<ApiGatewayProxyResponse, lambda_runtime::Error> {
+        let path = request.path.clone().unwrap_or_default();
+        let segment = path.trim_start_matches('/').split('/').next().unwrap_or_default();
+        let event = LambdaEvent::new(request, Context::default());
+
+        match segment {
+            "subscriptions" => subscription_service::function_handler(&self.subscriptions, event).await,
+            "shipments" => shipping_service::function_handler(&self.shipments, event).await,
+            "inventory" => inventory_service::function_handler(&self.inventory, event).await,
+            "processing" => plant_processing_service::function_handler(event).await,
+            "germination" => germination_service::function_handler(&self.germination, event).await,
+            "greenhouse" if path.starts_with("/greenhouse/workflow") => {
+                manufacturing_queue_service::function_handler(&self.manufacturing, event).await
+            }
+            "greenhouse" => greenhouse_service::function_handler(&self.greenhouse, event).await,
+            "queue" | "storage" => manufacturing_queue_service::function_handler(&self.manufacturing, event).await,
+            "images" => image_service::function_handler(&self.images, event).await,
+            "recalls" => recall_service::function_handler(&self.recalls, event).await,
+            _ => Ok(ApiError::not_found(format!("No lambda serves {}", path)).into_response()),
+        }
+    }
+}
+
 #[derive(Clone)]
 struct AppState {
     scans: Arc<Mutex<Vec<Scan>>>,
-    queue: Arc<Mutex<Vec<QueueItem>>>,
-    seeds: Arc<Mutex<Vec<SeedStorage>>>,
-    subscriptions: Arc<Mutex<Vec<Subscription>>>,
-    images: Arc<Mutex<Vec<PlantImage>>>,
-    audit_logs: Arc<Mutex<Vec<AuditLog>>>,
+    lambdas: Arc<Lambdas>,
 }
 
+/// Raw barcode scans have no domain model yet; the scanner UI only needs an echo
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct Scan {
     id: Uuid,
@@ -31,59 +109,6 @@ struct Scan {
     timestamp: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct QueueItem {
-    id: Uuid,
-    queue_type: String,
-    priority: String,
-    status: String,
-    created_at: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct SeedStorage {
-    id: Uuid,
-    seed_id: Uuid,
-    species: String,
-    location: String,
-    temperature: f32,
-    stored_at: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Subscription {
-    id: Uuid,
-    customer_id: Uuid,
-    tier: String,
-    status: String,
-    price_cents: u32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct PlantImage {
-    id: Uuid,
-    uploaded_by: Uuid,
-    item_id: Uuid,
-    item_type: String,
-    filename: String,
-    s3_url: String,
-    caption: Option<String>,
-    moderation_status: String,
-    uploaded_at: String,
-    deleted_at: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct AuditLog {
-    id: Uuid,
-    image_id: Uuid,
-    action: String,
-    performed_by: Uuid,
-    performed_at: String,
-    details: Option<String>,
-}
-
-// Request/Response types
 #[derive(Debug, Deserialize)]
 struct ScanRequest {
     code: String,
@@ -92,101 +117,118 @@ struct ScanRequest {
     timestamp: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct CreateQueueRequest {
-    queue_type: String,
-    priority: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct StoreSeedRequest {
-    seed_id: Uuid,
-    species: String,
-    facility: String,
-    room: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct CreateSubscriptionRequest {
-    customer_email: String,
-    customer_name: String,
-    tier: String,
-}
-
-#[tokio::main]
-async fn main() {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        .init();
-
-    // Initialize state
-    let state = AppState {
-        scans: Arc::new(Mutex::new(Vec::new())),
-        queue: Arc::new(Mutex::new(Vec::new())),
-        seeds: Arc::new(Mutex::new(Vec::new())),
-        subscriptions: Arc::new(Mutex::new(Vec::new())),
-        images: Arc::new(Mutex::new(Vec::new())),
-        audit_logs: Arc::new(Mutex::new(Vec::new())),
-    };
-
-    // Build router
-    let app = Router::new()
+fn app(state: AppState) -> Router {
+    Router::new()
         // Scanner endpoints
         .route("/api/scan", post(handle_scan))
         .route("/api/scans", get(list_scans))
-        
-        // Queue endpoints
-        .route("/api/queue", post(create_queue))
-        .route("/api/queue", get(list_queue))
-        .route("/api/queue/:id/start", put(start_queue))
-        .route("/api/queue/:id/complete", put(complete_queue))
-        
-        // Storage endpoints
-        .route("/api/storage/seeds", post(store_seed))
-        .route("/api/storage/seeds", get(list_storage))
-        .route("/api/storage/guide/:species", get(get_storage_guide))
-        
-        // Subscription endpoints
-        .route("/api/subscriptions", post(create_subscription))
-        .route("/api/subscriptions/:id", get(get_subscription))
-        
-        // Germination endpoints
-        .route("/api/germination/start", post(start_germination))
-        .route("/api/germination/ready", get(list_ready_germination))
-        
-        // Greenhouse endpoints
-        .route("/api/greenhouse/zones", get(list_zones))
-        .route("/api/greenhouse/plants", get(list_plants))
-        
-        // Image endpoints
-        .route("/images/request-upload", post(request_presigned_url))
-        .route("/images/confirm-upload", post(confirm_upload))
-        .route("/images/my-images", get(list_my_images))
-        .route("/images/:id", axum::routing::delete(delete_image))
-        .route("/images/pending-moderation", get(list_pending_moderation))
-        .route("/images/moderate", post(moderate_image))
-        .route("/images/:id/audit", get(get_image_audit))
-        
         // Health check
         .route("/health", get(health_check))
-        
         // Root/index page
         .route("/", get(index_page))
-        
         // PACT verification endpoint
         .route("/api/pact", get(pact_contracts))
-        
+        // Everything else goes to the lambdas
+        .fallback(lambda_proxy)
         .layer(CorsLayer::permissive())
-        .with_state(state);
+        .with_state(state)
+}
+
+#[tokio::main]
+async fn main() {
+    // Initialize tracing
+    tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).init();
+
+    let lambdas = Lambdas::in_memory();
+    lambdas.seed().await;
+
+    let state = AppState { scans: Arc::new(Mutex::new(Vec::new())), lambdas: Arc::new(lambdas) };
 
     let addr = "127.0.0.1:3000";
-    info!("🔫 Mock API Server starting on http://{}", addr);
+    info!("🔫 Dev API Server starting on http://{}", addr);
     info!("📋 PACT contracts available at http://{}/api/pact", addr);
     info!("🏥 Health check at http://{}/health", addr);
+    info!("🔑 Unauthenticated requests act as {} ({}); set X-Dev-User / X-Dev-Roles to change", DEV_USER, DEV_ROLES);
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app(state)).await.unwrap();
+}
+
+// LAMBDA PROXY
+
+async fn lambda_proxy(State(state): State<AppState>, request: axum::extract::Request) -> Response {
+    let event = match to_proxy_request(request).await {
+        Ok(event) => event,
+        Err(err) => return from_proxy_response(err.into_response()),
+    };
+    info!("{} {} -> lambda", event.http_method, event.path.as_deref().unwrap_or_default());
+
+    let response = state.lambdas.invoke(event).await.unwrap_or_else(|err| ApiError::from(err).into_response());
+    from_proxy_response(response)
+}
+
+/// Build the event API Gateway would deliver for `request`
+///
+/// The web pages prefix some paths with `/api`; API Gateway does not, so it is
+/// stripped. Without an `Authorization` header the caller is the dev user, passed
+/// as authorizer claims; `X-Dev-User` and `X-Dev-Roles` override it. Bearer
+/// tokens are left for the lambda to verify against `JWT_SECRET`.
+async fn to_proxy_request(request: axum::extract::Request) -> Result<ApiGatewayProxyRequest, ApiError> {
+    let (parts, body) = request.into_parts();
+
+    let path = parts.uri.path();
+    let path = match path.strip_prefix("/api/") {
+        Some(rest) => format!("/{}", rest),
+        None => path.to_string(),
+    };
+    let query: QueryMap = parts.uri.query().unwrap_or_default().parse().unwrap_or_default();
+
+    let bytes = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|err| ApiError::validation(format!("Unreadable request body: {}", err)))?;
+    let body = if bytes.is_empty() {
+        None
+    } else {
+        Some(String::from_utf8(bytes.to_vec()).map_err(|_| ApiError::validation("Request body must be UTF-8"))?)
+    };
+
+    let mut event = ApiGatewayProxyRequest {
+        http_method: parts.method,
+        path: Some(path),
+        query_string_parameters: query.clone(),
+        multi_value_query_string_parameters: query,
+        body,
+        ..Default::default()
+    };
+
+    if !parts.headers.contains_key("authorization") {
+        let header = |name: &str, default: &'static str| {
+            parts.headers.get(name).and_then(|value| value.to_str().ok()).unwrap_or(default).to_string()
+        };
+        event.request_context.authorizer.fields.insert(
+            "claims".to_string(),
+            serde_json::json!({ "sub": header("x-dev-user", DEV_USER), "roles": header("x-dev-roles", DEV_ROLES) }),
+        );
+    }
+    event.headers = parts.headers;
+
+    Ok(event)
+}
+
+fn from_proxy_response(response: ApiGatewayProxyResponse) -> Response {
+    let status = u16::try_from(response.status_code)
+        .ok()
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let body = match response.body {
+        Some(LambdaBody::Text(text)) => Body::from(text),
+        Some(LambdaBody::Binary(bytes)) => Body::from(bytes),
+        Some(LambdaBody::Empty) | None => Body::empty(),
+    };
+
+    let mut reply = (status, body).into_response();
+    reply.headers_mut().extend(response.headers);
+    reply
 }
 
 // HANDLERS
@@ -200,23 +242,24 @@ async fn health_check() -> Json<serde_json::Value> {
 }
 
 async fn index_page() -> axum::response::Html<String> {
-    axum::response::Html(format!(r#"
+    axum::response::Html(r#"
 <!DOCTYPE html>
 <html>
 <head>
-    <title>🌱 Seed Box Bag Box - Mock API</title>
+    <title>🌱 Seed Box Bag Box - Dev API</title>
     <style>
-        body {{ font-family: monospace; padding: 40px; background: #f5f5f5; }}
-        h1 {{ color: #009688; }}
-        .endpoint {{ background: #fff; padding: 15px; margin: 10px 0; border-left: 4px solid #009688; }}
-        .method {{ color: #00BCD4; font-weight: bold; }}
-        a {{ color: #009688; text-decoration: none; }}
-        a:hover {{ text-decoration: underline; }}
+        body { font-family: monospace; padding: 40px; background: #f5f5f5; }
+        h1 { color: #009688; }
+        .endpoint { background: #fff; padding: 15px; margin: 10px 0; border-left: 4px solid #009688; }
+        .method { color: #00BCD4; font-weight: bold; }
+        a { color: #009688; text-decoration: none; }
+        a:hover { text-decoration: underline; }
     </style>
 </head>
 <body>
-    <h1>🔫 Seed Box Bag Box - Mock API Server</h1>
-    <p>Server is running! Here are the available endpoints:</p>
+    <h1>🔫 Seed Box Bag Box - Dev API Server</h1>
+    <p>Server is running the real lambda handlers against in-memory tables. Paths may be prefixed with <code>/api</code>.</p>
+    <p>Requests without an <code>Authorization</code> header act as an admin; send <code>X-Dev-User</code> and <code>X-Dev-Roles</code> to be someone else.</p>
     
     <h2>📋 Meta</h2>
     <div class="endpoint"><span class="method">GET</span> <a href="/health">/health</a> - Health check</div>
@@ -226,19 +269,16 @@ async fn index_page() -> axum::response::Html<String> {
     <div class="endpoint"><span class="method">POST</span> /api/scan - Record barcode scan</div>
     <div class="endpoint"><span class="method">GET</span> /api/scans - List scans</div>
     
-    <h2>📦 Queue</h2>
-    <div class="endpoint"><span class="method">POST</span> /api/queue - Create queue item</div>
-    <div class="endpoint"><span class="method">GET</span> /api/queue - List queue</div>
-    
-    <h2>🧊 Storage</h2>
-    <div class="endpoint"><span class="method">POST</span> /api/storage/seeds - Store seed</div>
-    <div class="endpoint"><span class="method">GET</span> /api/storage/seeds - List storage</div>
-    
-    <h2>📸 Images</h2>
-    <div class="endpoint"><span class="method">POST</span> /images/request-upload - Request presigned URL</div>
-    <div class="endpoint"><span class="method">POST</span> /images/confirm-upload - Confirm upload</div>
-    <div class="endpoint"><span class="method">GET</span> /images/my-images - List my images</div>
-    <div class="endpoint"><span class="method">GET</span> /images/pending-moderation - Pending images</div>
+    <h2>λ Lambdas</h2>
+    <div class="endpoint">/subscriptions/* - subscription-service</div>
+    <div class="endpoint">/shipments/* - shipping-service</div>
+    <div class="endpoint">/inventory/* - inventory-service</div>
+    <div class="endpoint">/greenhouse/* - greenhouse-service</div>
+    <div class="endpoint">/processing/* - plant-processing-service</div>
+    <div class="endpoint">/germination/* - germination-service</div>
+    <div class="endpoint">/queue/*, /storage/*, /greenhouse/workflow - manufacturing-queue-service</div>
+    <div class="endpoint">/images/* - image-service</div>
+    <div class="endpoint">/recalls/* - recall-service</div>
     
     <h2>🌐 Web Interfaces</h2>
     <p>Open these files in your browser (in the <code>web/</code> directory):</p>
@@ -257,7 +297,7 @@ async fn index_page() -> axum::response::Html<String> {
     </p>
 </body>
 </html>
-    "#))
+    "#.to_string())
 }
 
 async fn handle_scan(
@@ -265,23 +305,22 @@ async fn handle_scan(
     Json(payload): Json<ScanRequest>,
 ) -> (StatusCode, Json<serde_json::Value>) {
     info!("📦 Scan received: {} ({})", payload.code, payload.scan_type);
-    
-    let scan = Scan {
-        id: Uuid::new_v4(),
-        code: payload.code,
-        scan_type: payload.scan_type,
-        timestamp: payload.timestamp,
-    };
-    
+
+    let scan =
+        Scan { id: Uuid::new_v4(), code: payload.code, scan_type: payload.scan_type, timestamp: payload.timestamp };
+
     state.scans.lock().await.push(scan.clone());
-    
-    (StatusCode::CREATED, Json(serde_json::json!({
-        "scan_id": scan.id,
-        "code": scan.code,
-        "type": scan.scan_type,
-        "status": "processed",
-        "timestamp": scan.timestamp
-    })))
+
+    (
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "scan_id": scan.id,
+            "code": scan.code,
+            "type": scan.scan_type,
+            "status": "processed",
+            "timestamp": scan.timestamp
+        })),
+    )
 }
 
 async fn list_scans(State(state): State<AppState>) -> Json<serde_json::Value> {
@@ -292,446 +331,70 @@ async fn list_scans(State(state): State<AppState>) -> Json<serde_json::Value> {
     }))
 }
 
-async fn create_queue(
-    State(state): State<AppState>,
-    Json(payload): Json<CreateQueueRequest>,
-) -> (StatusCode, Json<serde_json::Value>) {
-    let queue_item = QueueItem {
-        id: Uuid::new_v4(),
-        queue_type: payload.queue_type,
-        priority: payload.priority,
-        status: "PENDING".to_string(),
-        created_at: chrono::Utc::now().to_rfc3339(),
-    };
-    
-    state.queue.lock().await.push(queue_item.clone());
-    
-    (StatusCode::CREATED, Json(serde_json::json!({
-        "queue_id": queue_item.id,
-        "status": queue_item.status,
-        "priority": queue_item.priority,
-        "created_at": queue_item.created_at
-    })))
-}
+/// The generated contracts the lambdas are verified against, keyed by provider
+async fn pact_contracts() -> Json<serde_json::Value> {
+    let contracts: serde_json::Map<String, serde_json::Value> = contracts::contracts()
+        .into_iter()
+        .map(|contract| (contract.provider.to_string(), serde_json::json!(contract)))
+        .collect();
 
-async fn list_queue(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let queue = state.queue.lock().await;
-    let pending = queue.iter().filter(|q| q.status == "PENDING").count();
-    let in_progress = queue.iter().filter(|q| q.status == "IN_PROGRESS").count();
-    
     Json(serde_json::json!({
-        "queue_items": queue.clone(),
-        "pending_count": pending,
-        "in_progress_count": in_progress
+        "service": "seed-box-bag-box",
+        "version": "0.1.0",
+        "contracts": contracts
     }))
 }
 
-async fn start_queue(
-    State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-) -> Json<serde_json::Value> {
-    let mut queue = state.queue.lock().await;
-    if let Some(item) = queue.iter_mut().find(|q| q.id == id) {
-        item.status = "IN_PROGRESS".to_string();
-    }
-    
-    Json(serde_json::json!({
-        "queue_id": id,
-        "status": "IN_PROGRESS"
-    }))
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+    use tower::ServiceExt;
 
-async fn complete_queue(
-    State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-) -> Json<serde_json::Value> {
-    let mut queue = state.queue.lock().await;
-    if let Some(item) = queue.iter_mut().find(|q| q.id == id) {
-        item.status = "COMPLETED".to_string();
+    fn test_app() -> Router {
+        app(AppState { scans: Arc::new(Mutex::new(Vec::new())), lambdas: Arc::new(Lambdas::in_memory()) })
     }
-    
-    Json(serde_json::json!({
-        "queue_id": id,
-        "status": "COMPLETED"
-    }))
-}
-
-async fn store_seed(
-    State(state): State<AppState>,
-    Json(payload): Json<StoreSeedRequest>,
-) -> (StatusCode, Json<serde_json::Value>) {
-    let storage = SeedStorage {
-        id: Uuid::new_v4(),
-        seed_id: payload.seed_id,
-        species: payload.species.clone(),
-        location: format!("{}/{}", payload.facility, payload.room),
-        temperature: 5.0,
-        stored_at: chrono::Utc::now().to_rfc3339(),
-    };
-    
-    state.seeds.lock().await.push(storage.clone());
-    
-    (StatusCode::CREATED, Json(serde_json::json!({
-        "storage_id": storage.id,
-        "seed_id": storage.seed_id,
-        "location": storage.location,
-        "refrigeration": true,
-        "temperature_range": "2°C - 8°C (optimal: 5°C)",
-        "max_storage_days": 1460
-    })))
-}
-
-async fn list_storage(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let seeds = state.seeds.lock().await;
-    Json(serde_json::json!({
-        "storage_locations": seeds.clone(),
-        "total_seeds": seeds.len(),
-        "refrigerated_count": seeds.len()
-    }))
-}
-
-async fn get_storage_guide(Path(species): Path<String>) -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "species": species,
-        "storage_requirements": {
-            "temperature_celsius": { "min": 2.0, "max": 8.0, "optimal": 5.0 },
-            "humidity_percent": { "min": 20.0, "max": 40.0, "optimal": 30.0 },
-            "light": "DARK",
-            "refrigeration_required": true,
-            "max_storage_days": 1460
-        },
-        "special_instructions": ["Store in airtight container", "Label with variety and date"]
-    }))
-}
 
-async fn create_subscription(
-    State(state): State<AppState>,
-    Json(payload): Json<CreateSubscriptionRequest>,
-) -> (StatusCode, Json<serde_json::Value>) {
-    let price = match payload.tier.as_str() {
-        "BRING_YOUR_OWN_BAGS" => 800,
-        "STANDARD" => 1500,
-        "PREMIUM" => 1900,
-        _ => 1500,
-    };
-    
-    let sub = Subscription {
-        id: Uuid::new_v4(),
-        customer_id: Uuid::new_v4(),
-        tier: payload.tier,
-        status: "ACTIVE".to_string(),
-        price_cents: price,
-    };
-    
-    state.subscriptions.lock().await.push(sub.clone());
-    
-    (StatusCode::CREATED, Json(serde_json::json!({
-        "subscription_id": sub.id,
-        "customer_id": sub.customer_id,
-        "tier": sub.tier,
-        "status": sub.status,
-        "monthly_price_cents": sub.price_cents,
-        "bags_required": sub.tier == "BRING_YOUR_OWN_BAGS"
-    })))
-}
-
-async fn get_subscription(Path(id): Path<Uuid>) -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "subscription_id": id,
-        "tier": "STANDARD",
-        "status": "ACTIVE",
-        "monthly_price_cents": 1500
-    }))
-}
-
-async fn start_germination(Json(payload): Json<serde_json::Value>) -> (StatusCode, Json<serde_json::Value>) {
-    (StatusCode::CREATED, Json(serde_json::json!({
-        "germination_record_id": Uuid::new_v4(),
-        "seed_id": payload.get("seed_id"),
-        "species": payload.get("species"),
-        "phase": "IMBIBITION",
-        "estimated_ship_date": chrono::Utc::now() + chrono::Duration::days(14)
-    })))
-}
-
-async fn list_ready_germination() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "ready_count": 0,
-        "records": []
-    }))
-}
-
-async fn list_zones() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "zones": []
-    }))
-}
-
-async fn list_plants() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "plants": []
-    }))
-}
-
-async fn request_presigned_url(
-    State(state): State<AppState>,
-    Json(payload): Json<serde_json::Value>,
-) -> (StatusCode, Json<serde_json::Value>) {
-    let upload_id = Uuid::new_v4();
-    let s3_key = format!("images/{}", upload_id);
-    
-    (StatusCode::OK, Json(serde_json::json!({
-        "upload_id": upload_id,
-        "presigned_url": format!("https://mock-s3-url/{}", s3_key),
-        "s3_key": s3_key,
-        "expires_in_seconds": 3600
-    })))
-}
-
-async fn confirm_upload(
-    State(state): State<AppState>,
-    Json(payload): Json<serde_json::Value>,
-) -> (StatusCode, Json<serde_json::Value>) {
-    let image_id = Uuid::new_v4();
-    let user_id = Uuid::new_v4();
-    
-    let image = PlantImage {
-        id: image_id,
-        uploaded_by: user_id,
-        item_id: Uuid::new_v4(),
-        item_type: "plant".to_string(),
-        filename: payload.get("metadata").and_then(|m| m.get("filename")).and_then(|f| f.as_str()).unwrap_or("image.jpg").to_string(),
-        s3_url: format!("https://via.placeholder.com/400x300/C8E6C9/009688?text=Plant+Photo"),
-        caption: None,
-        moderation_status: "PENDING".to_string(),
-        uploaded_at: chrono::Utc::now().to_rfc3339(),
-        deleted_at: None,
-    };
-    
-    state.images.lock().await.push(image.clone());
-    
-    // Audit log
-    let audit = AuditLog {
-        id: Uuid::new_v4(),
-        image_id,
-        action: "UPLOADED".to_string(),
-        performed_by: user_id,
-        performed_at: chrono::Utc::now().to_rfc3339(),
-        details: Some(format!("Uploaded {}", image.filename)),
-    };
-    state.audit_logs.lock().await.push(audit);
-    
-    (StatusCode::CREATED, Json(serde_json::to_value(&image).unwrap()))
-}
-
-async fn list_my_images(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let images = state.images.lock().await;
-    Json(serde_json::json!({
-        "images": images.clone(),
-        "count": images.len()
-    }))
-}
-
-async fn delete_image(
-    State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-) -> Json<serde_json::Value> {
-    let user_id = Uuid::new_v4();
-    
-    // Soft delete
-    let mut images = state.images.lock().await;
-    if let Some(image) = images.iter_mut().find(|img| img.id == id) {
-        image.deleted_at = Some(chrono::Utc::now().to_rfc3339());
-        image.moderation_status = "DELETED".to_string();
+    async fn send(app: &Router, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), MAX_BODY_BYTES).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
     }
-    
-    // Audit log
-    let audit = AuditLog {
-        id: Uuid::new_v4(),
-        image_id: id,
-        action: "DELETED".to_string(),
-        performed_by: user_id,
-        performed_at: chrono::Utc::now().to_rfc3339(),
-        details: Some("User deleted their own image".to_string()),
-    };
-    state.audit_logs.lock().await.push(audit);
-    
-    Json(serde_json::json!({
-        "status": "deleted",
-        "audit_logged": true
-    }))
-}
-
-async fn list_pending_moderation(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let images = state.images.lock().await;
-    let pending: Vec<_> = images.iter()
-        .filter(|img| img.moderation_status == "PENDING")
-        .cloned()
-        .collect();
-    
-    Json(serde_json::json!({
-        "pending_images": pending,
-        "count": pending.len()
-    }))
-}
 
-async fn moderate_image(
-    State(state): State<AppState>,
-    Json(payload): Json<serde_json::Value>,
-) -> Json<serde_json::Value> {
-    let image_id = payload.get("image_id").and_then(|v| v.as_str()).unwrap_or("");
-    let status = payload.get("status").and_then(|v| v.as_str()).unwrap_or("APPROVED");
-    let moderator_id = Uuid::new_v4();
-    
-    // Update image
-    let mut images = state.images.lock().await;
-    if let Ok(id) = Uuid::parse_str(image_id) {
-        if let Some(image) = images.iter_mut().find(|img| img.id == id) {
-            image.moderation_status = status.to_string();
-        }
+    #[tokio::test]
+    async fn test_requests_reach_the_lambda_handlers() {
+        let app = test_app();
+
+        let create = Request::post("/api/queue")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"queueType": {"BAG_CLEANING": {"bag_ids": []}}, "priority": "high"}"#))
+            .unwrap();
+        let (status, created) = send(&app, create).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["priority"], "high");
+
+        let (status, listed) = send(&app, Request::get("/queue?limit=10").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(listed["queue_items"][0]["id"], created["queueId"]);
+
+        let (status, missing) = send(&app, Request::get("/api/nowhere").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(missing["code"], "not_found");
     }
-    
-    // Audit log
-    let audit = AuditLog {
-        id: Uuid::new_v4(),
-        image_id: Uuid::parse_str(image_id).unwrap_or_default(),
-        action: status.to_string(),
-        performed_by: moderator_id,
-        performed_at: chrono::Utc::now().to_rfc3339(),
-        details: payload.get("notes").and_then(|v| v.as_str()).map(String::from),
-    };
-    state.audit_logs.lock().await.push(audit);
-    
-    Json(serde_json::json!({
-        "image_id": image_id,
-        "status": status,
-        "moderated_by": moderator_id,
-        "audit_logged": true
-    }))
-}
 
-async fn get_image_audit(
-    State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-) -> Json<serde_json::Value> {
-    let logs = state.audit_logs.lock().await;
-    let image_logs: Vec<_> = logs.iter()
-        .filter(|log| log.image_id == id)
-        .cloned()
-        .collect();
-    
-    Json(serde_json::json!({
-        "image_id": id,
-        "audit_logs": image_logs,
-        "count": image_logs.len()
-    }))
-}
+    #[tokio::test]
+    async fn test_dev_headers_choose_the_caller() {
+        let app = test_app();
+        let pending = |roles: &str| {
+            Request::get("/images/pending-moderation").header("x-dev-roles", roles).body(Body::empty()).unwrap()
+        };
 
-async fn pact_contracts() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "service": "seed-box-bag-box",
-        "version": "0.1.0",
-        "contracts": {
-            "scanner": {
-                "POST /api/scan": {
-                    "request": {
-                        "body": {
-                            "code": "string",
-                            "type": "string",
-                            "timestamp": "ISO8601"
-                        }
-                    },
-                    "response": {
-                        "status": 201,
-                        "body": {
-                            "scan_id": "uuid",
-                            "code": "string",
-                            "type": "string",
-                            "status": "processed"
-                        }
-                    }
-                },
-                "GET /api/scans": {
-                    "response": {
-                        "status": 200,
-                        "body": {
-                            "scans": "array",
-                            "count": "number"
-                        }
-                    }
-                }
-            },
-            "queue": {
-                "POST /api/queue": {
-                    "request": {
-                        "body": {
-                            "queue_type": "string",
-                            "priority": "string"
-                        }
-                    },
-                    "response": {
-                        "status": 201,
-                        "body": {
-                            "queue_id": "uuid",
-                            "status": "string",
-                            "priority": "string"
-                        }
-                    }
-                },
-                "GET /api/queue": {
-                    "response": {
-                        "status": 200,
-                        "body": {
-                            "queue_items": "array",
-                            "pending_count": "number",
-                            "in_progress_count": "number"
-                        }
-                    }
-                }
-            },
-            "storage": {
-                "POST /api/storage/seeds": {
-                    "request": {
-                        "body": {
-                            "seed_id": "uuid",
-                            "species": "string",
-                            "facility": "string",
-                            "room": "string"
-                        }
-                    },
-                    "response": {
-                        "status": 201,
-                        "body": {
-                            "storage_id": "uuid",
-                            "seed_id": "uuid",
-                            "location": "string",
-                            "refrigeration": "boolean",
-                            "temperature_range": "string"
-                        }
-                    }
-                }
-            },
-            "subscriptions": {
-                "POST /api/subscriptions": {
-                    "request": {
-                        "body": {
-                            "customer_email": "email",
-                            "customer_name": "string",
-                            "tier": "enum[BRING_YOUR_OWN_BAGS, STANDARD, PREMIUM]"
-                        }
-                    },
-                    "response": {
-                        "status": 201,
-                        "body": {
-                            "subscription_id": "uuid",
-                            "customer_id": "uuid",
-                            "tier": "string",
-                            "monthly_price_cents": "number"
-                        }
-                    }
-                }
-            }
-        }
-    }))
-}
+        let (status, problem) = send(&app, pending("customer")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(problem["code"], "forbidden");
 
+        let (status, _) = send(&app, pending("moderator")).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
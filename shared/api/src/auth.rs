@@ -0,0 +1,226 @@
+//! Who is calling: a [`Principal`] resolved from API Gateway's authorizer
+//! context, or from a bearer JWT when the request did not pass through one
+
+use std::fmt;
+use std::str::FromStr;
+
+use aws_lambda_events::apigw::ApiGatewayProxyRequest;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::ApiError;
+
+/// Environment variable holding the HS256 secret for bearer tokens
+pub const JWT_SECRET_VAR: &str = "JWT_SECRET";
+
+/// Roles are ranked customer < staff < moderator < admin; holding a role
+/// grants everything the roles below it can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Customer,
+    Staff,
+    Moderator,
+    Admin,
+}
+
+impl FromStr for Role {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s.to_lowercase().as_str() {
+            "customer" | "customers" => Ok(Self::Customer),
+            "staff" => Ok(Self::Staff),
+            "moderator" | "moderators" => Ok(Self::Moderator),
+            "admin" | "admins" => Ok(Self::Admin),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Customer => "customer",
+            Self::Staff => "staff",
+            Self::Moderator => "moderator",
+            Self::Admin => "admin",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The authenticated caller
+#[derive(Debug, Clone, PartialEq)]
+pub struct Principal {
+    /// The token's `sub`
+    pub user_id: Uuid,
+    pub roles: Vec<Role>,
+}
+
+impl Principal {
+    pub fn has_role(&self, role: Role) -> bool {
+        self.roles.iter().any(|held| *held >= role)
+    }
+
+    /// Fails with 403 unless the caller holds `role`
+    pub fn require(&self, role: Role) -> Result<(), ApiError> {
+        if self.has_role(role) {
+            Ok(())
+        } else {
+            Err(ApiError::forbidden(format!("Requires the {} role", role)))
+        }
+    }
+
+    /// Read the caller out of token claims: `sub` plus roles from `roles` or `cognito:groups`
+    fn from_claims(claims: &Map<String, Value>) -> Result<Self, ApiError> {
+        let user_id = claims
+            .get("sub")
+            .and_then(Value::as_str)
+            .and_then(|sub| Uuid::parse_str(sub).ok())
+            .ok_or_else(|| ApiError::unauthorized("Token subject is not a user id"))?;
+
+        let roles = ["roles", "cognito:groups"]
+            .iter()
+            .filter_map(|claim| claims.get(*claim))
+            .flat_map(role_names)
+            .filter_map(|name| name.parse().ok())
+            .collect();
+
+        Ok(Self { user_id, roles })
+    }
+}
+
+/// A roles claim is a JSON array from our own tokens, but API Gateway flattens
+/// arrays into strings such as `[moderator staff]` or `moderator,staff`
+fn role_names(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        Value::String(joined) => joined
+            .trim_matches(|c| c == '[' || c == ']')
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Resolves the [`Principal`] for a request
+///
+/// Claims placed in the request context by an API Gateway authorizer are
+/// trusted as-is, since API Gateway has already verified the token. Otherwise an
+/// `Authorization: Bearer` token is verified with the HS256 secret; without a
+/// secret configured, bearer tokens are rejected.
+#[derive(Clone, Default)]
+pub struct Authenticator {
+    key: Option<DecodingKey>,
+}
+
+impl Authenticator {
+    /// Reads the bearer token secret from [`JWT_SECRET_VAR`]
+    pub fn from_env() -> Self {
+        match std::env::var(JWT_SECRET_VAR) {
+            Ok(secret) if !secret.is_empty() => Self::with_secret(secret.as_bytes()),
+            _ => Self::default(),
+        }
+    }
+
+    pub fn with_secret(secret: &[u8]) -> Self {
+        Self { key: Some(DecodingKey::from_secret(secret)) }
+    }
+
+    /// `None` for an anonymous request; 401 when credentials are present but invalid
+    pub fn authenticate(&self, request: &ApiGatewayProxyRequest) -> Result<Option<Principal>, ApiError> {
+        if let Some(claims) = context_claims(request) {
+            return Principal::from_claims(&claims).map(Some);
+        }
+
+        let Some(header) = request.headers.get("authorization") else {
+            return Ok(None);
+        };
+        let token = header
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| ApiError::unauthorized("Authorization must be a Bearer token"))?;
+        let key = self.key.as_ref().ok_or_else(|| ApiError::unauthorized("Bearer tokens are not accepted here"))?;
+
+        let claims = jsonwebtoken::decode::<Map<String, Value>>(token, key, &Validation::new(Algorithm::HS256))
+            .map_err(|err| {
+                warn!("Rejected bearer token: {}", err);
+                ApiError::unauthorized("Invalid or expired token")
+            })?
+            .claims;
+        Principal::from_claims(&claims).map(Some)
+    }
+}
+
+/// Claims from an HTTP API JWT authorizer, a REST API Cognito authorizer
+/// (`authorizer.claims`), or a Lambda authorizer's context (`authorizer.sub`)
+fn context_claims(request: &ApiGatewayProxyRequest) -> Option<Map<String, Value>> {
+    let authorizer = &request.request_context.authorizer;
+    if let Some(jwt) = &authorizer.jwt {
+        return Some(jwt.claims.iter().map(|(k, v)| (k.clone(), Value::String(v.clone()))).collect());
+    }
+    if let Some(Value::Object(claims)) = authorizer.fields.get("claims") {
+        return Some(claims.clone());
+    }
+    if authorizer.fields.contains_key("sub") {
+        return Some(authorizer.fields.iter().map(|(k, v)| (k.clone(), v.clone())).collect());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{EncodingKey, Header};
+
+    fn with_bearer(token: &str) -> ApiGatewayProxyRequest {
+        let mut request = ApiGatewayProxyRequest::default();
+        request.headers.insert("authorization", format!("Bearer {}", token).parse().unwrap());
+        request
+    }
+
+    #[test]
+    fn test_roles_are_ranked() {
+        let moderator = Principal { user_id: Uuid::nil(), roles: vec![Role::Moderator] };
+        assert!(moderator.has_role(Role::Staff));
+        assert!(moderator.has_role(Role::Moderator));
+        assert!(!moderator.has_role(Role::Admin));
+        assert_eq!(moderator.require(Role::Admin).unwrap_err().status_code(), 403);
+    }
+
+    #[test]
+    fn test_authorizer_context_groups() {
+        let user_id = Uuid::new_v4();
+        let mut request = ApiGatewayProxyRequest::default();
+        request.request_context.authorizer.fields.insert(
+            "claims".to_string(),
+            serde_json::json!({ "sub": user_id.to_string(), "cognito:groups": "[staff moderator]" }),
+        );
+
+        let principal = Authenticator::default().authenticate(&request).unwrap().unwrap();
+        assert_eq!(principal.user_id, user_id);
+        assert_eq!(principal.roles, vec![Role::Staff, Role::Moderator]);
+    }
+
+    #[test]
+    fn test_bearer_token_is_verified() {
+        let user_id = Uuid::new_v4();
+        let claims = serde_json::json!({ "sub": user_id, "roles": ["customer"], "exp": u32::MAX });
+        let token = jsonwebtoken::encode(&Header::default(), &claims, &EncodingKey::from_secret(b"s3cret")).unwrap();
+
+        let principal = Authenticator::with_secret(b"s3cret").authenticate(&with_bearer(&token)).unwrap().unwrap();
+        assert_eq!(principal.roles, vec![Role::Customer]);
+
+        let forged = Authenticator::with_secret(b"other").authenticate(&with_bearer(&token));
+        assert_eq!(forged.unwrap_err().status_code(), 401);
+        assert!(Authenticator::default().authenticate(&with_bearer(&token)).is_err());
+        assert_eq!(Authenticator::default().authenticate(&ApiGatewayProxyRequest::default()).unwrap(), None);
+    }
+}
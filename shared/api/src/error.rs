@@ -0,0 +1,280 @@
+use aws_lambda_events::apigw::ApiGatewayProxyResponse;
+use database::DatabaseError;
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+use tracing::error;
+
+use crate::response;
+
+/// Media type of every error body (RFC 7807)
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// One rejected input, named by its path in the request (e.g. `metadata.itemType`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// A failed request, rendered as an RFC 7807 problem+json body
+///
+/// Each variant has a stable `code` the web UI can switch on; `detail` is the
+/// human-readable message.
+#[derive(Debug, Error)]
+pub enum ApiError {
+    /// Malformed input: bad JSON, missing body, unparseable parameters.
+    /// `errors` names the offending fields when they are known
+    #[error("{message}")]
+    Validation { message: String, errors: Vec<FieldError> },
+
+    /// No credentials, or credentials that failed verification
+    #[error("{0}")]
+    Unauthorized(String),
+
+    /// Authenticated, but not allowed to do this
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("Method {0} not allowed")]
+    MethodNotAllowed(String),
+
+    /// The write lost a race or the item already exists; `current` is the
+    /// stored state when known, so the caller can retry against it
+    #[error("{message}")]
+    Conflict { message: String, current: Option<Value> },
+
+    /// A service we depend on (DynamoDB, ShipStation, ...) failed; the detail is logged, not returned
+    #[error("{0}")]
+    UpstreamFailure(String),
+
+    /// Anything the caller cannot fix; the detail is logged, not returned
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl ApiError {
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::Validation { message: message.into(), errors: Vec::new() }
+    }
+
+    /// Validation failure pinned to a single field or parameter
+    pub fn invalid_field(field: impl Into<String>, message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self::Validation { errors: vec![FieldError { field: field.into(), message: message.clone() }], message }
+    }
+
+    /// A species name that `models::Species::resolve` does not recognise
+    pub fn unknown_species(field: impl Into<String>, name: &str) -> Self {
+        Self::invalid_field(field, format!("Unknown species '{}'", name))
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::Unauthorized(message.into())
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::Forbidden(message.into())
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound(message.into())
+    }
+
+    pub fn status_code(&self) -> u16 {
+        match self {
+            Self::Validation { .. } => 400,
+            Self::Unauthorized(_) => 401,
+            Self::Forbidden(_) => 403,
+            Self::NotFound(_) => 404,
+            Self::MethodNotAllowed(_) => 405,
+            Self::Conflict { .. } => 409,
+            Self::Internal(_) => 500,
+            Self::UpstreamFailure(_) => 502,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Validation { .. } => "validation",
+            Self::Unauthorized(_) => "unauthorized",
+            Self::Forbidden(_) => "forbidden",
+            Self::NotFound(_) => "not_found",
+            Self::MethodNotAllowed(_) => "method_not_allowed",
+            Self::Conflict { .. } => "conflict",
+            Self::UpstreamFailure(_) => "upstream_failure",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Validation { .. } => "Invalid request",
+            Self::Unauthorized(_) => "Unauthorized",
+            Self::Forbidden(_) => "Forbidden",
+            Self::NotFound(_) => "Not found",
+            Self::MethodNotAllowed(_) => "Method not allowed",
+            Self::Conflict { .. } => "Conflict",
+            Self::UpstreamFailure(_) => "Upstream service failed",
+            Self::Internal(_) => "Internal server error",
+        }
+    }
+
+    /// The problem+json document for this error
+    pub fn problem(&self) -> Value {
+        let detail = match self {
+            Self::UpstreamFailure(_) | Self::Internal(_) => self.title().to_string(),
+            other => other.to_string(),
+        };
+        let mut problem = serde_json::json!({
+            "type": format!("/problems/{}", self.code()),
+            "title": self.title(),
+            "status": self.status_code(),
+            "detail": detail,
+            "code": self.code(),
+        });
+        match self {
+            Self::Validation { errors, .. } if !errors.is_empty() => {
+                problem["errors"] = serde_json::json!(errors);
+            }
+            Self::Conflict { current: Some(current), .. } => {
+                problem["current"] = current.clone();
+            }
+            _ => {}
+        }
+        problem
+    }
+
+    pub fn into_response(self) -> ApiGatewayProxyResponse {
+        if let Self::UpstreamFailure(detail) | Self::Internal(detail) = &self {
+            error!("{}: {}", self.title(), detail);
+        }
+        response::with_content_type(self.status_code(), self.problem().to_string(), PROBLEM_JSON)
+    }
+}
+
+/// serde_json appends " at line L column C", which means nothing to a form
+fn without_position(err: &serde_json::Error) -> String {
+    let message = err.to_string();
+    match message.rsplit_once(" at line ") {
+        Some((message, _)) => message.to_string(),
+        None => message,
+    }
+}
+
+/// Handlers only deserialize with `?`; serializing a response goes through
+/// [`response::json`], which reports its own failures as `Internal`
+impl From<serde_json::Error> for ApiError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::validation(format!("Invalid request: {}", without_position(&err)))
+    }
+}
+
+/// Body deserialization errors carry the path of the field that failed
+impl From<serde_path_to_error::Error<serde_json::Error>> for ApiError {
+    fn from(err: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        let path = err.path().to_string();
+        let inner = err.into_inner();
+        if inner.is_syntax() || inner.is_eof() {
+            return Self::validation(format!("Malformed JSON body: {}", without_position(&inner)));
+        }
+
+        let message = without_position(&inner);
+        // A missing field is reported against its parent, so name it ourselves
+        let missing = message.strip_prefix("missing field `").and_then(|rest| rest.strip_suffix('`'));
+        let field = match (path.as_str(), missing) {
+            (".", Some(name)) => name.to_string(),
+            (parent, Some(name)) => format!("{}.{}", parent, name),
+            (path, None) => path.to_string(),
+        };
+        Self::invalid_field(field, message)
+    }
+}
+
+impl From<DatabaseError> for ApiError {
+    fn from(err: DatabaseError) -> Self {
+        match err {
+            DatabaseError::NotFound(_) => Self::NotFound(err.to_string()),
+            DatabaseError::AlreadyExists(_) => Self::Conflict { message: err.to_string(), current: None },
+            DatabaseError::Conflict { ref key, ref current } => Self::Conflict {
+                message: format!("Item was modified concurrently: {}", key),
+                current: Some(current.clone()),
+            },
+            DatabaseError::InvalidPage(message) => Self::validation(message),
+            // Stored data we can't read is our fault, not the caller's
+            DatabaseError::InvalidData(_) => Self::Internal(err.to_string()),
+            DatabaseError::DynamoDb(_) => Self::UpstreamFailure(err.to_string()),
+            DatabaseError::Serialization(_) => Self::Internal(err.to_string()),
+        }
+    }
+}
+
+/// Catch-all for the `lambda_runtime::Error` type and other boxed errors
+impl From<Box<dyn std::error::Error + Send + Sync>> for ApiError {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        Self::Internal(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_errors_map_to_status_codes() {
+        let status = |err: DatabaseError| ApiError::from(err).status_code();
+
+        assert_eq!(status(DatabaseError::NotFound("x".into())), 404);
+        assert_eq!(status(DatabaseError::AlreadyExists("x".into())), 409);
+        assert_eq!(status(DatabaseError::InvalidData("x".into())), 500);
+        assert_eq!(status(DatabaseError::InvalidPage("x".into())), 400);
+        assert_eq!(status(DatabaseError::Conflict { key: "x".into(), current: serde_json::json!({}) }), 409);
+    }
+
+    #[test]
+    fn test_internal_error_hides_detail() {
+        let response = ApiError::Internal("connection reset".into()).into_response();
+        assert_eq!(response.status_code, 500);
+        let body = format!("{:?}", response.body);
+        assert!(!body.contains("connection reset"));
+    }
+
+    #[test]
+    fn test_body_errors_name_the_field() {
+        #[derive(Debug, serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        #[allow(dead_code)]
+        struct Upload {
+            item_id: uuid::Uuid,
+            metadata: Metadata,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        #[allow(dead_code)]
+        struct Metadata {
+            file_name: String,
+        }
+
+        let problem = |body: &str| {
+            let err = serde_path_to_error::deserialize::<_, Upload>(&mut serde_json::Deserializer::from_str(body))
+                .unwrap_err();
+            ApiError::from(err).problem()
+        };
+
+        let bad_id = problem(r#"{"itemId": "nope", "metadata": {"fileName": "a.png"}}"#);
+        assert_eq!(bad_id["code"], "validation");
+        assert_eq!(bad_id["errors"][0]["field"], "itemId");
+
+        let missing = problem(&format!(r#"{{"itemId": "{}", "metadata": {{}}}}"#, uuid::Uuid::nil()));
+        assert_eq!(missing["errors"][0]["field"], "metadata.fileName");
+        assert_eq!(missing["errors"][0]["message"], "missing field `fileName`");
+
+        let malformed = problem("{");
+        assert_eq!(malformed["status"], 400);
+        assert!(malformed.get("errors").is_none());
+    }
+}
@@ -0,0 +1,19 @@
+//! HTTP plumbing shared by the lambdas: routing API Gateway proxy requests to
+//! handlers, authenticating callers, extracting path/query/JSON input, and
+//! rendering responses and errors.
+
+use aws_lambda_events::apigw::ApiGatewayProxyResponse;
+
+pub mod auth;
+pub mod error;
+pub mod request;
+pub mod response;
+pub mod router;
+
+pub use auth::{Authenticator, Principal, Role};
+pub use error::{ApiError, FieldError, PROBLEM_JSON};
+pub use request::Request;
+pub use router::{Handler, Router};
+
+/// What every handler returns; errors are rendered by [`ApiError::into_response`]
+pub type ApiResult = Result<ApiGatewayProxyResponse, ApiError>;
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use aws_lambda_events::apigw::ApiGatewayProxyRequest;
+use aws_lambda_events::query_map::QueryMap;
+use database::PageRequest;
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::auth::Principal;
+use crate::ApiError;
+
+/// An API Gateway proxy request plus the path parameters its route captured
+/// and the caller, if authenticated
+#[derive(Debug, Clone)]
+pub struct Request {
+    inner: ApiGatewayProxyRequest,
+    params: HashMap<&'static str, String>,
+    principal: Option<Principal>,
+}
+
+impl Request {
+    pub fn new(inner: ApiGatewayProxyRequest) -> Self {
+        Self { inner, params: HashMap::new(), principal: None }
+    }
+
+    pub(crate) fn with_params(mut self, params: HashMap<&'static str, String>) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub(crate) fn with_principal(mut self, principal: Option<Principal>) -> Self {
+        self.principal = principal;
+        self
+    }
+
+    /// The authenticated caller; 401 for an anonymous request
+    pub fn principal(&self) -> Result<&Principal, ApiError> {
+        self.principal.as_ref().ok_or_else(|| ApiError::unauthorized("Authentication required"))
+    }
+
+    pub fn method(&self) -> &str {
+        self.inner.http_method.as_str()
+    }
+
+    pub fn path(&self) -> &str {
+        self.inner.path.as_deref().unwrap_or("")
+    }
+
+    pub fn query_map(&self) -> &QueryMap {
+        &self.inner.query_string_parameters
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.inner.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    pub fn inner(&self) -> &ApiGatewayProxyRequest {
+        &self.inner
+    }
+
+    /// A `{name}` segment of the matched route
+    pub fn param(&self, name: &str) -> Result<&str, ApiError> {
+        self.params
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| ApiError::Internal(format!("Route has no parameter {}", name)))
+    }
+
+    pub fn uuid_param(&self, name: &str) -> Result<Uuid, ApiError> {
+        let value = self.param(name)?;
+        Uuid::parse_str(value).map_err(|_| ApiError::invalid_field(name, format!("Invalid {}: {}", name, value)))
+    }
+
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.inner.query_string_parameters.first(name)
+    }
+
+    /// A query parameter the handler cannot do without; 400 when absent
+    pub fn required_query(&self, name: &str) -> Result<&str, ApiError> {
+        self.query(name).ok_or_else(|| ApiError::invalid_field(name, format!("Missing {} parameter", name)))
+    }
+
+    /// A query parameter parsed the way serde reads it from JSON, e.g. an enum
+    /// such as `?status=cleaned`
+    pub fn query_as<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, ApiError> {
+        self.query(name)
+            .map(|value| {
+                serde_json::from_value(serde_json::Value::String(value.to_string()))
+                    .map_err(|_| ApiError::invalid_field(name, format!("Invalid {}: {}", name, value)))
+            })
+            .transpose()
+    }
+
+    /// `?limit=&cursor=` for paged list endpoints
+    pub fn page(&self) -> Result<PageRequest, ApiError> {
+        Ok(PageRequest::from_params(self.query("limit"), self.query("cursor"))?)
+    }
+
+    /// The body deserialized as JSON; a rejected field is reported by its path
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, ApiError> {
+        let body = self.inner.body.as_deref().ok_or_else(|| ApiError::validation("Request body is required"))?;
+        Ok(serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(body))?)
+    }
+}
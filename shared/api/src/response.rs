@@ -0,0 +1,44 @@
+//! Builders for `ApiGatewayProxyResponse` with JSON bodies
+
+use aws_lambda_events::apigw::ApiGatewayProxyResponse;
+use aws_lambda_events::encodings::Body;
+use aws_lambda_events::http::header::CONTENT_TYPE;
+use aws_lambda_events::http::{HeaderMap, HeaderValue};
+use serde::Serialize;
+
+use crate::{ApiError, ApiResult};
+
+/// Serialize `body` as the response with the given status
+pub fn json<T: Serialize + ?Sized>(status: u16, body: &T) -> ApiResult {
+    let body = serde_json::to_string(body)
+        .map_err(|err| ApiError::Internal(format!("Failed to serialize response: {}", err)))?;
+    Ok(raw_json(status, body))
+}
+
+/// 200 with a JSON body
+pub fn ok<T: Serialize + ?Sized>(body: &T) -> ApiResult {
+    json(200, body)
+}
+
+/// 201 with a JSON body
+pub fn created<T: Serialize + ?Sized>(body: &T) -> ApiResult {
+    json(201, body)
+}
+
+/// Response with an already-serialized JSON body
+pub fn raw_json(status: u16, body: String) -> ApiGatewayProxyResponse {
+    with_content_type(status, body, "application/json")
+}
+
+pub(crate) fn with_content_type(status: u16, body: String, content_type: &'static str) -> ApiGatewayProxyResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+    ApiGatewayProxyResponse {
+        status_code: status as i64,
+        headers,
+        multi_value_headers: Default::default(),
+        body: Some(Body::Text(body)),
+        is_base64_encoded: false,
+    }
+}
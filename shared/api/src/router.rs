@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+
+use crate::auth::{Authenticator, Role};
+use crate::{ApiError, ApiResult, Request};
+
+/// An async handler taking shared state and the request
+///
+/// Implemented for any `async fn(&S, Request) -> ApiResult`.
+pub trait Handler<'a, S: 'a>: Send + Sync {
+    type Future: Future<Output = ApiResult> + Send + 'a;
+
+    fn call(&self, state: &'a S, request: Request) -> Self::Future;
+}
+
+impl<'a, S: 'a, F, Fut> Handler<'a, S> for F
+where
+    F: Fn(&'a S, Request) -> Fut + Send + Sync,
+    Fut: Future<Output = ApiResult> + Send + 'a,
+{
+    type Future = Fut;
+
+    fn call(&self, state: &'a S, request: Request) -> Fut {
+        self(state, request)
+    }
+}
+
+type BoxFuture<'a> = Pin<Box<dyn Future<Output = ApiResult> + Send + 'a>>;
+
+/// [`Handler`] with the future type erased so routes can share a `Vec`
+trait ErasedHandler<S>: Send + Sync {
+    fn call<'a>(&'a self, state: &'a S, request: Request) -> BoxFuture<'a>;
+}
+
+impl<S, H> ErasedHandler<S> for H
+where
+    H: for<'a> Handler<'a, S>,
+{
+    fn call<'a>(&'a self, state: &'a S, request: Request) -> BoxFuture<'a> {
+        Box::pin(Handler::call(self, state, request))
+    }
+}
+
+enum Segment {
+    Literal(&'static str),
+    Param(&'static str),
+}
+
+struct Route<S> {
+    method: &'static str,
+    segments: Vec<Segment>,
+    /// Minimum role the caller must hold; `None` for public routes
+    role: Option<Role>,
+    handler: Box<dyn ErasedHandler<S>>,
+}
+
+impl<S> Route<S> {
+    /// Path parameters if `path` fits this route's pattern
+    fn matches(&self, path: &[&str]) -> Option<HashMap<&'static str, String>> {
+        if path.len() != self.segments.len() {
+            return None;
+        }
+        let mut params = HashMap::new();
+        for (segment, part) in self.segments.iter().zip(path) {
+            match segment {
+                Segment::Literal(literal) if literal == part => {}
+                Segment::Literal(_) => return None,
+                Segment::Param(name) => {
+                    params.insert(*name, part.to_string());
+                }
+            }
+        }
+        Some(params)
+    }
+}
+
+/// Dispatches API Gateway proxy requests to handlers by method and path
+///
+/// Patterns are literal segments and `{name}` parameters, e.g. `/queue/{id}/start`.
+/// Routes are tried in the order they were added, so register literal paths such
+/// as `/germination/ready` before `/germination/{id}`.
+///
+/// Routes are public unless followed by [`Router::requires`]. Callers are
+/// authenticated by [`Authenticator::from_env`] unless another is supplied.
+pub struct Router<S> {
+    routes: Vec<Route<S>>,
+    authenticator: Authenticator,
+}
+
+impl<S> Default for Router<S> {
+    fn default() -> Self {
+        Self { routes: Vec::new(), authenticator: Authenticator::from_env() }
+    }
+}
+
+impl<S: Sync + 'static> Router<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route<H>(mut self, method: &'static str, pattern: &'static str, handler: H) -> Self
+    where
+        H: for<'a> Handler<'a, S> + 'static,
+    {
+        let segments = split(pattern)
+            .map(|segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => Segment::Param(name),
+                None => Segment::Literal(segment),
+            })
+            .collect();
+        self.routes.push(Route { method, segments, role: None, handler: Box::new(handler) });
+        self
+    }
+
+    /// Restrict the route added just before to callers holding `role` (or above)
+    pub fn requires(mut self, role: Role) -> Self {
+        let route = self.routes.last_mut().expect("requires() must follow a route");
+        route.role = Some(role);
+        self
+    }
+
+    pub fn authenticator(mut self, authenticator: Authenticator) -> Self {
+        self.authenticator = authenticator;
+        self
+    }
+
+    pub fn get<H: for<'a> Handler<'a, S> + 'static>(self, pattern: &'static str, handler: H) -> Self {
+        self.route("GET", pattern, handler)
+    }
+
+    pub fn post<H: for<'a> Handler<'a, S> + 'static>(self, pattern: &'static str, handler: H) -> Self {
+        self.route("POST", pattern, handler)
+    }
+
+    pub fn put<H: for<'a> Handler<'a, S> + 'static>(self, pattern: &'static str, handler: H) -> Self {
+        self.route("PUT", pattern, handler)
+    }
+
+    pub fn delete<H: for<'a> Handler<'a, S> + 'static>(self, pattern: &'static str, handler: H) -> Self {
+        self.route("DELETE", pattern, handler)
+    }
+
+    /// Run the matching handler, rendering any error (including no match) as a response
+    pub async fn handle(&self, state: &S, request: ApiGatewayProxyRequest) -> ApiGatewayProxyResponse {
+        let request = Request::new(request);
+        let path: Vec<&str> = split(request.path()).collect();
+
+        let mut path_matched = false;
+        for route in &self.routes {
+            let Some(params) = route.matches(&path) else {
+                continue;
+            };
+            if route.method != request.method() {
+                path_matched = true;
+                continue;
+            }
+            return match self.dispatch(route, state, request.clone().with_params(params)).await {
+                Ok(response) => response,
+                Err(err) => err.into_response(),
+            };
+        }
+
+        let err = if path_matched {
+            ApiError::MethodNotAllowed(request.method().to_string())
+        } else {
+            ApiError::not_found("Not found")
+        };
+        err.into_response()
+    }
+
+    /// Authenticate the caller and enforce the route's role before running it
+    async fn dispatch(&self, route: &Route<S>, state: &S, request: Request) -> ApiResult {
+        let principal = self.authenticator.authenticate(request.inner())?;
+        if let Some(role) = route.role {
+            principal.as_ref().ok_or_else(|| ApiError::unauthorized("Authentication required"))?.require(role)?;
+        }
+        route.handler.call(state, request.with_principal(principal)).await
+    }
+}
+
+fn split(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response;
+
+    struct Counter(u32);
+
+    async fn show(state: &Counter, request: Request) -> ApiResult {
+        response::ok(&serde_json::json!({ "id": request.param("id")?, "count": state.0 }))
+    }
+
+    async fn ready(_: &Counter, _: Request) -> ApiResult {
+        response::ok("ready")
+    }
+
+    fn request(method: &str, path: &str) -> ApiGatewayProxyRequest {
+        ApiGatewayProxyRequest {
+            http_method: method.parse().unwrap(),
+            path: Some(path.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn router() -> Router<Counter> {
+        Router::new().get("/items/ready", ready).get("/items/{id}", show)
+    }
+
+    fn body(response: &ApiGatewayProxyResponse) -> serde_json::Value {
+        match &response.body {
+            Some(aws_lambda_events::encodings::Body::Text(text)) => serde_json::from_str(text).unwrap(),
+            other => panic!("unexpected body {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_routes_by_method_and_path() {
+        let router = router();
+        let state = Counter(3);
+
+        let response = router.handle(&state, request("GET", "/items/abc")).await;
+        assert_eq!(response.status_code, 200);
+        assert_eq!(body(&response), serde_json::json!({ "id": "abc", "count": 3 }));
+
+        let ready = router.handle(&state, request("GET", "/items/ready/")).await;
+        assert_eq!(body(&ready), "ready");
+    }
+
+    #[tokio::test]
+    async fn test_unmatched_paths_and_methods() {
+        let router = router();
+        let state = Counter(0);
+
+        assert_eq!(router.handle(&state, request("GET", "/items/abc/extra")).await.status_code, 404);
+        assert_eq!(router.handle(&state, request("DELETE", "/items/abc")).await.status_code, 405);
+    }
+
+    #[tokio::test]
+    async fn test_route_role_is_enforced() {
+        let router =
+            Router::new().authenticator(Authenticator::default()).get("/items/{id}", show).requires(Role::Moderator);
+        let state = Counter(0);
+        let as_role = |role: &str| {
+            let mut request = request("GET", "/items/abc");
+            request.request_context.authorizer.fields.insert(
+                "claims".to_string(),
+                serde_json::json!({ "sub": uuid::Uuid::new_v4().to_string(), "roles": role }),
+            );
+            request
+        };
+
+        assert_eq!(router.handle(&state, request("GET", "/items/abc")).await.status_code, 401);
+        assert_eq!(router.handle(&state, as_role("customer")).await.status_code, 403);
+        assert_eq!(router.handle(&state, as_role("admin")).await.status_code, 200);
+    }
+}
@@ -1,47 +1,89 @@
 use aws_sdk_dynamodb::Client;
 use uuid::Uuid;
 
-use models::Bag;
-use crate::{Result, DatabaseError, get_table_name};
+use crate::item::key_string;
+use crate::page::{Page, PageRequest};
+use crate::repository::{dynamodb_store, Entity, Index, Store};
+use crate::Result;
+use models::{Bag, BagStatus};
 
+impl Entity for Bag {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+
+    /// `status-index` is keyed on `status`, which the model calls `current_status`;
+    /// `customer-index` on `customer_id`, the original owner, for bags that have one
+    fn index_keys(&self) -> Result<Vec<(&'static str, String)>> {
+        let mut keys = vec![("status", key_string(&self.current_status)?)];
+        if let Some(owner) = self.original_owner_id {
+            keys.push(("customer_id", owner.to_string()));
+        }
+        Ok(keys)
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+}
+
+#[derive(Clone)]
 pub struct BagsRepository {
-    client: Client,
-    table_name: String,
+    store: Store<Bag>,
 }
 
 impl BagsRepository {
     pub fn new(client: Client) -> Self {
-        let table_name = get_table_name("BAGS_TABLE", "seed-box-bags");
-        Self { client, table_name }
+        Self::with_store(dynamodb_store(client, "BAGS_TABLE", "seed-box-bags"))
+    }
+
+    pub fn with_store(store: Store<Bag>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
     }
 
     pub async fn create(&self, bag: &Bag) -> Result<()> {
-        // TODO: Implement DynamoDB put_item
-        // Convert Bag to DynamoDB AttributeValue map
-        // Call client.put_item()
-        Ok(())
+        self.store.create(bag).await
     }
 
     pub async fn get(&self, id: Uuid) -> Result<Bag> {
-        // TODO: Implement DynamoDB get_item
-        // Call client.get_item()
-        // Convert AttributeValue map to Bag
-        Err(DatabaseError::NotFound(id.to_string()))
+        self.store.get(&id.to_string()).await
+    }
+
+    pub async fn update(&self, bag: &Bag) -> Result<Bag> {
+        self.store.update(bag).await
+    }
+
+    pub async fn list_by_status(&self, status: &BagStatus) -> Result<Vec<Bag>> {
+        self.store.query_index(Index::STATUS, &key_string(status)?).await
     }
 
-    pub async fn update(&self, bag: &Bag) -> Result<()> {
-        // TODO: Implement DynamoDB update_item
-        Ok(())
+    pub async fn page_by_status(&self, status: &BagStatus, page: &PageRequest) -> Result<Page<Bag>> {
+        self.store.query_index_page(Index::STATUS, &key_string(status)?, page).await
     }
 
-    pub async fn list_by_status(&self, status: &str) -> Result<Vec<Bag>> {
-        // TODO: Implement DynamoDB query using status-index GSI
-        Ok(vec![])
+    /// The bags a customer sent in, whatever their status
+    pub async fn list_by_owner(&self, customer_id: Uuid) -> Result<Vec<Bag>> {
+        self.store.query_index(Index::CUSTOMER, &customer_id.to_string()).await
+    }
+
+    pub async fn list_all(&self) -> Result<Vec<Bag>> {
+        self.store.scan(None).await
+    }
+
+    pub async fn page_all(&self, page: &PageRequest) -> Result<Page<Bag>> {
+        self.store.scan_page(None, page).await
     }
 
     pub async fn delete(&self, id: Uuid) -> Result<()> {
-        // TODO: Implement DynamoDB delete_item
-        Ok(())
+        self.store.delete(&id.to_string()).await
     }
 }
-
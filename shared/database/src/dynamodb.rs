@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::operation::update_item::UpdateItemError;
+use aws_sdk_dynamodb::types::{AttributeValue, ReturnValuesOnConditionCheckFailure, Select};
+use aws_sdk_dynamodb::Client;
+use serde_json::Value;
+
+use crate::item::{from_item, to_attribute, to_item, Item};
+use crate::page::{Cursor, Page, PageRequest};
+use crate::repository::{Entity, Index, Repository, VERSION};
+use crate::{DatabaseError, Result};
+
+/// Update expression and its placeholders for replacing a stored item
+struct Overwrite {
+    expression: String,
+    names: HashMap<String, String>,
+    values: HashMap<String, AttributeValue>,
+}
+
+/// [`Repository`] backed by a single DynamoDB table keyed by `T::KEY`
+///
+/// Items are whole models plus any extra attributes needed as GSI keys
+/// (e.g. `status` for the `status-index`).
+pub struct DynamoDbRepository<T> {
+    client: Client,
+    table_name: String,
+    _entity: PhantomData<fn() -> T>,
+}
+
+impl<T: Entity> DynamoDbRepository<T> {
+    pub fn new(client: Client, table_name: String) -> Self {
+        Self { client, table_name, _entity: PhantomData }
+    }
+
+    fn item(entity: &T) -> Result<Item> {
+        let mut item = to_item(entity)?;
+        for (attribute, value) in entity.index_keys()? {
+            item.insert(attribute.to_string(), AttributeValue::S(value));
+        }
+        Ok(item)
+    }
+
+    /// `SET` every attribute of `entity` but its key, and `REMOVE` the
+    /// [`Entity::SPARSE`] attributes it leaves off
+    fn overwrite(entity: &T) -> Result<Overwrite> {
+        let mut item = Self::item(entity)?;
+        item.remove(T::KEY);
+
+        let mut names = HashMap::with_capacity(item.len() + T::SPARSE.len() + 1);
+        let mut values = HashMap::with_capacity(item.len() + 1);
+        let mut assignments = Vec::with_capacity(item.len());
+        let removals: Vec<_> = T::SPARSE.iter().filter(|attribute| !item.contains_key(**attribute)).collect();
+        for (i, (attribute, value)) in item.into_iter().enumerate() {
+            names.insert(format!("#a{}", i), attribute);
+            values.insert(format!(":v{}", i), value);
+            assignments.push(format!("#a{i} = :v{i}"));
+        }
+
+        let mut expression = format!("SET {}", assignments.join(", "));
+        if !removals.is_empty() {
+            let mut removed = Vec::with_capacity(removals.len());
+            for (i, attribute) in removals.into_iter().enumerate() {
+                names.insert(format!("#r{}", i), attribute.to_string());
+                removed.push(format!("#r{i}"));
+            }
+            expression.push_str(&format!(" REMOVE {}", removed.join(", ")));
+        }
+
+        Ok(Overwrite { expression, names, values })
+    }
+
+    fn key_condition(exists: bool) -> String {
+        if exists {
+            format!("attribute_exists({})", T::KEY)
+        } else {
+            format!("attribute_not_exists({})", T::KEY)
+        }
+    }
+
+    /// A single `Query` request, returning its items and `LastEvaluatedKey`
+    async fn query_once(
+        &self,
+        index: Index,
+        value: &str,
+        limit: Option<u32>,
+        start_key: Option<Item>,
+    ) -> Result<(Vec<T>, Option<Item>)> {
+        let output = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(index.name)
+            .key_condition_expression("#k = :v")
+            .expression_attribute_names("#k", index.attribute)
+            .expression_attribute_values(":v", AttributeValue::S(value.to_string()))
+            .set_limit(limit.map(|limit| limit as i32))
+            .set_exclusive_start_key(start_key)
+            .send()
+            .await
+            .map_err(aws_sdk_dynamodb::Error::from)?;
+
+        let items = output.items.unwrap_or_default().into_iter().map(from_item).collect::<Result<_>>()?;
+        Ok((items, output.last_evaluated_key.filter(|key| !key.is_empty())))
+    }
+
+    /// A single `Scan` request, returning its items and `LastEvaluatedKey`
+    async fn scan_once(
+        &self,
+        filter: Option<&(&str, AttributeValue)>,
+        limit: Option<u32>,
+        start_key: Option<Item>,
+    ) -> Result<(Vec<T>, Option<Item>)> {
+        let mut request = self
+            .client
+            .scan()
+            .table_name(&self.table_name)
+            .set_limit(limit.map(|limit| limit as i32))
+            .set_exclusive_start_key(start_key);
+
+        if let Some((attribute, value)) = filter {
+            request = request
+                .filter_expression("#f = :f")
+                .expression_attribute_names("#f", *attribute)
+                .expression_attribute_values(":f", value.clone());
+        }
+
+        let output = request.send().await.map_err(aws_sdk_dynamodb::Error::from)?;
+
+        let items = output.items.unwrap_or_default().into_iter().map(from_item).collect::<Result<_>>()?;
+        Ok((items, output.last_evaluated_key.filter(|key| !key.is_empty())))
+    }
+
+    fn page(items: Vec<T>, last_key: Option<Item>) -> Result<Page<T>> {
+        let next_cursor = last_key.map(Cursor::from_item).transpose()?;
+        Ok(Page { items, next_cursor })
+    }
+}
+
+#[async_trait]
+impl<T: Entity> Repository<T> for DynamoDbRepository<T> {
+    async fn create(&self, entity: &T) -> Result<()> {
+        let result = self
+            .client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(Self::item(entity)?))
+            .condition_expression(Self::key_condition(false))
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) if err.as_service_error().is_some_and(|e| e.is_conditional_check_failed_exception()) => {
+                Err(DatabaseError::AlreadyExists(entity.key()))
+            }
+            Err(err) => Err(aws_sdk_dynamodb::Error::from(err).into()),
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<T> {
+        let output = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key(T::KEY, AttributeValue::S(key.to_string()))
+            .send()
+            .await
+            .map_err(aws_sdk_dynamodb::Error::from)?;
+
+        match output.item {
+            Some(item) => from_item(item),
+            None => Err(DatabaseError::NotFound(key.to_string())),
+        }
+    }
+
+    /// Overwrites every attribute of the existing item using `update_item`,
+    /// removing unset sparse ones, conditional on the stored version for
+    /// versioned entities
+    async fn update(&self, entity: &T) -> Result<T> {
+        let key = entity.key();
+        let expected = entity.version();
+        let mut stored = entity.clone();
+        if let Some(expected) = expected {
+            stored.set_version(expected + 1);
+        }
+
+        let Overwrite { expression, mut names, mut values } = Self::overwrite(&stored)?;
+
+        let mut condition = Self::key_condition(true);
+        if let Some(expected) = expected {
+            names.insert("#version".to_string(), VERSION.to_string());
+            values.insert(":expected".to_string(), AttributeValue::N(expected.to_string()));
+            // Items written before versioning have no attribute and count as version 0
+            if expected == 0 {
+                condition.push_str(" AND (attribute_not_exists(#version) OR #version = :expected)");
+            } else {
+                condition.push_str(" AND #version = :expected");
+            }
+        }
+
+        let result = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .key(T::KEY, AttributeValue::S(key.clone()))
+            .update_expression(expression)
+            .set_expression_attribute_names(Some(names))
+            .set_expression_attribute_values(Some(values))
+            .condition_expression(condition)
+            .return_values_on_condition_check_failure(ReturnValuesOnConditionCheckFailure::AllOld)
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(stored),
+            Err(err) => match err.into_service_error() {
+                UpdateItemError::ConditionalCheckFailedException(failed) => match failed.item {
+                    Some(current) if expected.is_some() => {
+                        let current: T = from_item(current)?;
+                        Err(DatabaseError::Conflict { key, current: serde_json::to_value(current)? })
+                    }
+                    _ => Err(DatabaseError::NotFound(key)),
+                },
+                err => Err(aws_sdk_dynamodb::Error::from(err).into()),
+            },
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.client
+            .delete_item()
+            .table_name(&self.table_name)
+            .key(T::KEY, AttributeValue::S(key.to_string()))
+            .send()
+            .await
+            .map_err(aws_sdk_dynamodb::Error::from)?;
+        Ok(())
+    }
+
+    async fn query_index(&self, index: Index, value: &str) -> Result<Vec<T>> {
+        let mut results = Vec::new();
+        let mut start_key = None;
+
+        loop {
+            let (items, last_key) = self.query_once(index, value, None, start_key).await?;
+            results.extend(items);
+
+            match last_key {
+                Some(key) => start_key = Some(key),
+                None => return Ok(results),
+            }
+        }
+    }
+
+    async fn query_index_page(&self, index: Index, value: &str, page: &PageRequest) -> Result<Page<T>> {
+        let start_key = page.cursor.as_ref().map(Cursor::to_item).transpose()?;
+        let (items, last_key) = self.query_once(index, value, Some(page.limit), start_key).await?;
+        Self::page(items, last_key)
+    }
+
+    /// Counts with `Select::COUNT` rather than fetching every item
+    async fn count_index(&self, index: Index, value: &str) -> Result<usize> {
+        let mut count = 0;
+        let mut start_key: Option<Item> = None;
+
+        loop {
+            let output = self
+                .client
+                .query()
+                .table_name(&self.table_name)
+                .index_name(index.name)
+                .key_condition_expression("#k = :v")
+                .expression_attribute_names("#k", index.attribute)
+                .expression_attribute_values(":v", AttributeValue::S(value.to_string()))
+                .select(Select::Count)
+                .set_exclusive_start_key(start_key.take())
+                .send()
+                .await
+                .map_err(aws_sdk_dynamodb::Error::from)?;
+
+            count += output.count as usize;
+
+            match output.last_evaluated_key {
+                Some(key) if !key.is_empty() => start_key = Some(key),
+                _ => return Ok(count),
+            }
+        }
+    }
+
+    async fn scan(&self, filter: Option<(&str, Value)>) -> Result<Vec<T>> {
+        let filter = filter.map(|(attribute, value)| (attribute, to_attribute(value)));
+        let mut results = Vec::new();
+        let mut start_key = None;
+
+        loop {
+            let (items, last_key) = self.scan_once(filter.as_ref(), None, start_key).await?;
+            results.extend(items);
+
+            match last_key {
+                Some(key) => start_key = Some(key),
+                None => return Ok(results),
+            }
+        }
+    }
+
+    async fn scan_page(&self, filter: Option<(&str, Value)>, page: &PageRequest) -> Result<Page<T>> {
+        let filter = filter.map(|(attribute, value)| (attribute, to_attribute(value)));
+        let start_key = page.cursor.as_ref().map(Cursor::to_item).transpose()?;
+        let (items, last_key) = self.scan_once(filter.as_ref(), Some(page.limit), start_key).await?;
+        Self::page(items, last_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use models::{ManufacturingQueue, Priority, QueueStatus, QueueType};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_overwrite_removes_a_released_assignee() {
+        let mut item = ManufacturingQueue {
+            id: Uuid::new_v4(),
+            queue_type: QueueType::BagCleaning { bag_ids: Vec::new() },
+            priority: Priority::Normal,
+            status: QueueStatus::InProgress,
+            created_at: Utc::now(),
+            scheduled_start: None,
+            actual_start: Some(Utc::now()),
+            completed_at: None,
+            assigned_to: Some("alice".to_string()),
+            notes: None,
+            estimated_completion: None,
+            version: 3,
+            history: Vec::new(),
+            previous_step: None,
+            depends_on: Vec::new(),
+            batch_id: None,
+            required_skills: Vec::new(),
+            lease_expires_at: None,
+            leased: false,
+        };
+
+        let held = DynamoDbRepository::overwrite(&item).unwrap();
+        assert!(held.names.values().any(|name| name == "assigned_to"));
+        assert!(held.expression.ends_with("REMOVE #r0"));
+        assert_eq!(held.names["#r0"], "batch_id");
+
+        assert!(item.transition(QueueStatus::Pending, Utc::now(), None, None));
+        let released = DynamoDbRepository::overwrite(&item).unwrap();
+        let removed = released.expression.split(" REMOVE ").nth(1).unwrap();
+        let mut removed: Vec<_> = removed.split(", ").map(|name| released.names[name].as_str()).collect();
+        removed.sort_unstable();
+        assert_eq!(removed, ["assigned_to", "batch_id"]);
+        assert!(!released.expression.split(" REMOVE ").next().unwrap().contains("#r"));
+    }
+}
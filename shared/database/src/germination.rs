@@ -0,0 +1,324 @@
+use aws_sdk_dynamodb::Client;
+use uuid::Uuid;
+
+use crate::item::key_string;
+use crate::page::{Page, PageRequest};
+use crate::repository::{dynamodb_store, Entity, Index, Store};
+use crate::{DatabaseError, Result};
+use models::{
+    CustomerSproutPreferences, GerminationGuide, GerminationObservation, GerminationPhase, GerminationRecord,
+    GerminationTray, PackageStatus, SproutShipmentPackage,
+};
+
+impl Entity for GerminationRecord {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+
+    /// `phase-index` is keyed on `phase`, which the model calls `germination_phase`
+    fn index_keys(&self) -> Result<Vec<(&'static str, String)>> {
+        Ok(vec![("phase", key_string(&self.germination_phase)?)])
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+}
+
+impl Entity for GerminationObservation {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+impl Entity for GerminationTray {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+impl Entity for SproutShipmentPackage {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+}
+
+/// A customer has one set of preferences, keyed by the customer
+impl Entity for CustomerSproutPreferences {
+    const KEY: &'static str = "customer_id";
+
+    fn key(&self) -> String {
+        self.customer_id.to_string()
+    }
+}
+
+/// Guides are keyed by species rather than by a generated id
+impl Entity for GerminationGuide {
+    const KEY: &'static str = "species";
+
+    fn key(&self) -> String {
+        self.species.clone()
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+}
+
+#[derive(Clone)]
+pub struct GerminationRecordsRepository {
+    store: Store<GerminationRecord>,
+}
+
+impl GerminationRecordsRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "GERMINATION_RECORDS_TABLE", "seed-box-germination-records"))
+    }
+
+    pub fn with_store(store: Store<GerminationRecord>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, record: &GerminationRecord) -> Result<()> {
+        self.store.create(record).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<GerminationRecord> {
+        self.store.get(&id.to_string()).await
+    }
+
+    pub async fn update(&self, record: &GerminationRecord) -> Result<GerminationRecord> {
+        self.store.update(record).await
+    }
+
+    pub async fn list_by_phase(&self, phase: &GerminationPhase) -> Result<Vec<GerminationRecord>> {
+        self.store.query_index(Index::PHASE, &key_string(phase)?).await
+    }
+
+    pub async fn page_by_phase(&self, phase: &GerminationPhase, page: &PageRequest) -> Result<Page<GerminationRecord>> {
+        self.store.query_index_page(Index::PHASE, &key_string(phase)?, page).await
+    }
+
+    pub async fn list_by_customer(&self, customer_id: Uuid) -> Result<Vec<GerminationRecord>> {
+        self.store.query_index(Index::CUSTOMER, &customer_id.to_string()).await
+    }
+
+    pub async fn list_by_species(&self, species: &str) -> Result<Vec<GerminationRecord>> {
+        self.store.query_index(Index::SPECIES, species).await
+    }
+
+    pub async fn list_all(&self) -> Result<Vec<GerminationRecord>> {
+        self.store.scan(None).await
+    }
+}
+
+#[derive(Clone)]
+pub struct GerminationObservationsRepository {
+    store: Store<GerminationObservation>,
+}
+
+impl GerminationObservationsRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "GERMINATION_OBSERVATIONS_TABLE", "seed-box-germination-observations"))
+    }
+
+    pub fn with_store(store: Store<GerminationObservation>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, observation: &GerminationObservation) -> Result<()> {
+        self.store.create(observation).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<GerminationObservation> {
+        self.store.get(&id.to_string()).await
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        self.store.delete(&id.to_string()).await
+    }
+
+    /// Every observation of a record, oldest first
+    pub async fn list_by_record(&self, germination_record_id: Uuid) -> Result<Vec<GerminationObservation>> {
+        let mut observations = self.store.query_index(Index::RECORD, &germination_record_id.to_string()).await?;
+        observations.sort_by_key(|o| o.observed_at);
+        Ok(observations)
+    }
+}
+
+#[derive(Clone)]
+pub struct GerminationGuidesRepository {
+    store: Store<GerminationGuide>,
+}
+
+impl GerminationGuidesRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "GERMINATION_GUIDES_TABLE", "seed-box-germination-guides"))
+    }
+
+    pub fn with_store(store: Store<GerminationGuide>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, guide: &GerminationGuide) -> Result<()> {
+        self.store.create(guide).await
+    }
+
+    pub async fn get(&self, species: &str) -> Result<GerminationGuide> {
+        self.store.get(species).await
+    }
+
+    pub async fn update(&self, guide: &GerminationGuide) -> Result<GerminationGuide> {
+        self.store.update(guide).await
+    }
+
+    pub async fn delete(&self, species: &str) -> Result<()> {
+        self.store.delete(species).await
+    }
+
+    pub async fn list_all(&self) -> Result<Vec<GerminationGuide>> {
+        self.store.scan(None).await
+    }
+}
+
+#[derive(Clone)]
+pub struct GerminationTraysRepository {
+    store: Store<GerminationTray>,
+}
+
+impl GerminationTraysRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "GERMINATION_TRAYS_TABLE", "seed-box-germination-trays"))
+    }
+
+    pub fn with_store(store: Store<GerminationTray>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, tray: &GerminationTray) -> Result<()> {
+        self.store.create(tray).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<GerminationTray> {
+        self.store.get(&id.to_string()).await
+    }
+
+    pub async fn list_all(&self) -> Result<Vec<GerminationTray>> {
+        self.store.scan(None).await
+    }
+}
+
+#[derive(Clone)]
+pub struct SproutPackagesRepository {
+    store: Store<SproutShipmentPackage>,
+}
+
+impl SproutPackagesRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "SPROUT_PACKAGES_TABLE", "seed-box-sprout-packages"))
+    }
+
+    pub fn with_store(store: Store<SproutShipmentPackage>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, package: &SproutShipmentPackage) -> Result<()> {
+        self.store.create(package).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<SproutShipmentPackage> {
+        self.store.get(&id.to_string()).await
+    }
+
+    pub async fn update(&self, package: &SproutShipmentPackage) -> Result<SproutShipmentPackage> {
+        self.store.update(package).await
+    }
+
+    pub async fn list_by_status(&self, status: PackageStatus) -> Result<Vec<SproutShipmentPackage>> {
+        self.store.query_index(Index::STATUS, &key_string(&status)?).await
+    }
+
+    pub async fn list_by_customer(&self, customer_id: Uuid) -> Result<Vec<SproutShipmentPackage>> {
+        self.store.query_index(Index::CUSTOMER, &customer_id.to_string()).await
+    }
+}
+
+#[derive(Clone)]
+pub struct SproutPreferencesRepository {
+    store: Store<CustomerSproutPreferences>,
+}
+
+impl SproutPreferencesRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "SPROUT_PREFERENCES_TABLE", "seed-box-sprout-preferences"))
+    }
+
+    pub fn with_store(store: Store<CustomerSproutPreferences>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn get(&self, customer_id: Uuid) -> Result<CustomerSproutPreferences> {
+        self.store.get(&customer_id.to_string()).await
+    }
+
+    /// Replace the customer's preferences, creating them the first time
+    pub async fn put(&self, preferences: &CustomerSproutPreferences) -> Result<CustomerSproutPreferences> {
+        match self.store.update(preferences).await {
+            Err(DatabaseError::NotFound(_)) => {
+                self.store.create(preferences).await?;
+                Ok(preferences.clone())
+            }
+            result => result,
+        }
+    }
+
+    pub async fn list_all(&self) -> Result<Vec<CustomerSproutPreferences>> {
+        self.store.scan(None).await
+    }
+}
@@ -1,75 +1,103 @@
 use aws_sdk_dynamodb::Client;
 use uuid::Uuid;
 
-use models::{GreenhouseZone, Plant};
-use crate::{Result, DatabaseError, get_table_name};
+use crate::repository::{dynamodb_store, Entity, Index, Store};
+use crate::Result;
+use models::{GreenhouseZone, Plant, PlantHealthStatus};
 
+impl Entity for GreenhouseZone {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+impl Entity for Plant {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+
+    /// `zone-index` is keyed on `zone_id`, which the model calls `current_zone_id`
+    fn index_keys(&self) -> Result<Vec<(&'static str, String)>> {
+        Ok(vec![("zone_id", self.current_zone_id.to_string())])
+    }
+}
+
+#[derive(Clone)]
 pub struct GreenhouseZonesRepository {
-    client: Client,
-    table_name: String,
+    store: Store<GreenhouseZone>,
 }
 
 impl GreenhouseZonesRepository {
     pub fn new(client: Client) -> Self {
-        let table_name = get_table_name("GREENHOUSE_ZONES_TABLE", "seed-box-greenhouse-zones");
-        Self { client, table_name }
+        Self::with_store(dynamodb_store(client, "GREENHOUSE_ZONES_TABLE", "seed-box-greenhouse-zones"))
+    }
+
+    pub fn with_store(store: Store<GreenhouseZone>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
     }
 
     pub async fn create(&self, zone: &GreenhouseZone) -> Result<()> {
-        // TODO: Implement DynamoDB put_item
-        Ok(())
+        self.store.create(zone).await
     }
 
     pub async fn get(&self, id: Uuid) -> Result<GreenhouseZone> {
-        // TODO: Implement DynamoDB get_item
-        Err(DatabaseError::NotFound(id.to_string()))
+        self.store.get(&id.to_string()).await
     }
 
-    pub async fn update(&self, zone: &GreenhouseZone) -> Result<()> {
-        // TODO: Implement DynamoDB update_item
-        Ok(())
+    pub async fn update(&self, zone: &GreenhouseZone) -> Result<GreenhouseZone> {
+        self.store.update(zone).await
     }
 
     pub async fn list_all(&self) -> Result<Vec<GreenhouseZone>> {
-        // TODO: Implement DynamoDB scan
-        Ok(vec![])
+        self.store.scan(None).await
     }
 }
 
+#[derive(Clone)]
 pub struct PlantsRepository {
-    client: Client,
-    table_name: String,
+    store: Store<Plant>,
 }
 
 impl PlantsRepository {
     pub fn new(client: Client) -> Self {
-        let table_name = get_table_name("PLANTS_TABLE", "seed-box-plants");
-        Self { client, table_name }
+        Self::with_store(dynamodb_store(client, "PLANTS_TABLE", "seed-box-plants"))
+    }
+
+    pub fn with_store(store: Store<Plant>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
     }
 
     pub async fn create(&self, plant: &Plant) -> Result<()> {
-        // TODO: Implement DynamoDB put_item
-        Ok(())
+        self.store.create(plant).await
     }
 
     pub async fn get(&self, id: Uuid) -> Result<Plant> {
-        // TODO: Implement DynamoDB get_item
-        Err(DatabaseError::NotFound(id.to_string()))
+        self.store.get(&id.to_string()).await
     }
 
-    pub async fn update(&self, plant: &Plant) -> Result<()> {
-        // TODO: Implement DynamoDB update_item
-        Ok(())
+    pub async fn update(&self, plant: &Plant) -> Result<Plant> {
+        self.store.update(plant).await
     }
 
     pub async fn list_by_zone(&self, zone_id: Uuid) -> Result<Vec<Plant>> {
-        // TODO: Implement DynamoDB query using zone-index GSI
-        Ok(vec![])
+        self.store.query_index(Index::ZONE, &zone_id.to_string()).await
     }
 
-    pub async fn list_by_health_status(&self, status: &str) -> Result<Vec<Plant>> {
-        // TODO: Implement DynamoDB scan with filter
-        Ok(vec![])
+    pub async fn list_by_health_status(&self, status: &PlantHealthStatus) -> Result<Vec<Plant>> {
+        self.store.scan(Some(("health_status", serde_json::to_value(status)?))).await
     }
-}
 
+    pub async fn list_all(&self) -> Result<Vec<Plant>> {
+        self.store.scan(None).await
+    }
+}
@@ -0,0 +1,117 @@
+use aws_sdk_dynamodb::Client;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::item::key_string;
+use crate::page::{Page, PageRequest};
+use crate::repository::{dynamodb_store, Entity, Index, Store};
+use crate::Result;
+use models::{ImageAuditLog, ModerationStatus, PlantImage};
+
+impl Entity for PlantImage {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+
+    /// `status-index` is keyed on `status`, which the model calls `moderation_status`
+    fn index_keys(&self) -> Result<Vec<(&'static str, String)>> {
+        Ok(vec![("status", key_string(&self.moderation_status)?)])
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+}
+
+impl Entity for ImageAuditLog {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+#[derive(Clone)]
+pub struct PlantImagesRepository {
+    store: Store<PlantImage>,
+}
+
+impl PlantImagesRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "PLANT_IMAGES_TABLE", "seed-box-plant-images"))
+    }
+
+    pub fn with_store(store: Store<PlantImage>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, image: &PlantImage) -> Result<()> {
+        self.store.create(image).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<PlantImage> {
+        self.store.get(&id.to_string()).await
+    }
+
+    pub async fn update(&self, image: &PlantImage) -> Result<PlantImage> {
+        self.store.update(image).await
+    }
+
+    pub async fn list_by_status(&self, status: &ModerationStatus) -> Result<Vec<PlantImage>> {
+        self.store.query_index(Index::STATUS, &key_string(status)?).await
+    }
+
+    pub async fn page_by_status(&self, status: &ModerationStatus, page: &PageRequest) -> Result<Page<PlantImage>> {
+        self.store.query_index_page(Index::STATUS, &key_string(status)?, page).await
+    }
+
+    pub async fn list_by_uploader(&self, user_id: Uuid) -> Result<Vec<PlantImage>> {
+        self.store.scan(Some(("uploaded_by", Value::from(user_id.to_string())))).await
+    }
+
+    pub async fn list_all(&self) -> Result<Vec<PlantImage>> {
+        self.store.scan(None).await
+    }
+}
+
+#[derive(Clone)]
+pub struct ImageAuditLogsRepository {
+    store: Store<ImageAuditLog>,
+}
+
+impl ImageAuditLogsRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "IMAGE_AUDIT_LOGS_TABLE", "seed-box-image-audit-logs"))
+    }
+
+    pub fn with_store(store: Store<ImageAuditLog>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, entry: &ImageAuditLog) -> Result<()> {
+        self.store.create(entry).await
+    }
+
+    /// Audit trail of one image, oldest first
+    pub async fn list_by_image(&self, image_id: Uuid) -> Result<Vec<ImageAuditLog>> {
+        let mut entries = self.store.query_index(Index::IMAGE, &image_id.to_string()).await?;
+        entries.sort_by_key(|e| e.performed_at);
+        Ok(entries)
+    }
+
+    pub async fn list_all(&self) -> Result<Vec<ImageAuditLog>> {
+        self.store.scan(None).await
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::{DatabaseError, Result};
+
+/// A DynamoDB item as returned by the SDK
+pub type Item = HashMap<String, AttributeValue>;
+
+/// Convert any serializable model into a DynamoDB item
+pub fn to_item<T: Serialize>(value: &T) -> Result<Item> {
+    match serde_json::to_value(value)? {
+        Value::Object(map) => Ok(map.into_iter().map(|(key, value)| (key, to_attribute(value))).collect()),
+        other => Err(DatabaseError::InvalidData(format!("expected an object at the top level, got {}", other))),
+    }
+}
+
+/// Convert a DynamoDB item back into a model
+pub fn from_item<T: DeserializeOwned>(item: Item) -> Result<T> {
+    let mut map = Map::with_capacity(item.len());
+    for (key, value) in item {
+        map.insert(key, from_attribute(value)?);
+    }
+    Ok(serde_json::from_value(Value::Object(map))?)
+}
+
+/// Convert a JSON value into the equivalent DynamoDB attribute
+pub fn to_attribute(value: Value) -> AttributeValue {
+    match value {
+        Value::Null => AttributeValue::Null(true),
+        Value::Bool(b) => AttributeValue::Bool(b),
+        Value::Number(n) => AttributeValue::N(n.to_string()),
+        Value::String(s) => AttributeValue::S(s),
+        Value::Array(values) => AttributeValue::L(values.into_iter().map(to_attribute).collect()),
+        Value::Object(map) => {
+            AttributeValue::M(map.into_iter().map(|(key, value)| (key, to_attribute(value))).collect())
+        }
+    }
+}
+
+/// Convert a DynamoDB attribute into the equivalent JSON value
+pub fn from_attribute(value: AttributeValue) -> Result<Value> {
+    Ok(match value {
+        AttributeValue::Null(_) => Value::Null,
+        AttributeValue::Bool(b) => Value::Bool(b),
+        AttributeValue::N(n) => Value::Number(parse_number(&n)?),
+        AttributeValue::S(s) => Value::String(s),
+        AttributeValue::L(values) => Value::Array(values.into_iter().map(from_attribute).collect::<Result<Vec<_>>>()?),
+        AttributeValue::M(map) => {
+            let mut object = Map::with_capacity(map.len());
+            for (key, value) in map {
+                object.insert(key, from_attribute(value)?);
+            }
+            Value::Object(object)
+        }
+        AttributeValue::Ss(values) => Value::Array(values.into_iter().map(Value::String).collect()),
+        AttributeValue::Ns(values) => {
+            Value::Array(values.iter().map(|n| parse_number(n).map(Value::Number)).collect::<Result<Vec<_>>>()?)
+        }
+        other => return Err(DatabaseError::InvalidData(format!("unsupported attribute type: {:?}", other))),
+    })
+}
+
+/// Render a unit enum (or any value serializing to a string) as the string
+/// stored in index key attributes, e.g. `BagStatus::Received` -> `"received"`
+pub fn key_string<T: Serialize>(value: &T) -> Result<String> {
+    match serde_json::to_value(value)? {
+        Value::String(s) => Ok(s),
+        other => Err(DatabaseError::InvalidData(format!("index key must serialize to a string, got {}", other))),
+    }
+}
+
+fn parse_number(n: &str) -> Result<Number> {
+    if let Ok(i) = n.parse::<i64>() {
+        return Ok(i.into());
+    }
+    if let Ok(u) = n.parse::<u64>() {
+        return Ok(u.into());
+    }
+    n.parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .ok_or_else(|| DatabaseError::InvalidData(format!("invalid number attribute: {}", n)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use models::{Bag, BagCondition, BagStatus, BagType};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_bag_round_trip() {
+        let bag = Bag {
+            id: Uuid::new_v4(),
+            original_owner_id: None,
+            current_status: BagStatus::ReadyForShipment,
+            bag_type: BagType::Other("burlap".to_string()),
+            condition: BagCondition::Good,
+            received_at: Utc::now(),
+            cleaned_at: Some(Utc::now()),
+            last_updated: Utc::now(),
+            contains_seeds: true,
+            seed_ids: vec![Uuid::new_v4(), Uuid::new_v4()],
+            version: 0,
+        };
+
+        let item = to_item(&bag).unwrap();
+        assert_eq!(item.get("id"), Some(&AttributeValue::S(bag.id.to_string())));
+        assert_eq!(item.get("original_owner_id"), Some(&AttributeValue::Null(true)));
+
+        let restored: Bag = from_item(item).unwrap();
+        assert_eq!(restored.id, bag.id);
+        assert_eq!(restored.current_status, BagStatus::ReadyForShipment);
+        assert_eq!(restored.seed_ids, bag.seed_ids);
+        assert_eq!(restored.cleaned_at, bag.cleaned_at);
+    }
+
+    #[test]
+    fn test_numbers_keep_their_kind() {
+        assert_eq!(from_attribute(AttributeValue::N("42".into())).unwrap(), Value::from(42));
+        assert_eq!(from_attribute(AttributeValue::N("-3".into())).unwrap(), Value::from(-3));
+        assert_eq!(from_attribute(AttributeValue::N("2.5".into())).unwrap(), Value::from(2.5));
+        assert!(from_attribute(AttributeValue::N("not-a-number".into())).is_err());
+    }
+
+    #[test]
+    fn test_key_string() {
+        assert_eq!(key_string(&BagStatus::ReadyForShipment).unwrap(), "readyforshipment");
+        assert!(key_string(&BagType::Other("x".into())).is_err());
+    }
+}
@@ -1,32 +1,86 @@
+use aws_config::BehaviorVersion;
 use aws_sdk_dynamodb::Client;
 use thiserror::Error;
 
+pub mod dynamodb;
+pub mod item;
+#[cfg(feature = "in-memory")]
+pub mod memory;
+pub mod page;
+pub mod repository;
+
 pub mod bags;
+pub mod germination;
+pub mod greenhouse;
+pub mod images;
+pub mod manufacturing;
+pub mod recalls;
 pub mod seeds;
-pub mod subscriptions;
 pub mod shipments;
-pub mod greenhouse;
+pub mod subscriptions;
+
+pub use bags::BagsRepository;
+pub use dynamodb::DynamoDbRepository;
+pub use germination::{
+    GerminationGuidesRepository, GerminationObservationsRepository, GerminationRecordsRepository,
+    GerminationTraysRepository, SproutPackagesRepository, SproutPreferencesRepository,
+};
+pub use greenhouse::{GreenhouseZonesRepository, PlantsRepository};
+pub use images::{ImageAuditLogsRepository, PlantImagesRepository};
+pub use manufacturing::{
+    ManufacturingQueueRepository, QueueDurationsRepository, QueueWorkersRepository, SeedStorageRepository,
+};
+#[cfg(feature = "in-memory")]
+pub use memory::InMemoryRepository;
+pub use page::{Cursor, Page, PageRequest};
+pub use recalls::{RecallNotificationsRepository, RecallReviewsRepository, RecallsRepository};
+pub use repository::{Entity, Index, Repository, Store};
+pub use seeds::SeedsRepository;
+pub use shipments::ShipmentsRepository;
+pub use subscriptions::SubscriptionsRepository;
 
 #[derive(Debug, Error)]
 pub enum DatabaseError {
     #[error("Item not found: {0}")]
     NotFound(String),
-    
+
+    #[error("Item already exists: {0}")]
+    AlreadyExists(String),
+
+    /// A versioned item was updated by someone else since it was read
+    #[error("Item was modified concurrently: {key}")]
+    Conflict {
+        key: String,
+        /// The item as currently stored
+        current: serde_json::Value,
+    },
+
     #[error("DynamoDB error: {0}")]
-    DynamoDb(#[from] aws_sdk_dynamodb::Error),
-    
+    DynamoDb(Box<aws_sdk_dynamodb::Error>),
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
-    
+
+    /// Stored data that couldn't be read back
     #[error("Invalid data: {0}")]
     InvalidData(String),
+
+    /// A page cursor or limit the caller sent that can't be used
+    #[error("Invalid page: {0}")]
+    InvalidPage(String),
+}
+
+impl From<aws_sdk_dynamodb::Error> for DatabaseError {
+    fn from(err: aws_sdk_dynamodb::Error) -> Self {
+        Self::DynamoDb(Box::new(err))
+    }
 }
 
 pub type Result<T> = std::result::Result<T, DatabaseError>;
 
 /// Get DynamoDB client configured for the current AWS environment
 pub async fn get_client() -> Client {
-    let config = aws_config::load_from_env().await;
+    let config = aws_config::load_defaults(BehaviorVersion::latest()).await;
     Client::new(&config)
 }
 
@@ -34,4 +88,3 @@ pub async fn get_client() -> Client {
 pub fn get_table_name(env_var: &str, default: &str) -> String {
     std::env::var(env_var).unwrap_or_else(|_| default.to_string())
 }
-
@@ -0,0 +1,223 @@
+use aws_sdk_dynamodb::Client;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::item::key_string;
+use crate::page::{Page, PageRequest};
+use crate::repository::{dynamodb_store, Entity, Index, Store};
+use crate::{DatabaseError, Result};
+use models::{ManufacturingQueue, QueueStatus, QueueTypeDurations, QueueWorker, SeedStorage};
+
+impl Entity for ManufacturingQueue {
+    const SPARSE: &'static [&'static str] = &["assigned_to", "batch_id"];
+
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+}
+
+impl Entity for QueueWorker {
+    fn key(&self) -> String {
+        self.id.clone()
+    }
+}
+
+impl Entity for QueueTypeDurations {
+    const KEY: &'static str = "queue_type";
+
+    fn key(&self) -> String {
+        self.queue_type.clone()
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+}
+
+impl Entity for SeedStorage {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+#[derive(Clone)]
+pub struct ManufacturingQueueRepository {
+    store: Store<ManufacturingQueue>,
+}
+
+impl ManufacturingQueueRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "MANUFACTURING_QUEUE_TABLE", "seed-box-manufacturing-queue"))
+    }
+
+    pub fn with_store(store: Store<ManufacturingQueue>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, item: &ManufacturingQueue) -> Result<()> {
+        self.store.create(item).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<ManufacturingQueue> {
+        self.store.get(&id.to_string()).await
+    }
+
+    pub async fn update(&self, item: &ManufacturingQueue) -> Result<ManufacturingQueue> {
+        self.store.update(item).await
+    }
+
+    pub async fn list_by_status(&self, status: &QueueStatus) -> Result<Vec<ManufacturingQueue>> {
+        self.store.query_index(Index::STATUS, &key_string(status)?).await
+    }
+
+    pub async fn count_by_status(&self, status: &QueueStatus) -> Result<usize> {
+        self.store.count_index(Index::STATUS, &key_string(status)?).await
+    }
+
+    pub async fn list_by_batch(&self, batch_id: Uuid) -> Result<Vec<ManufacturingQueue>> {
+        self.store.query_index(Index::BATCH, &batch_id.to_string()).await
+    }
+
+    /// Everything the worker claimed or started, in any status
+    pub async fn list_by_assignee(&self, worker_id: &str) -> Result<Vec<ManufacturingQueue>> {
+        self.store.query_index(Index::ASSIGNEE, worker_id).await
+    }
+
+    pub async fn list_all(&self) -> Result<Vec<ManufacturingQueue>> {
+        self.store.scan(None).await
+    }
+
+    pub async fn page_all(&self, page: &PageRequest) -> Result<Page<ManufacturingQueue>> {
+        self.store.scan_page(None, page).await
+    }
+}
+
+#[derive(Clone)]
+pub struct QueueWorkersRepository {
+    store: Store<QueueWorker>,
+}
+
+impl QueueWorkersRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "QUEUE_WORKERS_TABLE", "seed-box-queue-workers"))
+    }
+
+    pub fn with_store(store: Store<QueueWorker>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<QueueWorker> {
+        self.store.get(id).await
+    }
+
+    /// Replace the worker's details, registering them the first time
+    pub async fn put(&self, worker: &QueueWorker) -> Result<QueueWorker> {
+        match self.store.update(worker).await {
+            Err(DatabaseError::NotFound(_)) => {
+                self.store.create(worker).await?;
+                Ok(worker.clone())
+            }
+            result => result,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct QueueDurationsRepository {
+    store: Store<QueueTypeDurations>,
+}
+
+impl QueueDurationsRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "QUEUE_DURATIONS_TABLE", "seed-box-queue-durations"))
+    }
+
+    pub fn with_store(store: Store<QueueTypeDurations>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn get(&self, queue_type: &str) -> Result<QueueTypeDurations> {
+        self.store.get(queue_type).await
+    }
+
+    /// Store the durations, creating the type's item the first time; fails
+    /// with `Conflict` or `AlreadyExists` if someone else stored it since it
+    /// was read
+    pub async fn put(&self, durations: &QueueTypeDurations) -> Result<QueueTypeDurations> {
+        if durations.version == 0 {
+            // Stored at 1 so the next reader updates rather than creates
+            let mut created = durations.clone();
+            created.version = 1;
+            self.store.create(&created).await?;
+            return Ok(created);
+        }
+        self.store.update(durations).await
+    }
+}
+
+#[derive(Clone)]
+pub struct SeedStorageRepository {
+    store: Store<SeedStorage>,
+}
+
+impl SeedStorageRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "SEED_STORAGE_TABLE", "seed-box-seed-storage"))
+    }
+
+    pub fn with_store(store: Store<SeedStorage>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, storage: &SeedStorage) -> Result<()> {
+        self.store.create(storage).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<SeedStorage> {
+        self.store.get(&id.to_string()).await
+    }
+
+    pub async fn update(&self, storage: &SeedStorage) -> Result<SeedStorage> {
+        self.store.update(storage).await
+    }
+
+    pub async fn list_by_seed(&self, seed_id: Uuid) -> Result<Vec<SeedStorage>> {
+        self.store.scan(Some(("seed_id", Value::from(seed_id.to_string())))).await
+    }
+
+    pub async fn list_all(&self) -> Result<Vec<SeedStorage>> {
+        self.store.scan(None).await
+    }
+}
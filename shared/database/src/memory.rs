@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde_json::Value;
+
+use crate::page::{Cursor, Page, PageRequest};
+use crate::repository::{Entity, Index, Repository};
+use crate::{DatabaseError, Result};
+
+/// [`Repository`] that keeps every item in a process-local map
+///
+/// Mirrors the DynamoDB backend's semantics (conditional create/update, GSI
+/// lookups via `Entity::index_keys`) so handlers behave the same against either.
+pub struct InMemoryRepository<T> {
+    items: RwLock<HashMap<String, T>>,
+}
+
+impl<T> Default for InMemoryRepository<T> {
+    fn default() -> Self {
+        Self { items: RwLock::new(HashMap::new()) }
+    }
+}
+
+impl<T: Entity> InMemoryRepository<T> {
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, T>> {
+        self.items.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, T>> {
+        self.items.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Top-level attribute as DynamoDB would see it, including index key attributes
+    fn attribute(entity: &T, attribute: &str) -> Result<Option<Value>> {
+        if let Some((_, value)) = entity.index_keys()?.into_iter().find(|(name, _)| *name == attribute) {
+            return Ok(Some(Value::String(value)));
+        }
+        Ok(serde_json::to_value(entity)?.get(attribute).cloned())
+    }
+
+    /// Pages in key order, so a cursor is just the last key returned
+    fn page(mut matches: Vec<T>, page: &PageRequest) -> Result<Page<T>> {
+        matches.sort_by_key(|entity| entity.key());
+        let after = match &page.cursor {
+            Some(cursor) => match cursor.to_item()?.remove(T::KEY) {
+                Some(AttributeValue::S(key)) => Some(key),
+                _ => return Err(DatabaseError::InvalidPage(format!("invalid cursor: {}", cursor))),
+            },
+            None => None,
+        };
+
+        let mut remaining =
+            matches.into_iter().filter(|entity| after.as_ref().is_none_or(|after| entity.key() > *after)).peekable();
+        let items: Vec<T> = remaining.by_ref().take(page.limit as usize).collect();
+        let next_cursor = match (remaining.peek(), items.last()) {
+            (Some(_), Some(last)) => {
+                let key = HashMap::from([(T::KEY.to_string(), AttributeValue::S(last.key()))]);
+                Some(Cursor::from_item(key)?)
+            }
+            _ => None,
+        };
+        Ok(Page { items, next_cursor })
+    }
+}
+
+#[async_trait]
+impl<T: Entity> Repository<T> for InMemoryRepository<T> {
+    async fn create(&self, entity: &T) -> Result<()> {
+        let key = entity.key();
+        let mut items = self.write();
+        if items.contains_key(&key) {
+            return Err(DatabaseError::AlreadyExists(key));
+        }
+        items.insert(key, entity.clone());
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<T> {
+        self.read().get(key).cloned().ok_or_else(|| DatabaseError::NotFound(key.to_string()))
+    }
+
+    async fn update(&self, entity: &T) -> Result<T> {
+        let key = entity.key();
+        let mut items = self.write();
+        let Some(existing) = items.get_mut(&key) else {
+            return Err(DatabaseError::NotFound(key));
+        };
+
+        let mut stored = entity.clone();
+        if let Some(expected) = entity.version() {
+            if existing.version() != Some(expected) {
+                let current = serde_json::to_value(&*existing)?;
+                return Err(DatabaseError::Conflict { key, current });
+            }
+            stored.set_version(expected + 1);
+        }
+
+        *existing = stored.clone();
+        Ok(stored)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.write().remove(key);
+        Ok(())
+    }
+
+    async fn query_index(&self, index: Index, value: &str) -> Result<Vec<T>> {
+        let expected = Value::String(value.to_string());
+        self.scan(Some((index.attribute, expected))).await
+    }
+
+    async fn query_index_page(&self, index: Index, value: &str, page: &PageRequest) -> Result<Page<T>> {
+        Self::page(self.query_index(index, value).await?, page)
+    }
+
+    async fn scan(&self, filter: Option<(&str, Value)>) -> Result<Vec<T>> {
+        let items = self.read();
+        let Some((attribute, expected)) = filter else {
+            return Ok(items.values().cloned().collect());
+        };
+
+        let mut results = Vec::new();
+        for entity in items.values() {
+            if Self::attribute(entity, attribute)?.as_ref() == Some(&expected) {
+                results.push(entity.clone());
+            }
+        }
+        Ok(results)
+    }
+
+    async fn scan_page(&self, filter: Option<(&str, Value)>, page: &PageRequest) -> Result<Page<T>> {
+        Self::page(self.scan(filter).await?, page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use models::{Bag, BagCondition, BagStatus, BagType};
+    use uuid::Uuid;
+
+    fn bag(status: BagStatus) -> Bag {
+        Bag {
+            id: Uuid::new_v4(),
+            original_owner_id: None,
+            current_status: status,
+            bag_type: BagType::Paper,
+            condition: BagCondition::Good,
+            received_at: Utc::now(),
+            cleaned_at: None,
+            last_updated: Utc::now(),
+            contains_seeds: false,
+            seed_ids: vec![],
+            version: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_is_conditional() {
+        let repo = InMemoryRepository::default();
+        let bag = bag(BagStatus::Received);
+
+        repo.create(&bag).await.unwrap();
+        assert!(matches!(repo.create(&bag).await, Err(DatabaseError::AlreadyExists(_))));
+        assert_eq!(repo.get(&bag.id.to_string()).await.unwrap().id, bag.id);
+    }
+
+    #[tokio::test]
+    async fn test_update_requires_existing_item() {
+        let repo = InMemoryRepository::default();
+        let bag = bag(BagStatus::Received);
+
+        assert!(matches!(repo.update(&bag).await, Err(DatabaseError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_rejects_stale_version() {
+        let repo = InMemoryRepository::default();
+        let bag = bag(BagStatus::Received);
+        repo.create(&bag).await.unwrap();
+
+        let mut first = bag.clone();
+        first.current_status = BagStatus::Cleaning;
+        let stored = repo.update(&first).await.unwrap();
+        assert_eq!(stored.version, 1);
+
+        let mut stale = bag.clone();
+        stale.current_status = BagStatus::Recycled;
+        match repo.update(&stale).await {
+            Err(DatabaseError::Conflict { current, .. }) => {
+                assert_eq!(current["version"], 1);
+                assert_eq!(current["current_status"], "cleaning");
+            }
+            other => panic!("expected conflict, got {:?}", other.map(|b| b.version)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scan_page_follows_cursor_to_the_end() {
+        let repo = InMemoryRepository::default();
+        for _ in 0..5 {
+            repo.create(&bag(BagStatus::Received)).await.unwrap();
+        }
+
+        let mut page = PageRequest { limit: 2, cursor: None };
+        let mut seen = Vec::new();
+        loop {
+            let result = repo.scan_page(None, &page).await.unwrap();
+            seen.extend(result.items.into_iter().map(|b| b.id));
+            match result.next_cursor {
+                Some(cursor) => page.cursor = Some(cursor),
+                None => break,
+            }
+        }
+
+        let unique: std::collections::HashSet<_> = seen.iter().collect();
+        assert_eq!(seen.len(), 5);
+        assert_eq!(unique.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_query_index_uses_index_keys() {
+        let repo = InMemoryRepository::default();
+        repo.create(&bag(BagStatus::Received)).await.unwrap();
+        repo.create(&bag(BagStatus::Cleaning)).await.unwrap();
+
+        let received = repo.query_index(Index::STATUS, "received").await.unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].current_status, BagStatus::Received);
+    }
+}
@@ -0,0 +1,128 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::item::{from_attribute, to_attribute, Item};
+use crate::{DatabaseError, Result};
+
+/// Page size used when the caller does not ask for one
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// Largest page a caller may ask for
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+/// Opaque position in a paged listing
+///
+/// Wraps the `LastEvaluatedKey` of the previous page as URL-safe base64 JSON,
+/// so clients can pass it back verbatim as `?cursor=`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Cursor(String);
+
+impl Cursor {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub(crate) fn from_item(key: Item) -> Result<Self> {
+        let mut map = Map::with_capacity(key.len());
+        for (attribute, value) in key {
+            map.insert(attribute, from_attribute(value)?);
+        }
+        Ok(Self(URL_SAFE_NO_PAD.encode(Value::Object(map).to_string())))
+    }
+
+    pub(crate) fn to_item(&self) -> Result<Item> {
+        let invalid = || DatabaseError::InvalidPage(format!("invalid cursor: {}", self.0));
+        let bytes = URL_SAFE_NO_PAD.decode(&self.0).map_err(|_| invalid())?;
+        match serde_json::from_slice(&bytes).map_err(|_| invalid())? {
+            Value::Object(map) => {
+                Ok(map.into_iter().map(|(attribute, value)| (attribute, to_attribute(value))).collect())
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl std::fmt::Display for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Which page of a listing to fetch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRequest {
+    pub limit: u32,
+    pub cursor: Option<Cursor>,
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self { limit: DEFAULT_PAGE_SIZE, cursor: None }
+    }
+}
+
+impl PageRequest {
+    /// Build from the raw `limit` and `cursor` query parameters, clamping the limit
+    /// to `1..=MAX_PAGE_SIZE`
+    pub fn from_params(limit: Option<&str>, cursor: Option<&str>) -> Result<Self> {
+        let limit = match limit {
+            Some(limit) => limit
+                .parse::<u32>()
+                .map_err(|_| DatabaseError::InvalidPage(format!("invalid limit: {}", limit)))?
+                .clamp(1, MAX_PAGE_SIZE),
+            None => DEFAULT_PAGE_SIZE,
+        };
+        let cursor = cursor.filter(|c| !c.is_empty()).map(|c| Cursor(c.to_string()));
+        if let Some(cursor) = &cursor {
+            // Reject garbage up front rather than on the first query
+            cursor.to_item()?;
+        }
+        Ok(Self { limit, cursor })
+    }
+}
+
+/// One page of a listing plus where to resume
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// `None` once the listing is exhausted
+    pub next_cursor: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page { items: self.items.into_iter().map(f).collect(), next_cursor: self.next_cursor }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let key: Item = [
+            ("id".to_string(), AttributeValue::S("abc".to_string())),
+            ("status".to_string(), AttributeValue::S("pending".to_string())),
+        ]
+        .into_iter()
+        .collect();
+
+        let cursor = Cursor::from_item(key.clone()).unwrap();
+        let request = PageRequest::from_params(Some("10"), Some(cursor.as_str())).unwrap();
+
+        assert_eq!(request.limit, 10);
+        assert_eq!(request.cursor.unwrap().to_item().unwrap(), key);
+    }
+
+    #[test]
+    fn test_from_params_rejects_bad_input() {
+        assert!(PageRequest::from_params(Some("ten"), None).is_err());
+        assert!(PageRequest::from_params(None, Some("not-a-cursor")).is_err());
+        assert_eq!(PageRequest::from_params(Some("100000"), None).unwrap().limit, MAX_PAGE_SIZE);
+    }
+}
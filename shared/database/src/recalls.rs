@@ -0,0 +1,140 @@
+use aws_sdk_dynamodb::Client;
+use uuid::Uuid;
+
+use crate::item::key_string;
+use crate::page::{Page, PageRequest};
+use crate::repository::{dynamodb_store, Entity, Index, Store};
+use crate::Result;
+use models::{RecallData, RecallNotification, RecallReview, RecallStatus};
+
+impl Entity for RecallData {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+impl Entity for RecallReview {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+impl Entity for RecallNotification {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+#[derive(Clone)]
+pub struct RecallsRepository {
+    store: Store<RecallData>,
+}
+
+impl RecallsRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "RECALLS_TABLE", "seed-box-recalls"))
+    }
+
+    pub fn with_store(store: Store<RecallData>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, recall: &RecallData) -> Result<()> {
+        self.store.create(recall).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<RecallData> {
+        self.store.get(&id.to_string()).await
+    }
+
+    pub async fn update(&self, recall: &RecallData) -> Result<RecallData> {
+        self.store.update(recall).await
+    }
+
+    pub async fn list_by_status(&self, status: &RecallStatus) -> Result<Vec<RecallData>> {
+        self.store.query_index(Index::STATUS, &key_string(status)?).await
+    }
+
+    pub async fn page_by_status(&self, status: &RecallStatus, page: &PageRequest) -> Result<Page<RecallData>> {
+        self.store.query_index_page(Index::STATUS, &key_string(status)?, page).await
+    }
+
+    /// Look up a recall by its USDA/FDA recall number, to avoid importing it twice
+    pub async fn find_by_external_id(&self, external_id: &str) -> Result<Option<RecallData>> {
+        let matches = self.store.scan(Some(("external_id", external_id.into()))).await?;
+        Ok(matches.into_iter().next())
+    }
+}
+
+#[derive(Clone)]
+pub struct RecallReviewsRepository {
+    store: Store<RecallReview>,
+}
+
+impl RecallReviewsRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "RECALL_REVIEWS_TABLE", "seed-box-recall-reviews"))
+    }
+
+    pub fn with_store(store: Store<RecallReview>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, review: &RecallReview) -> Result<()> {
+        self.store.create(review).await
+    }
+
+    pub async fn update(&self, review: &RecallReview) -> Result<RecallReview> {
+        self.store.update(review).await
+    }
+
+    pub async fn list_by_recall(&self, recall_id: Uuid) -> Result<Vec<RecallReview>> {
+        self.store.query_index(Index::RECALL, &recall_id.to_string()).await
+    }
+}
+
+#[derive(Clone)]
+pub struct RecallNotificationsRepository {
+    store: Store<RecallNotification>,
+}
+
+impl RecallNotificationsRepository {
+    pub fn new(client: Client) -> Self {
+        Self::with_store(dynamodb_store(client, "RECALL_NOTIFICATIONS_TABLE", "seed-box-recall-notifications"))
+    }
+
+    pub fn with_store(store: Store<RecallNotification>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
+    }
+
+    pub async fn create(&self, notification: &RecallNotification) -> Result<()> {
+        self.store.create(notification).await
+    }
+
+    pub async fn update(&self, notification: &RecallNotification) -> Result<RecallNotification> {
+        self.store.update(notification).await
+    }
+
+    pub async fn list_by_recall(&self, recall_id: Uuid) -> Result<Vec<RecallNotification>> {
+        self.store.query_index(Index::RECALL, &recall_id.to_string()).await
+    }
+
+    pub async fn list_by_customer(&self, customer_id: Uuid) -> Result<Vec<RecallNotification>> {
+        self.store.query_index(Index::CUSTOMER, &customer_id.to_string()).await
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::Client;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::dynamodb::DynamoDbRepository;
+use crate::page::{Page, PageRequest};
+use crate::{get_table_name, Result};
+
+/// A model stored as a single item in a single table
+pub trait Entity: Serialize + DeserializeOwned + Clone + Send + Sync + 'static {
+    /// Name of the table's hash key attribute
+    const KEY: &'static str = "id";
+
+    /// Value of the hash key for this item
+    fn key(&self) -> String;
+
+    /// Extra attributes written alongside the model so that GSIs can find it,
+    /// e.g. `[("status", "received")]` for a bag
+    fn index_keys(&self) -> Result<Vec<(&'static str, String)>> {
+        Ok(Vec::new())
+    }
+
+    /// Attributes left off the stored item while unset, e.g. to keep a GSI
+    /// sparse. `update` removes them from the stored item when they are unset
+    const SPARSE: &'static [&'static str] = &[];
+
+    /// Optimistic concurrency counter, for entities that carry one
+    ///
+    /// `update` of a versioned entity only succeeds if the stored item still
+    /// has this version, and stores it with the version bumped.
+    fn version(&self) -> Option<u64> {
+        None
+    }
+
+    fn set_version(&mut self, _version: u64) {}
+}
+
+/// Name of the attribute holding [`Entity::version`]
+pub(crate) const VERSION: &str = "version";
+
+/// A global secondary index whose hash key is a single string attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Index {
+    pub name: &'static str,
+    pub attribute: &'static str,
+}
+
+impl Index {
+    pub const STATUS: Index = Index { name: "status-index", attribute: "status" };
+    pub const CUSTOMER: Index = Index { name: "customer-index", attribute: "customer_id" };
+    pub const ZONE: Index = Index { name: "zone-index", attribute: "zone_id" };
+    pub const PHASE: Index = Index { name: "phase-index", attribute: "phase" };
+    pub const SPECIES: Index = Index { name: "species-index", attribute: "species" };
+    pub const RECORD: Index = Index { name: "record-index", attribute: "germination_record_id" };
+    pub const IMAGE: Index = Index { name: "image-index", attribute: "image_id" };
+    pub const RECALL: Index = Index { name: "recall-index", attribute: "recall_id" };
+    pub const BATCH: Index = Index { name: "batch-index", attribute: "batch_id" };
+    pub const ASSIGNEE: Index = Index { name: "assignee-index", attribute: "assigned_to" };
+}
+
+/// Storage-agnostic access to one table of `T`
+///
+/// Implemented by [`DynamoDbRepository`] for the deployed lambdas and by
+/// `InMemoryRepository` (behind the `in-memory` feature) for tests and the mock server.
+#[async_trait]
+pub trait Repository<T: Entity>: Send + Sync {
+    /// Store a new item, failing with `AlreadyExists` if the key is taken
+    async fn create(&self, entity: &T) -> Result<()>;
+
+    async fn get(&self, key: &str) -> Result<T>;
+
+    /// Replace an existing item, failing with `NotFound` if there is none and
+    /// with `Conflict` if a versioned item changed since it was read
+    ///
+    /// Returns the item as stored, i.e. with its new version.
+    async fn update(&self, entity: &T) -> Result<T>;
+
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    async fn query_index(&self, index: Index, value: &str) -> Result<Vec<T>>;
+
+    /// One page of [`Repository::query_index`], resuming after `page.cursor`
+    async fn query_index_page(&self, index: Index, value: &str, page: &PageRequest) -> Result<Page<T>>;
+
+    /// Number of items [`Repository::query_index`] would return
+    async fn count_index(&self, index: Index, value: &str) -> Result<usize> {
+        Ok(self.query_index(index, value).await?.len())
+    }
+
+    /// Every item, optionally keeping only those where `attribute == value`
+    async fn scan(&self, filter: Option<(&str, Value)>) -> Result<Vec<T>>;
+
+    /// One page of [`Repository::scan`], resuming after `page.cursor`
+    ///
+    /// As with DynamoDB, the limit applies before the filter, so a page may
+    /// hold fewer than `page.limit` items and still have a `next_cursor`.
+    async fn scan_page(&self, filter: Option<(&str, Value)>, page: &PageRequest) -> Result<Page<T>>;
+}
+
+/// Shared handle to a repository backend
+pub type Store<T> = Arc<dyn Repository<T>>;
+
+/// DynamoDB backend for the table named by `env_var`, or `default` if unset
+pub(crate) fn dynamodb_store<T: Entity>(client: Client, env_var: &str, default: &str) -> Store<T> {
+    Arc::new(DynamoDbRepository::new(client, get_table_name(env_var, default)))
+}
+
+#[cfg(feature = "in-memory")]
+pub(crate) fn in_memory_store<T: Entity>() -> Store<T> {
+    Arc::new(crate::memory::InMemoryRepository::default())
+}
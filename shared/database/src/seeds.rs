@@ -1,43 +1,67 @@
 use aws_sdk_dynamodb::Client;
+use serde_json::Value;
 use uuid::Uuid;
 
-use models::Seed;
-use crate::{Result, DatabaseError, get_table_name};
+use crate::item::key_string;
+use crate::page::{Page, PageRequest};
+use crate::repository::{dynamodb_store, Entity, Index, Store};
+use crate::Result;
+use models::{Seed, SeedStatus};
 
+impl Entity for Seed {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+#[derive(Clone)]
 pub struct SeedsRepository {
-    client: Client,
-    table_name: String,
+    store: Store<Seed>,
 }
 
 impl SeedsRepository {
     pub fn new(client: Client) -> Self {
-        let table_name = get_table_name("SEEDS_TABLE", "seed-box-seeds");
-        Self { client, table_name }
+        Self::with_store(dynamodb_store(client, "SEEDS_TABLE", "seed-box-seeds"))
+    }
+
+    pub fn with_store(store: Store<Seed>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
     }
 
     pub async fn create(&self, seed: &Seed) -> Result<()> {
-        // TODO: Implement DynamoDB put_item
-        Ok(())
+        self.store.create(seed).await
     }
 
     pub async fn get(&self, id: Uuid) -> Result<Seed> {
-        // TODO: Implement DynamoDB get_item
-        Err(DatabaseError::NotFound(id.to_string()))
+        self.store.get(&id.to_string()).await
     }
 
-    pub async fn update(&self, seed: &Seed) -> Result<()> {
-        // TODO: Implement DynamoDB update_item
-        Ok(())
+    pub async fn update(&self, seed: &Seed) -> Result<Seed> {
+        self.store.update(seed).await
     }
 
-    pub async fn list_by_status(&self, status: &str) -> Result<Vec<Seed>> {
-        // TODO: Implement DynamoDB query using status-index GSI
-        Ok(vec![])
+    pub async fn list_by_status(&self, status: &SeedStatus) -> Result<Vec<Seed>> {
+        self.store.query_index(Index::STATUS, &key_string(status)?).await
+    }
+
+    pub async fn page_by_status(&self, status: &SeedStatus, page: &PageRequest) -> Result<Page<Seed>> {
+        self.store.query_index_page(Index::STATUS, &key_string(status)?, page).await
     }
 
     pub async fn list_by_species(&self, species: &str) -> Result<Vec<Seed>> {
-        // TODO: Implement DynamoDB scan with filter
-        Ok(vec![])
+        self.store.scan(Some(("plant_species", Value::from(species)))).await
     }
-}
 
+    pub async fn list_all(&self) -> Result<Vec<Seed>> {
+        self.store.scan(None).await
+    }
+
+    pub async fn page_all(&self, page: &PageRequest) -> Result<Page<Seed>> {
+        self.store.scan_page(None, page).await
+    }
+}
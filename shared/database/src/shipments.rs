@@ -1,43 +1,76 @@
 use aws_sdk_dynamodb::Client;
 use uuid::Uuid;
 
-use models::ShipmentCycle;
-use crate::{Result, DatabaseError, get_table_name};
+use crate::repository::{dynamodb_store, Entity, Index, Store};
+use crate::{DatabaseError, Result};
+use models::{LegStatus, ShipmentCycle};
 
+impl Entity for ShipmentCycle {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+}
+
+#[derive(Clone)]
 pub struct ShipmentsRepository {
-    client: Client,
-    table_name: String,
+    store: Store<ShipmentCycle>,
 }
 
 impl ShipmentsRepository {
     pub fn new(client: Client) -> Self {
-        let table_name = get_table_name("SHIPMENTS_TABLE", "seed-box-shipments");
-        Self { client, table_name }
+        Self::with_store(dynamodb_store(client, "SHIPMENTS_TABLE", "seed-box-shipments"))
+    }
+
+    pub fn with_store(store: Store<ShipmentCycle>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
     }
 
     pub async fn create(&self, shipment: &ShipmentCycle) -> Result<()> {
-        // TODO: Implement DynamoDB put_item
-        Ok(())
+        self.store.create(shipment).await
     }
 
     pub async fn get(&self, id: Uuid) -> Result<ShipmentCycle> {
-        // TODO: Implement DynamoDB get_item
-        Err(DatabaseError::NotFound(id.to_string()))
+        self.store.get(&id.to_string()).await
     }
 
     pub async fn get_by_customer(&self, customer_id: Uuid) -> Result<Vec<ShipmentCycle>> {
-        // TODO: Implement DynamoDB query using customer-index GSI
-        Ok(vec![])
+        self.store.query_index(Index::CUSTOMER, &customer_id.to_string()).await
     }
 
-    pub async fn update(&self, shipment: &ShipmentCycle) -> Result<()> {
-        // TODO: Implement DynamoDB update_item
-        Ok(())
+    pub async fn update(&self, shipment: &ShipmentCycle) -> Result<ShipmentCycle> {
+        self.store.update(shipment).await
     }
 
-    pub async fn update_leg_status(&self, shipment_id: Uuid, leg_number: u32, status: &str) -> Result<()> {
-        // TODO: Implement DynamoDB update_item for specific leg
-        Ok(())
+    /// Update the status of a single leg, returning the updated cycle
+    pub async fn update_leg_status(
+        &self,
+        shipment_id: Uuid,
+        leg_number: u32,
+        status: &LegStatus,
+    ) -> Result<ShipmentCycle> {
+        let mut shipment = self.get(shipment_id).await?;
+        let leg = shipment
+            .legs
+            .iter_mut()
+            .find(|leg| leg.leg_number == leg_number)
+            .ok_or_else(|| DatabaseError::NotFound(format!("{} leg {}", shipment_id, leg_number)))?;
+
+        leg.status = status.clone();
+        shipment.updated_at = chrono::Utc::now();
+
+        self.update(&shipment).await
     }
 }
-
@@ -1,43 +1,53 @@
 use aws_sdk_dynamodb::Client;
 use uuid::Uuid;
 
-use models::Subscription;
-use crate::{Result, DatabaseError, get_table_name};
+use crate::repository::{dynamodb_store, Entity, Index, Store};
+use crate::Result;
+use models::{Subscription, SubscriptionStatus};
 
+impl Entity for Subscription {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+#[derive(Clone)]
 pub struct SubscriptionsRepository {
-    client: Client,
-    table_name: String,
+    store: Store<Subscription>,
 }
 
 impl SubscriptionsRepository {
     pub fn new(client: Client) -> Self {
-        let table_name = get_table_name("SUBSCRIPTIONS_TABLE", "seed-box-subscriptions");
-        Self { client, table_name }
+        Self::with_store(dynamodb_store(client, "SUBSCRIPTIONS_TABLE", "seed-box-subscriptions"))
+    }
+
+    pub fn with_store(store: Store<Subscription>) -> Self {
+        Self { store }
+    }
+
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self::with_store(crate::repository::in_memory_store())
     }
 
     pub async fn create(&self, subscription: &Subscription) -> Result<()> {
-        // TODO: Implement DynamoDB put_item
-        Ok(())
+        self.store.create(subscription).await
     }
 
     pub async fn get(&self, id: Uuid) -> Result<Subscription> {
-        // TODO: Implement DynamoDB get_item
-        Err(DatabaseError::NotFound(id.to_string()))
+        self.store.get(&id.to_string()).await
     }
 
     pub async fn get_by_customer(&self, customer_id: Uuid) -> Result<Vec<Subscription>> {
-        // TODO: Implement DynamoDB query using customer-index GSI
-        Ok(vec![])
+        self.store.query_index(Index::CUSTOMER, &customer_id.to_string()).await
     }
 
-    pub async fn update(&self, subscription: &Subscription) -> Result<()> {
-        // TODO: Implement DynamoDB update_item
-        Ok(())
+    pub async fn update(&self, subscription: &Subscription) -> Result<Subscription> {
+        self.store.update(subscription).await
     }
 
     pub async fn list_active(&self) -> Result<Vec<Subscription>> {
-        // TODO: Implement DynamoDB scan with filter for active status
-        Ok(vec![])
+        let active = serde_json::to_value(SubscriptionStatus::Active)?;
+        self.store.scan(Some(("status", active))).await
     }
 }
-
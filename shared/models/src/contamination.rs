@@ -1,9 +1,10 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Contamination tracking and food safety management
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ContaminationReport {
     pub id: Uuid,
     pub report_type: ReportType,
@@ -19,7 +20,7 @@ pub struct ContaminationReport {
     pub notes: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ReportType {
     Internal,
@@ -28,7 +29,7 @@ pub enum ReportType {
     Recall,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ItemType {
     Seed,
@@ -37,7 +38,7 @@ pub enum ItemType {
     Zone,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum ContaminationType {
     Bacterial(BacterialContamination),
     Fungal(FungalContamination),
@@ -46,7 +47,7 @@ pub enum ContaminationType {
     Physical(PhysicalContamination),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum BacterialContamination {
     Salmonella,
     EColi,
@@ -54,7 +55,7 @@ pub enum BacterialContamination {
     Other(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum FungalContamination {
     Mold,
     Mildew,
@@ -63,7 +64,7 @@ pub enum FungalContamination {
     Other(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum PestContamination {
     Insects,
     Rodents,
@@ -71,7 +72,7 @@ pub enum PestContamination {
     Other(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum ChemicalContamination {
     Pesticide,
     Herbicide,
@@ -79,13 +80,13 @@ pub enum ChemicalContamination {
     Other(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum PhysicalContamination {
     Foreign(String),
     Damage,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SeverityLevel {
     Low,
@@ -94,7 +95,7 @@ pub enum SeverityLevel {
     Critical,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum RemediationStatus {
     Identified,
@@ -106,7 +107,7 @@ pub enum RemediationStatus {
 }
 
 /// USDA/FDA recall tracking
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RecallAlert {
     pub id: Uuid,
     pub source_agency: RegulatoryAgency,
@@ -122,7 +123,7 @@ pub struct RecallAlert {
     pub url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum RegulatoryAgency {
     Usda,
@@ -132,7 +133,7 @@ pub enum RegulatoryAgency {
     Epa,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum HealthHazardLevel {
     ClassI,   // Serious adverse health consequences or death
     ClassII,  // Temporary or reversible adverse health consequences
@@ -140,7 +141,7 @@ pub enum HealthHazardLevel {
 }
 
 /// Food safety compliance checklist
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SafetyChecklist {
     pub id: Uuid,
     pub checklist_type: ChecklistType,
@@ -151,7 +152,7 @@ pub struct SafetyChecklist {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ChecklistType {
     BagCleaning,
@@ -161,7 +162,7 @@ pub enum ChecklistType {
     ShipmentPrep,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ChecklistItem {
     pub item_number: u32,
     pub description: String,
@@ -169,4 +170,3 @@ pub struct ChecklistItem {
     pub compliant: bool,
     pub notes: Option<String>,
 }
-
@@ -1,9 +1,13 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::manufacturing::Severity;
+use crate::plant::DifficultyLevel;
+
 /// Germination tracking for seeds that will be sent back as sprouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GerminationRecord {
     pub id: Uuid,
     pub seed_id: Uuid,
@@ -11,11 +15,11 @@ pub struct GerminationRecord {
     pub customer_id: Uuid,
     pub species: String,
     pub variety: Option<String>,
-    
+
     /// Germination phase tracking
     pub germination_phase: GerminationPhase,
     pub started_at: DateTime<Utc>,
-    
+
     /// Botanical stage timestamps
     pub imbibition_started_at: DateTime<Utc>,
     pub radicle_emerged_at: Option<DateTime<Utc>>,
@@ -24,43 +28,69 @@ pub struct GerminationRecord {
     pub true_leaf_emerged_at: Option<DateTime<Utc>>, // Becomes "true plant" at this point
     pub photosynthesis_started_at: Option<DateTime<Utc>>,
     pub ready_for_shipment_at: Option<DateTime<Utc>>,
-    
+
     /// Growing conditions
     pub growing_medium: GrowingMedium,
     pub temperature_celsius: Option<f32>,
     pub humidity_percent: Option<f32>,
     pub light_hours_per_day: Option<f32>,
-    
+
     /// Health and quality
     pub germination_success: bool,
     pub health_status: SproutHealthStatus,
-    
+
     /// Growth measurements
     pub root_length_mm: Option<f32>,
     pub shoot_length_mm: Option<f32>,
-    pub cotyledon_count: Option<u32>, // Usually 2, but can vary by species
-    pub true_leaf_count: Option<u32>, // Count of true leaves (not cotyledons)
+    pub cotyledon_count: Option<u32>,  // Usually 2, but can vary by species
+    pub true_leaf_count: Option<u32>,  // Count of true leaves (not cotyledons)
     pub total_leaf_count: Option<u32>, // Cotyledons + true leaves
-    
+
     /// True plant indicators
     pub is_true_plant: bool, // True when true leaves have emerged
     pub is_autotrophic: bool, // True when photosynthesizing independently
-    
+
     /// Edible parts indicators
     pub has_edible_fruit_potential: Option<bool>, // Whether this species bears edible fruit
     pub has_edible_leaves_potential: Option<bool>, // Whether this species has edible leaves
     pub has_edible_stalks_potential: Option<bool>, // Whether this species has edible stalks/stems
-    
+
     /// Shipment information
     pub shipment_type: ShipmentType,
     pub estimated_ship_date: Option<DateTime<Utc>>,
     pub actual_ship_date: Option<DateTime<Utc>>,
     pub customer_instructions: Option<String>,
-    
+
     pub notes: Option<String>,
+
+    /// Phase the stall evaluator last queued the record for review in, so
+    /// each stall is reviewed once
+    #[serde(default)]
+    pub stalled_phase: Option<GerminationPhase>,
+
+    /// Conditions that stayed outside the guide's ranges, open and resolved
+    #[serde(default)]
+    pub environmental_alerts: Vec<EnvironmentalAlert>,
+
+    /// The forecast behind `estimated_ship_date`, refreshed by every observation
+    #[serde(default)]
+    pub ship_date_forecast: Option<ShipDateForecast>,
+
+    /// The tray the seed was started in, if any
+    #[serde(default)]
+    pub tray_id: Option<Uuid>,
+
+    /// The proposed or approved package holding the record, claimed before
+    /// the package is written so two allocation runs can't both pack it
+    #[serde(default)]
+    pub package_id: Option<Uuid>,
+
+    /// Optimistic concurrency counter, bumped on every successful update
+    #[serde(default)]
+    pub version: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum GerminationPhase {
     /// Phase 1: Seed absorbs water and begins to swell
@@ -87,7 +117,7 @@ pub enum GerminationPhase {
     Failed,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum GrowingMedium {
     Soil,
@@ -99,7 +129,7 @@ pub enum GrowingMedium {
     Other(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SproutHealthStatus {
     Excellent,
@@ -110,7 +140,7 @@ pub enum SproutHealthStatus {
     Dead,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ShipmentType {
     /// Sprout with roots in growing medium
@@ -124,11 +154,11 @@ pub enum ShipmentType {
 }
 
 /// Species-specific germination guide
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GerminationGuide {
     pub species: String,
     pub variety: Option<String>,
-    
+
     /// Timing information for each stage
     pub imbibition_days: u32, // Days for seed to absorb water and swell (1-3 typically)
     pub radicle_emergence_days_min: u32,
@@ -142,49 +172,67 @@ pub struct GerminationGuide {
     pub photosynthesis_days_min: u32, // When plant becomes autotrophic
     pub photosynthesis_days_max: u32,
     pub ready_to_ship_days: u32, // Days after imbibition started
-    
+
     /// Optimal conditions
     pub optimal_temperature_celsius: TemperatureRange,
     pub optimal_humidity_percent: HumidityRange,
     pub light_requirement: LightRequirement,
     pub preferred_medium: Vec<GrowingMedium>,
-    
+
     /// Shipping readiness criteria
     pub min_root_length_mm: f32,
     pub min_shoot_length_mm: f32,
-    pub min_true_leaf_count: u32, // Minimum true leaves (not cotyledons)
-    pub must_be_true_plant: bool, // Require true leaves before shipping
+    pub min_true_leaf_count: u32,  // Minimum true leaves (not cotyledons)
+    pub must_be_true_plant: bool,  // Require true leaves before shipping
     pub must_be_autotrophic: bool, // Require photosynthesis before shipping
-    
+
     /// Special instructions
     pub pre_soak_required: bool,
     pub pre_soak_hours: Option<u32>,
     pub scarification_required: bool,
     pub stratification_required: bool,
     pub stratification_days: Option<u32>,
-    
+
     /// Care instructions for customer
     pub planting_depth_mm: f32,
     pub spacing_cm: f32,
     pub days_to_maturity: u32,
     pub customer_care_instructions: String,
+    /// How hard it is for a customer to grow on; hard species aren't sent to beginners
+    #[serde(default)]
+    pub difficulty: DifficultyLevel,
+
+    /// Varieties that differ from the species guide
+    #[serde(default)]
+    pub varieties: Vec<VarietyOverride>,
+    /// Optimistic concurrency counter, bumped on every update
+    #[serde(default)]
+    pub version: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A variety's differences from its species guide
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VarietyOverride {
+    pub variety: String,
+    /// Guide fields to replace, e.g. `{"ready_to_ship_days": 21}`; the rest are inherited
+    pub overrides: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TemperatureRange {
     pub min: f32,
     pub max: f32,
     pub optimal: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HumidityRange {
     pub min: f32,
     pub max: f32,
     pub optimal: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum LightRequirement {
     /// No light needed, darkness preferred
@@ -200,13 +248,13 @@ pub enum LightRequirement {
 }
 
 /// Daily observation log for germination tracking
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GerminationObservation {
     pub id: Uuid,
     pub germination_record_id: Uuid,
     pub observed_at: DateTime<Utc>,
     pub observed_by: String,
-    
+
     pub root_length_mm: Option<f32>,
     pub shoot_length_mm: Option<f32>,
     pub cotyledon_count: Option<u32>,
@@ -215,56 +263,206 @@ pub struct GerminationObservation {
     pub cotyledon_color: Option<String>,
     pub true_leaf_color: Option<String>,
     pub health_status: SproutHealthStatus,
-    
+
     /// Botanical observations
     pub radicle_visible: bool,
     pub shoot_visible: bool,
     pub cotyledons_expanded: bool,
     pub true_leaves_present: bool,
     pub appears_autotrophic: bool, // Leaves look healthy and photosynthesizing
-    
+
     pub temperature_celsius: Option<f32>,
     pub humidity_percent: Option<f32>,
-    
+    /// Readings outside the guide's ranges when the observation was recorded
+    #[serde(default)]
+    pub environmental_deviations: Vec<EnvironmentalDeviation>,
+
     pub issues_noted: Vec<String>,
     pub actions_taken: Vec<String>,
     pub notes: Option<String>,
     pub photo_url: Option<String>,
 }
 
+/// Seeds started together in one tray, with a germination record per cell
+///
+/// Every cell shares the tray's medium, conditions and start time.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GerminationTray {
+    pub id: Uuid,
+    pub label: String,
+    pub rows: u32,
+    pub columns: u32,
+    pub growing_medium: GrowingMedium,
+    pub temperature_celsius: Option<f32>,
+    pub humidity_percent: Option<f32>,
+    pub light_hours_per_day: Option<f32>,
+    pub started_at: DateTime<Utc>,
+    pub started_by: String,
+    pub cells: Vec<TrayCell>,
+    pub notes: Option<String>,
+}
+
+/// A seeded cell; rows and columns count from zero
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TrayCell {
+    pub row: u32,
+    pub column: u32,
+    pub seed_id: Uuid,
+    /// Supplier or harvest lot the seed came from
+    pub seed_lot: Option<String>,
+    pub germination_record_id: Uuid,
+}
+
+/// When a record is expected to be ready to ship, and how sure we are
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ShipDateForecast {
+    pub estimated: DateTime<Utc>,
+    /// Bounds of the `confidence` interval
+    pub earliest: DateTime<Utc>,
+    pub latest: DateTime<Utc>,
+    pub confidence: f32,
+    /// Observations the growth curves were fitted to
+    pub observations: usize,
+    /// Past records of the species behind the prior; 0 while the guide's
+    /// `ready_to_ship_days` stands in
+    pub prior_records: usize,
+    pub forecast_at: DateTime<Utc>,
+}
+
+/// A shipment criterion from the guide
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ShipmentCriterion {
+    RootLength,
+    ShootLength,
+    TrueLeaves,
+    TruePlant,
+    Autotrophic,
+    Health,
+}
+
+/// A criterion's actual value against what the guide requires
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CriterionResult {
+    pub criterion: ShipmentCriterion,
+    pub passed: bool,
+    /// `None` until the record has been measured
+    pub actual: Option<serde_json::Value>,
+    pub required: serde_json::Value,
+    /// When a failing criterion should pass; `None` once passed or when
+    /// there is no growth to project from
+    pub projected_pass_at: Option<DateTime<Utc>>,
+}
+
+/// How close a record is to its guide's shipment criteria
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Readiness {
+    /// Every criterion passes and the seed germinated
+    pub ready: bool,
+    pub criteria: Vec<CriterionResult>,
+    /// When the last failing criterion should pass; `None` when ready or when
+    /// a failing criterion has no projection
+    pub projected_ready_at: Option<DateTime<Utc>>,
+}
+
+/// A growing condition the guide gives an optimal range for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EnvironmentalCondition {
+    Temperature,
+    Humidity,
+}
+
+/// A reading outside the guide's range
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EnvironmentalDeviation {
+    pub condition: EnvironmentalCondition,
+    pub reading: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+/// Raised when a condition stays out of range across consecutive observations
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EnvironmentalAlert {
+    pub condition: EnvironmentalCondition,
+    /// Warning at first, critical once the run reaches `CRITICAL_AFTER_OBSERVATIONS`
+    pub severity: Severity,
+    /// Consecutive out-of-range readings so far
+    pub consecutive_observations: u32,
+    pub latest_reading: f32,
+    pub raised_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Set by the first reading back in range
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Consecutive out-of-range readings that raise a warning
+pub const ALERT_AFTER_OBSERVATIONS: u32 = 2;
+/// Consecutive out-of-range readings that escalate it to critical
+pub const CRITICAL_AFTER_OBSERVATIONS: u32 = 4;
+
 /// Package configuration for shipping live sprouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SproutShipmentPackage {
     pub id: Uuid,
     pub germination_records: Vec<Uuid>, // Multiple sprouts can ship together
     pub customer_id: Uuid,
     pub shipment_cycle_id: Option<Uuid>,
-    
+
     pub package_type: PackageType,
     pub container_count: u32,
     pub total_sprouts: u32,
-    
+
     /// Packaging materials
     pub moisture_retention: bool, // Wet paper towels, etc.
     pub temperature_control: bool, // Ice packs or heat packs
     pub ventilation: bool,
-    
+
     /// Shipping constraints
     pub expedited_shipping_required: bool,
-    pub ship_on_days: Vec<Weekday>, // e.g., only ship Mon-Wed to avoid weekend delays
+    /// Weekdays a package can leave and still reach the customer on a delivery day without a layover
+    pub ship_on_days: Vec<Weekday>,
     pub max_transit_days: u32,
-    
+
     /// Customer communication
     pub care_instructions_included: bool,
     pub transplant_instructions_included: bool,
     pub species_info_card_included: bool,
-    
+
     pub packed_at: Option<DateTime<Utc>>,
     pub shipped_at: Option<DateTime<Utc>>,
     pub expected_delivery: Option<DateTime<Utc>>,
+
+    /// Allocator proposals wait for staff approval; staff-prepared packages start approved
+    pub status: PackageStatus,
+    /// Why the allocator chose these sprouts for the customer
+    #[serde(default)]
+    pub allocation_reasons: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// Optimistic concurrency counter, bumped on every update
+    #[serde(default)]
+    pub version: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageStatus {
+    Proposed,
+    Approved,
+    Rejected,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl PackageStatus {
+    /// Holding its sprouts; a rejected proposal releases them
+    pub fn is_open(&self) -> bool {
+        !matches!(self, Self::Rejected)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum PackageType {
     /// Small cardboard box with ventilation holes
     VentilatedBox,
@@ -276,7 +474,7 @@ pub enum PackageType {
     Custom(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Weekday {
     Monday,
@@ -288,24 +486,38 @@ pub enum Weekday {
     Sunday,
 }
 
+impl From<chrono::Weekday> for Weekday {
+    fn from(day: chrono::Weekday) -> Self {
+        match day {
+            chrono::Weekday::Mon => Self::Monday,
+            chrono::Weekday::Tue => Self::Tuesday,
+            chrono::Weekday::Wed => Self::Wednesday,
+            chrono::Weekday::Thu => Self::Thursday,
+            chrono::Weekday::Fri => Self::Friday,
+            chrono::Weekday::Sat => Self::Saturday,
+            chrono::Weekday::Sun => Self::Sunday,
+        }
+    }
+}
+
 /// Customer preferences for sprout delivery
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CustomerSproutPreferences {
     pub customer_id: Uuid,
-    
+
     /// What types of sprouts customer wants
     pub preferred_species: Vec<String>,
     pub excluded_species: Vec<String>,
     pub preferred_shipment_type: ShipmentType,
-    
+
     /// Timing preferences
     pub delivery_frequency: DeliveryFrequency,
     pub preferred_delivery_days: Vec<Weekday>,
-    
+
     /// Quantity preferences
     pub sprouts_per_shipment_min: u32,
     pub sprouts_per_shipment_max: u32,
-    
+
     /// Growing experience level
     pub experience_level: ExperienceLevel,
     pub has_grow_lights: bool,
@@ -313,7 +525,7 @@ pub struct CustomerSproutPreferences {
     pub has_outdoor_space: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum DeliveryFrequency {
     Weekly,
@@ -323,7 +535,20 @@ pub enum DeliveryFrequency {
     AsReady, // Ship as soon as sprouts are ready
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl DeliveryFrequency {
+    /// Shortest gap between two packages to the customer
+    pub fn min_days_between(&self) -> i64 {
+        match self {
+            Self::Weekly => 7,
+            Self::Biweekly => 14,
+            Self::Monthly => 28,
+            Self::Seasonal => 90,
+            Self::AsReady => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
 pub enum ExperienceLevel {
     Beginner,
@@ -332,6 +557,31 @@ pub enum ExperienceLevel {
     Expert,
 }
 
+impl CustomerSproutPreferences {
+    /// Whether the customer can be trusted with a species this hard: beginners
+    /// get easy and medium ones, hard ones go to advanced growers and to
+    /// intermediate ones with grow lights or a greenhouse
+    pub fn can_grow(&self, difficulty: DifficultyLevel) -> bool {
+        match difficulty {
+            DifficultyLevel::Easy | DifficultyLevel::Medium => true,
+            DifficultyLevel::Hard => match self.experience_level {
+                ExperienceLevel::Beginner => false,
+                ExperienceLevel::Intermediate => self.has_grow_lights || self.has_greenhouse,
+                ExperienceLevel::Advanced | ExperienceLevel::Expert => true,
+            },
+        }
+    }
+
+    /// Whether the customer has somewhere bright enough for the guide's light requirement
+    pub fn has_light_for(&self, requirement: &LightRequirement) -> bool {
+        match requirement {
+            LightRequirement::Dark | LightRequirement::Low | LightRequirement::Medium => true,
+            LightRequirement::High => self.has_grow_lights || self.has_greenhouse || self.has_outdoor_space,
+            LightRequirement::Photoperiod { .. } => self.has_grow_lights || self.has_greenhouse,
+        }
+    }
+}
+
 impl GerminationPhase {
     /// Get the next phase in the germination process
     pub fn next_phase(&self) -> Option<Self> {
@@ -348,12 +598,38 @@ impl GerminationPhase {
             Self::Delivered | Self::Failed => None,
         }
     }
-    
+
+    /// Whether a record may move from this phase to `to` without an admin
+    /// override: one step along `next_phase`, or to `Failed` while still growing
+    pub fn can_transition_to(&self, to: &Self) -> bool {
+        self.next_phase().as_ref() == Some(to) || (*to == Self::Failed && self.is_growing())
+    }
+
+    /// The phases passed through, in order, to reach `target` by `next_phase`
+    /// steps; `None` when `target` is not ahead of this phase
+    pub fn steps_to(&self, target: &Self) -> Option<Vec<Self>> {
+        let mut steps = Vec::new();
+        let mut phase = self.clone();
+        while let Some(next) = phase.next_phase() {
+            steps.push(next.clone());
+            if next == *target {
+                return Some(steps);
+            }
+            phase = next;
+        }
+        None
+    }
+
+    /// Still with the grower: neither shipped nor failed
+    pub fn is_growing(&self) -> bool {
+        !matches!(self, Self::Shipped | Self::Delivered | Self::Failed)
+    }
+
     /// Check if sprout is ready for shipment
     pub fn is_shippable(&self) -> bool {
         matches!(self, Self::ReadyForShipment)
     }
-    
+
     /// Check if plant has reached "true plant" stage (true leaves emerged)
     pub fn is_true_plant(&self) -> bool {
         matches!(
@@ -366,19 +642,15 @@ impl GerminationPhase {
                 | Self::Delivered
         )
     }
-    
+
     /// Check if plant is autotrophic (performing photosynthesis)
     pub fn is_autotrophic(&self) -> bool {
         matches!(
             self,
-            Self::Photosynthesis
-                | Self::ContinuedGrowth
-                | Self::ReadyForShipment
-                | Self::Shipped
-                | Self::Delivered
+            Self::Photosynthesis | Self::ContinuedGrowth | Self::ReadyForShipment | Self::Shipped | Self::Delivered
         )
     }
-    
+
     /// Get human-readable description of the phase
     pub fn description(&self) -> &str {
         match self {
@@ -397,49 +669,373 @@ impl GerminationPhase {
     }
 }
 
+impl GerminationGuide {
+    /// Days after the start by which a record in `phase` should have reached
+    /// the next stage (that stage's `*_days_max`); `None` once the timed
+    /// stages are behind it
+    pub fn stage_deadline_days(&self, phase: &GerminationPhase) -> Option<u32> {
+        match phase {
+            GerminationPhase::Imbibition => Some(self.radicle_emergence_days_max),
+            GerminationPhase::RadicleEmergence => Some(self.shoot_emergence_days_max),
+            GerminationPhase::ShootEmergence => Some(self.cotyledon_expansion_days_max),
+            GerminationPhase::CotyledonExpansion => Some(self.true_leaf_emergence_days_max),
+            GerminationPhase::TrueLeafEmergence => Some(self.photosynthesis_days_max),
+            _ => None,
+        }
+    }
+
+    /// The readings that fall outside the optimal temperature and humidity ranges
+    pub fn environmental_deviations(
+        &self,
+        temperature_celsius: Option<f32>,
+        humidity_percent: Option<f32>,
+    ) -> Vec<EnvironmentalDeviation> {
+        let temperature = &self.optimal_temperature_celsius;
+        let humidity = &self.optimal_humidity_percent;
+        [
+            (EnvironmentalCondition::Temperature, temperature_celsius, temperature.min, temperature.max),
+            (EnvironmentalCondition::Humidity, humidity_percent, humidity.min, humidity.max),
+        ]
+        .into_iter()
+        .filter_map(|(condition, reading, min, max)| {
+            let reading = reading?;
+            (reading < min || reading > max).then_some(EnvironmentalDeviation { condition, reading, min, max })
+        })
+        .collect()
+    }
+}
+
+impl EnvironmentalCondition {
+    /// The observation's reading of this condition, if it took one
+    pub fn reading(&self, observation: &GerminationObservation) -> Option<f32> {
+        match self {
+            Self::Temperature => observation.temperature_celsius,
+            Self::Humidity => observation.humidity_percent,
+        }
+    }
+}
+
+impl EnvironmentalDeviation {
+    /// For an observation's `issues_noted`
+    pub fn describe(&self) -> String {
+        let (name, unit) = match self.condition {
+            EnvironmentalCondition::Temperature => ("Temperature", "°C"),
+            EnvironmentalCondition::Humidity => ("Humidity", "%"),
+        };
+        let side = if self.reading < self.min { "below" } else { "above" };
+        format!("{} {}{} is {} the guide's {}-{}{} range", name, self.reading, unit, side, self.min, self.max, unit)
+    }
+}
+
 impl GerminationRecord {
     /// Check if sprout meets shipment criteria based on guide
     pub fn meets_shipment_criteria(&self, guide: &GerminationGuide) -> bool {
-        if !self.germination_success {
-            return false;
+        self.germination_success && self.shipment_criteria(guide).iter().all(|result| result.passed)
+    }
+
+    /// Each shipment criterion with the record's value against the guide's.
+    /// `observations` (oldest first) give the growth rates that project when
+    /// a failing criterion will pass
+    pub fn readiness(
+        &self,
+        guide: &GerminationGuide,
+        observations: &[GerminationObservation],
+        now: DateTime<Utc>,
+    ) -> Readiness {
+        let mut criteria = self.shipment_criteria(guide);
+        for result in criteria.iter_mut().filter(|result| !result.passed) {
+            result.projected_pass_at = self.projected_pass_at(result.criterion, guide, observations, now);
         }
-        
-        // Check physical measurements
-        let root_ok = self.root_length_mm
-            .map(|len| len >= guide.min_root_length_mm)
-            .unwrap_or(false);
-        
-        let shoot_ok = self.shoot_length_mm
-            .map(|len| len >= guide.min_shoot_length_mm)
-            .unwrap_or(false);
-        
-        let true_leaf_ok = self.true_leaf_count
-            .map(|count| count >= guide.min_true_leaf_count)
-            .unwrap_or(false);
-        
-        // Check botanical maturity
-        let true_plant_ok = !guide.must_be_true_plant || self.is_true_plant;
-        let autotrophic_ok = !guide.must_be_autotrophic || self.is_autotrophic;
-        
-        // Check health
-        let health_ok = matches!(
-            self.health_status,
-            SproutHealthStatus::Excellent | SproutHealthStatus::Good
-        );
-        
-        root_ok && shoot_ok && true_leaf_ok && true_plant_ok && autotrophic_ok && health_ok
+
+        let ready = self.germination_success && criteria.iter().all(|result| result.passed);
+        let projected_ready_at = if ready {
+            None
+        } else {
+            criteria
+                .iter()
+                .filter(|result| !result.passed)
+                .map(|result| result.projected_pass_at)
+                .collect::<Option<Vec<_>>>()
+                .and_then(|dates| dates.into_iter().max())
+        };
+
+        Readiness { ready, criteria, projected_ready_at }
     }
-    
+
+    fn shipment_criteria(&self, guide: &GerminationGuide) -> Vec<CriterionResult> {
+        let measured = |criterion, actual: Option<f32>, required: f32| CriterionResult {
+            criterion,
+            // Nothing measured yet fails, but shows as `actual: null`
+            passed: actual.is_some_and(|actual| actual >= required),
+            actual: actual.map(serde_json::Value::from),
+            required: required.into(),
+            projected_pass_at: None,
+        };
+        let flag = |criterion, actual: bool, required: bool| CriterionResult {
+            criterion,
+            passed: actual || !required,
+            actual: Some(actual.into()),
+            required: required.into(),
+            projected_pass_at: None,
+        };
+        let healthy = [SproutHealthStatus::Excellent, SproutHealthStatus::Good];
+
+        vec![
+            measured(ShipmentCriterion::RootLength, self.root_length_mm, guide.min_root_length_mm),
+            measured(ShipmentCriterion::ShootLength, self.shoot_length_mm, guide.min_shoot_length_mm),
+            measured(
+                ShipmentCriterion::TrueLeaves,
+                self.true_leaf_count.map(|count| count as f32),
+                guide.min_true_leaf_count as f32,
+            ),
+            flag(ShipmentCriterion::TruePlant, self.is_true_plant, guide.must_be_true_plant),
+            flag(ShipmentCriterion::Autotrophic, self.is_autotrophic, guide.must_be_autotrophic),
+            CriterionResult {
+                criterion: ShipmentCriterion::Health,
+                passed: matches!(self.health_status, SproutHealthStatus::Excellent | SproutHealthStatus::Good),
+                actual: serde_json::to_value(&self.health_status).ok(),
+                required: serde_json::to_value(healthy).unwrap_or_default(),
+                projected_pass_at: None,
+            },
+        ]
+    }
+
+    /// Measurements extrapolate their observed growth; stages use the guide's
+    /// latest day for them. Health can't be projected
+    fn projected_pass_at(
+        &self,
+        criterion: ShipmentCriterion,
+        guide: &GerminationGuide,
+        observations: &[GerminationObservation],
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let growth =
+            |reading: fn(&GerminationObservation) -> Option<f32>, since: Option<DateTime<Utc>>, required: f32| {
+                let points: Vec<_> = observations
+                    .iter()
+                    .filter_map(|observation| Some((observation.observed_at, reading(observation)?)))
+                    .collect();
+                project_growth(&points, since.unwrap_or(self.started_at), required)
+            };
+        let stage = |days: u32| Some(self.started_at + Duration::days(days as i64)).filter(|at| *at > now);
+
+        match criterion {
+            ShipmentCriterion::RootLength => {
+                growth(|o| o.root_length_mm, self.radicle_emerged_at, guide.min_root_length_mm)
+            }
+            ShipmentCriterion::ShootLength => {
+                growth(|o| o.shoot_length_mm, self.shoot_emerged_at, guide.min_shoot_length_mm)
+            }
+            ShipmentCriterion::TrueLeaves => growth(
+                |o| o.true_leaf_count.map(|count| count as f32),
+                self.true_leaf_emerged_at,
+                guide.min_true_leaf_count as f32,
+            ),
+            ShipmentCriterion::TruePlant => stage(guide.true_leaf_emergence_days_max),
+            ShipmentCriterion::Autotrophic => stage(guide.photosynthesis_days_max),
+            ShipmentCriterion::Health => None,
+        }
+    }
+
     /// Update the is_true_plant flag based on phase and true leaf count
     pub fn update_true_plant_status(&mut self) {
-        self.is_true_plant = self.germination_phase.is_true_plant()
-            && self.true_leaf_count.unwrap_or(0) > 0;
+        self.is_true_plant = self.germination_phase.is_true_plant() && self.true_leaf_count.unwrap_or(0) > 0;
     }
-    
+
     /// Update the is_autotrophic flag based on phase
     pub fn update_autotrophic_status(&mut self) {
-        self.is_autotrophic = self.germination_phase.is_autotrophic()
-            && self.is_true_plant;
+        self.is_autotrophic = self.germination_phase.is_autotrophic() && self.is_true_plant;
+    }
+
+    /// Move to a new phase, stamping the matching botanical timestamp if it isn't set yet
+    pub fn enter_phase(&mut self, phase: GerminationPhase, at: DateTime<Utc>) {
+        let stamp = match phase {
+            GerminationPhase::RadicleEmergence => Some(&mut self.radicle_emerged_at),
+            GerminationPhase::ShootEmergence => Some(&mut self.shoot_emerged_at),
+            GerminationPhase::CotyledonExpansion => Some(&mut self.cotyledon_expanded_at),
+            GerminationPhase::TrueLeafEmergence => Some(&mut self.true_leaf_emerged_at),
+            GerminationPhase::Photosynthesis => Some(&mut self.photosynthesis_started_at),
+            GerminationPhase::ReadyForShipment => Some(&mut self.ready_for_shipment_at),
+            GerminationPhase::Shipped => Some(&mut self.actual_ship_date),
+            _ => None,
+        };
+        if let Some(stamp) = stamp {
+            stamp.get_or_insert(at);
+        }
+
+        match phase {
+            GerminationPhase::Imbibition => {}
+            GerminationPhase::Failed => self.germination_success = false,
+            _ => self.germination_success = true,
+        }
+
+        self.germination_phase = phase;
+        self.update_true_plant_status();
+        self.update_autotrophic_status();
+    }
+
+    /// Copy the latest measurements from an observation onto the record
+    pub fn apply_observation(&mut self, observation: &GerminationObservation) {
+        if observation.root_length_mm.is_some() {
+            self.root_length_mm = observation.root_length_mm;
+        }
+        if observation.shoot_length_mm.is_some() {
+            self.shoot_length_mm = observation.shoot_length_mm;
+        }
+        if observation.cotyledon_count.is_some() {
+            self.cotyledon_count = observation.cotyledon_count;
+        }
+        if observation.true_leaf_count.is_some() {
+            self.true_leaf_count = observation.true_leaf_count;
+        }
+        if observation.total_leaf_count.is_some() {
+            self.total_leaf_count = observation.total_leaf_count;
+        }
+        if observation.temperature_celsius.is_some() {
+            self.temperature_celsius = observation.temperature_celsius;
+        }
+        if observation.humidity_percent.is_some() {
+            self.humidity_percent = observation.humidity_percent;
+        }
+        self.health_status = observation.health_status.clone();
+        self.update_true_plant_status();
+        self.update_autotrophic_status();
+    }
+
+    /// Apply an observation, then step through every phase it shows the
+    /// sprout has reached, stamping each stage at the observation time. A dead
+    /// sprout fails the record. Returns the phases entered, in order
+    pub fn progress_from_observation(&mut self, observation: &GerminationObservation) -> Vec<GerminationPhase> {
+        self.apply_observation(observation);
+
+        let steps = if matches!(observation.health_status, SproutHealthStatus::Dead) {
+            if self.germination_phase.can_transition_to(&GerminationPhase::Failed) {
+                vec![GerminationPhase::Failed]
+            } else {
+                vec![]
+            }
+        } else {
+            observation.observed_phase().and_then(|target| self.germination_phase.steps_to(&target)).unwrap_or_default()
+        };
+
+        for phase in &steps {
+            self.enter_phase(phase.clone(), observation.observed_at);
+        }
+        steps
+    }
+
+    /// Raise, escalate or resolve an alert per condition from the record's
+    /// observations, oldest first. Observations without a reading of a
+    /// condition neither extend nor break its run. Returns the alerts raised
+    /// or escalated by the latest observation
+    pub fn track_environment(&mut self, history: &[GerminationObservation]) -> Vec<EnvironmentalAlert> {
+        let mut changed = Vec::new();
+        for condition in [EnvironmentalCondition::Temperature, EnvironmentalCondition::Humidity] {
+            let Some(latest) = history.last().filter(|obs| condition.reading(obs).is_some()) else {
+                continue;
+            };
+            let run = history
+                .iter()
+                .rev()
+                .filter(|obs| condition.reading(obs).is_some())
+                .take_while(|obs| obs.environmental_deviations.iter().any(|d| d.condition == condition))
+                .count() as u32;
+            let open = self
+                .environmental_alerts
+                .iter_mut()
+                .find(|alert| alert.condition == condition && alert.resolved_at.is_none());
+
+            if run == 0 {
+                if let Some(alert) = open {
+                    alert.resolved_at = Some(latest.observed_at);
+                }
+                continue;
+            }
+            if run < ALERT_AFTER_OBSERVATIONS {
+                continue;
+            }
+
+            let severity = if run >= CRITICAL_AFTER_OBSERVATIONS { Severity::Critical } else { Severity::Warning };
+            let reading = condition.reading(latest).unwrap_or_default();
+            let alert = match open {
+                Some(alert) => {
+                    let escalated =
+                        matches!(severity, Severity::Critical) && !matches!(alert.severity, Severity::Critical);
+                    alert.severity = severity;
+                    alert.consecutive_observations = run;
+                    alert.latest_reading = reading;
+                    alert.updated_at = latest.observed_at;
+                    if !escalated {
+                        continue;
+                    }
+                    alert.clone()
+                }
+                None => {
+                    let alert = EnvironmentalAlert {
+                        condition,
+                        severity,
+                        consecutive_observations: run,
+                        latest_reading: reading,
+                        raised_at: latest.observed_at,
+                        updated_at: latest.observed_at,
+                        resolved_at: None,
+                    };
+                    self.environmental_alerts.push(alert.clone());
+                    alert
+                }
+            };
+            changed.push(alert);
+        }
+        changed
     }
 }
 
+impl GerminationObservation {
+    /// The furthest stage of development the observation shows, if any
+    pub fn observed_phase(&self) -> Option<GerminationPhase> {
+        if self.appears_autotrophic {
+            Some(GerminationPhase::Photosynthesis)
+        } else if self.true_leaves_present || self.true_leaf_count.unwrap_or(0) > 0 {
+            Some(GerminationPhase::TrueLeafEmergence)
+        } else if self.cotyledons_expanded {
+            Some(GerminationPhase::CotyledonExpansion)
+        } else if self.shoot_visible {
+            Some(GerminationPhase::ShootEmergence)
+        } else if self.radicle_visible {
+            Some(GerminationPhase::RadicleEmergence)
+        } else {
+            None
+        }
+    }
+}
+
+/// When a measurement reaches `required`, extrapolating from its first and
+/// latest readings; with a single reading, growth is taken from zero at `since`.
+/// None when it isn't growing, or so slowly that no date can hold the answer
+fn project_growth(points: &[(DateTime<Utc>, f32)], since: DateTime<Utc>, required: f32) -> Option<DateTime<Utc>> {
+    let &(latest_at, latest) = points.last()?;
+    let (first_at, first) = if points.len() > 1 { points[0] } else { (since, 0.0) };
+
+    let elapsed = (latest_at - first_at).num_microseconds()? as f64;
+    let rate = (latest - first) as f64 / elapsed;
+    if elapsed <= 0.0 || rate <= 0.0 {
+        return None;
+    }
+    latest_at.checked_add_signed(Duration::microseconds(((required - latest) as f64 / rate) as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_growth_extrapolates_and_gives_up_on_a_crawl() {
+        let start = Utc::now();
+        let points = [(start + Duration::days(1), 2.0), (start + Duration::days(3), 6.0)];
+        assert_eq!(project_growth(&points, start, 10.0), Some(start + Duration::days(5)));
+
+        let crawl = [(start, 1.0), (start + Duration::days(30), 1.000_001)];
+        assert_eq!(project_growth(&crawl, start, 1e30), None);
+        assert_eq!(project_growth(&points[..1], start + Duration::days(1), 10.0), None);
+    }
+}
@@ -1,9 +1,10 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Greenhouse management system with spatial quarantine
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Greenhouse {
     pub id: Uuid,
     pub name: String,
@@ -13,7 +14,7 @@ pub struct Greenhouse {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GreenhouseZone {
     pub id: Uuid,
     pub zone_number: u32,
@@ -26,7 +27,7 @@ pub struct GreenhouseZone {
     pub contamination_risk_level: RiskLevel,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ZoneType {
     /// Standard growing zone
@@ -41,7 +42,7 @@ pub enum ZoneType {
     HarvestStaging,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SpatialCoordinates {
     /// Grid position X
     pub x: u32,
@@ -53,7 +54,7 @@ pub struct SpatialCoordinates {
     pub isolation_distance_meters: Option<f32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum QuarantineStatus {
     /// No quarantine, zone is operational
@@ -68,7 +69,7 @@ pub enum QuarantineStatus {
     Cleared,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EnvironmentalConditions {
     pub temperature_celsius: Option<f32>,
     pub humidity_percent: Option<f32>,
@@ -77,7 +78,7 @@ pub struct EnvironmentalConditions {
     pub last_measured: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum RiskLevel {
     Low,
@@ -87,7 +88,7 @@ pub enum RiskLevel {
 }
 
 /// Plant tracking in greenhouse
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Plant {
     pub id: Uuid,
     pub seed_id: Option<Uuid>,
@@ -102,7 +103,7 @@ pub struct Plant {
     pub contamination_history: Vec<ContaminationEvent>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum GrowthStage {
     Germination,
@@ -114,7 +115,7 @@ pub enum GrowthStage {
     Curing,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PlantHealthStatus {
     Healthy,
@@ -126,7 +127,7 @@ pub enum PlantHealthStatus {
     Dead,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ContaminationEvent {
     pub detected_at: DateTime<Utc>,
     pub contamination_type: String,
@@ -134,4 +135,3 @@ pub struct ContaminationEvent {
     pub action_taken: String,
     pub resolved_at: Option<DateTime<Utc>>,
 }
-
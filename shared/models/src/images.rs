@@ -1,45 +1,50 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Plant/Seed image with moderation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PlantImage {
     pub id: Uuid,
     pub uploaded_by: Uuid, // User ID
     pub item_id: Uuid,     // Seed or Plant ID
     pub item_type: ImageItemType,
-    
+
     /// S3 details
     pub s3_bucket: String,
     pub s3_key: String,
     pub s3_url: String,
-    
+
     /// Metadata
     pub filename: String,
     pub content_type: String,
     pub size_bytes: u64,
     pub width: Option<u32>,
     pub height: Option<u32>,
-    
+
     /// Description and tags
     pub caption: Option<String>,
     pub growth_stage: Option<String>,
     pub tags: Vec<String>,
-    
+
     /// Moderation
     pub moderation_status: ModerationStatus,
     pub moderation_notes: Option<String>,
     pub moderated_by: Option<Uuid>,
     pub moderated_at: Option<DateTime<Utc>>,
-    
+
     /// Timestamps
     pub uploaded_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
     pub deleted_by: Option<Uuid>,
+
+    /// Optimistic concurrency counter, bumped on every successful update
+    #[serde(default)]
+    pub version: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ImageItemType {
     Seed,
@@ -48,7 +53,7 @@ pub enum ImageItemType {
     General,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ModerationStatus {
     /// Awaiting moderation
@@ -64,7 +69,7 @@ pub enum ModerationStatus {
 }
 
 /// Audit log entry for image actions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ImageAuditLog {
     pub id: Uuid,
     pub image_id: Uuid,
@@ -76,7 +81,7 @@ pub struct ImageAuditLog {
     pub user_agent: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ImageAction {
     Uploaded,
@@ -89,7 +94,7 @@ pub enum ImageAction {
 }
 
 /// S3 presigned URL for upload
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PresignedUpload {
     pub upload_id: Uuid,
     pub presigned_url: String,
@@ -100,7 +105,7 @@ pub struct PresignedUpload {
 }
 
 /// Image upload metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ImageUploadMetadata {
     pub item_id: Uuid,
     pub item_type: ImageItemType,
@@ -110,4 +115,3 @@ pub struct ImageUploadMetadata {
     pub growth_stage: Option<String>,
     pub tags: Vec<String>,
 }
-
@@ -1,9 +1,10 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Bag inventory tracking system
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Bag {
     pub id: Uuid,
     pub original_owner_id: Option<Uuid>, // None if anonymous donation
@@ -15,9 +16,12 @@ pub struct Bag {
     pub last_updated: DateTime<Utc>,
     pub contains_seeds: bool,
     pub seed_ids: Vec<Uuid>,
+    /// Optimistic concurrency counter, bumped on every successful update
+    #[serde(default)]
+    pub version: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum BagStatus {
     Received,
@@ -29,7 +33,7 @@ pub enum BagStatus {
     Recycled,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum BagType {
     Plastic,
@@ -38,7 +42,7 @@ pub enum BagType {
     Other(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum BagCondition {
     Excellent,
@@ -49,7 +53,7 @@ pub enum BagCondition {
 }
 
 /// Seed collection and tracking
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Seed {
     pub id: Uuid,
     pub plant_species: String,
@@ -59,17 +63,17 @@ pub struct Seed {
     pub status: SeedStatus,
     pub germination_tested: bool,
     pub germination_rate: Option<f32>,
-    
+
     /// Edible parts indicators
     pub is_edible_fruit_bearing: Option<bool>,
     pub has_edible_leaves: Option<bool>,
     pub has_edible_stalks: Option<bool>,
-    
+
     pub contamination_check: Option<ContaminationCheck>,
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum SeedStatus {
     Collected,
@@ -81,7 +85,7 @@ pub enum SeedStatus {
     Distributed,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ContaminationCheck {
     pub checked_at: DateTime<Utc>,
     pub is_contaminated: bool,
@@ -90,7 +94,7 @@ pub struct ContaminationCheck {
 }
 
 /// Inventory summary and reporting
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InventorySummary {
     pub total_bags: u32,
     pub bags_by_status: Vec<StatusCount>,
@@ -101,9 +105,8 @@ pub struct InventorySummary {
     pub random_sampling_pool_size: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StatusCount {
     pub status: String,
     pub count: u32,
 }
-
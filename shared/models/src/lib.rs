@@ -1,22 +1,29 @@
-pub mod subscription;
-pub mod shipping;
-pub mod inventory;
-pub mod greenhouse;
-pub mod plant;
 pub mod contamination;
 pub mod germination;
-pub mod manufacturing;
+pub mod greenhouse;
 pub mod images;
+pub mod inventory;
+pub mod manufacturing;
+pub mod plant;
 pub mod recalls;
+pub mod shipping;
+pub mod species;
+pub mod subscription;
 
-pub use subscription::*;
-pub use shipping::*;
-pub use inventory::*;
-pub use greenhouse::*;
-pub use plant::*;
 pub use contamination::*;
 pub use germination::*;
-pub use manufacturing::*;
+pub use greenhouse::*;
 pub use images::*;
+pub use inventory::*;
+pub use manufacturing::*;
+pub use plant::*;
 pub use recalls::*;
+pub use shipping::*;
+pub use species::*;
+pub use subscription::*;
 
+// Names defined by more than one module. The glob re-exports above would be
+// ambiguous, so pick one here; reach the other through its module path
+// (e.g. `models::recalls::RiskLevel`, `models::plant::LightExposure`).
+pub use greenhouse::RiskLevel;
+pub use manufacturing::LightExposure;
@@ -1,9 +1,13 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::germination::{GerminationPhase, HumidityRange, TemperatureRange};
+use crate::species::Species;
+
 /// Manufacturing queue for coordinating seed → greenhouse → shipment workflow
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ManufacturingQueue {
     pub id: Uuid,
     pub queue_type: QueueType,
@@ -13,11 +17,92 @@ pub struct ManufacturingQueue {
     pub scheduled_start: Option<DateTime<Utc>>,
     pub actual_start: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// The [`QueueWorker`] who claimed or started it. Left off the stored item
+    /// when unset, so the assignee index stays sparse
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub assigned_to: Option<String>,
     pub notes: Option<String>,
+    /// Set by the scheduler while the item is waiting or in progress
+    #[serde(default)]
+    pub estimated_completion: Option<DateTime<Utc>>,
+    /// Optimistic concurrency counter, bumped on every successful update
+    #[serde(default)]
+    pub version: u64,
+    /// Every status change, oldest first
+    #[serde(default)]
+    pub history: Vec<QueueTransition>,
+    /// The item whose completion queued this one
+    #[serde(default)]
+    pub previous_step: Option<Uuid>,
+    /// Items that must be completed before this one can start
+    #[serde(default)]
+    pub depends_on: Vec<Uuid>,
+    /// Items in the same batch are picked up and started together. Left off
+    /// the stored item when unset, so the batch index stays sparse
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_id: Option<Uuid>,
+    /// Certifications a worker needs to claim it
+    #[serde(default)]
+    pub required_skills: Vec<Skill>,
+    /// A claimed item goes back to the queue at this time unless its worker
+    /// sends a heartbeat first
+    #[serde(default)]
+    pub lease_expires_at: Option<DateTime<Utc>>,
+    /// Claimed on a lease, which resuming after a pause renews. Work started
+    /// by hand has none to renew
+    #[serde(default)]
+    pub leased: bool,
+}
+
+/// One status change of a queue item
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QueueTransition {
+    pub from: QueueStatus,
+    pub to: QueueStatus,
+    pub at: DateTime<Utc>,
+    /// The staff member who made it, when known
+    pub by: Option<Uuid>,
+    pub reason: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+impl ManufacturingQueue {
+    /// Move to `to` if the transition table allows it, stamping `actual_start`
+    /// on first start and `completed_at` when the item stops for good or
+    /// fails, and recording the change. A retry or release goes back to
+    /// `Pending` with both stamps, the assignee and any claim cleared, and only work in
+    /// progress keeps its lease. Returns false, changing nothing, for a disallowed move
+    pub fn transition(&mut self, to: QueueStatus, at: DateTime<Utc>, by: Option<Uuid>, reason: Option<String>) -> bool {
+        if !self.status.can_transition_to(&to) {
+            return false;
+        }
+
+        match to {
+            QueueStatus::InProgress => {
+                self.actual_start.get_or_insert(at);
+            }
+            QueueStatus::Completed | QueueStatus::Cancelled | QueueStatus::Failed => self.completed_at = Some(at),
+            QueueStatus::Pending => {
+                self.actual_start = None;
+                self.completed_at = None;
+                self.assigned_to = None;
+                self.leased = false;
+            }
+            QueueStatus::Scheduled | QueueStatus::Paused => {}
+        }
+        if !to.is_open() {
+            self.estimated_completion = None;
+        }
+        if to != QueueStatus::InProgress {
+            self.lease_expires_at = None;
+        }
+
+        self.history.push(QueueTransition { from: self.status.clone(), to: to.clone(), at, by, reason });
+        self.status = to;
+        true
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum QueueType {
     /// Seeds received, need to be processed and stored
@@ -32,9 +117,48 @@ pub enum QueueType {
     BagCleaning { bag_ids: Vec<Uuid> },
     /// Bags cleaned, ready for return shipment
     BagShipmentPrep { bag_ids: Vec<Uuid>, customer_id: Uuid },
+    /// A sprout overran its guide's window for a stage; staff decide whether it has failed
+    GerminationReview { germination_record_id: Uuid, stalled_phase: GerminationPhase },
+}
+
+impl QueueType {
+    /// The variant's name as serialized, e.g. `BAG_CLEANING`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SeedIntake { .. } => "SEED_INTAKE",
+            Self::GerminationScheduled { .. } => "GERMINATION_SCHEDULED",
+            Self::GreenhouseTransfer { .. } => "GREENHOUSE_TRANSFER",
+            Self::ShipmentPrep { .. } => "SHIPMENT_PREP",
+            Self::BagCleaning { .. } => "BAG_CLEANING",
+            Self::BagShipmentPrep { .. } => "BAG_SHIPMENT_PREP",
+            Self::GerminationReview { .. } => "GERMINATION_REVIEW",
+        }
+    }
+
+    /// Items with the same key can be batched: work of one kind for the
+    /// same customer, or transfers between the same two zones
+    pub fn batch_key(&self) -> Option<String> {
+        match self {
+            Self::GreenhouseTransfer { from_zone_id, to_zone_id, .. } => {
+                Some(format!("{}/{}/{}", self.name(), from_zone_id, to_zone_id))
+            }
+            _ => self.customer_id().map(|customer_id| format!("{}/{}", self.name(), customer_id)),
+        }
+    }
+
+    /// The customer the work is for, when it is for one
+    pub fn customer_id(&self) -> Option<Uuid> {
+        match self {
+            Self::GerminationScheduled { customer_id, .. }
+            | Self::ShipmentPrep { customer_id, .. }
+            | Self::BagShipmentPrep { customer_id, .. } => Some(*customer_id),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Ordered lowest first
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
 pub enum Priority {
     Low,
@@ -43,7 +167,7 @@ pub enum Priority {
     Urgent,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum QueueStatus {
     Pending,
@@ -55,8 +179,82 @@ pub enum QueueStatus {
     Failed,
 }
 
+impl QueueStatus {
+    /// The transition table: work is scheduled or started from `Pending`,
+    /// paused and resumed while in progress, and ends `Completed` or `Failed`.
+    /// Anything not yet finished can be cancelled, a failure can be retried
+    /// from the start, and claimed work that is released or abandoned goes
+    /// back to waiting
+    pub fn can_transition_to(&self, to: &Self) -> bool {
+        use QueueStatus::*;
+        matches!(
+            (self, to),
+            (Pending, Scheduled | InProgress)
+                | (Scheduled, InProgress)
+                | (InProgress, Pending | Paused | Completed | Failed)
+                | (Paused, InProgress | Failed)
+                | (Pending | Scheduled | InProgress | Paused, Cancelled)
+                | (Failed, Pending)
+        )
+    }
+
+    /// Waiting or being worked on
+    pub fn is_open(&self) -> bool {
+        matches!(self, Self::Pending | Self::Scheduled | Self::InProgress | Self::Paused)
+    }
+}
+
+/// A certification some queue work needs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Skill {
+    /// Regulated species such as cannabis, which need a licence to handle
+    Cannabis,
+    /// Seeds, bags or plants held in quarantine
+    Quarantine,
+}
+
+impl Skill {
+    /// What handling the species needs certification for; unknown species don't
+    pub fn for_species(name: &str) -> Option<Self> {
+        Species::resolve(name).filter(|species| species.regulated).map(|_| Self::Cannabis)
+    }
+}
+
+/// A staff member or station that claims queue items
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QueueWorker {
+    /// A staff member's user id, or a station's name
+    pub id: String,
+    pub kind: WorkerKind,
+    pub name: Option<String>,
+    pub skills: Vec<Skill>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkerKind {
+    Staff,
+    /// Shared equipment any staff member can claim work for
+    Station,
+}
+
+/// How long a queue type's most recent completions were in progress, which
+/// the scheduler estimates the type's duration from
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QueueTypeDurations {
+    /// [`QueueType::name`]
+    pub queue_type: String,
+    /// Seconds worked on each, newest first
+    pub recent_seconds: Vec<i64>,
+    /// Optimistic concurrency counter, bumped on every successful update
+    #[serde(default)]
+    pub version: u64,
+}
+
 /// Seed storage requirements and tracking
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SeedStorage {
     pub id: Uuid,
     pub seed_id: Uuid,
@@ -70,7 +268,7 @@ pub struct SeedStorage {
     pub estimated_viability_percent: Option<f32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StorageLocation {
     pub facility: String,
     pub room: String,
@@ -79,7 +277,7 @@ pub struct StorageLocation {
     pub bin: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum StorageUnit {
     /// Cold storage refrigerator (2-8°C)
@@ -92,7 +290,7 @@ pub enum StorageUnit {
     ControlledVault { vault_number: u32 },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StorageRequirements {
     /// Optimal storage temperature in Celsius
     pub temperature_celsius: TemperatureRange,
@@ -112,21 +310,7 @@ pub struct StorageRequirements {
     pub quarantine_days: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TemperatureRange {
-    pub min: f32,
-    pub max: f32,
-    pub optimal: f32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct HumidityRange {
-    pub min: f32,
-    pub max: f32,
-    pub optimal: f32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum LightExposure {
     Dark,
@@ -134,7 +318,7 @@ pub enum LightExposure {
     Indirect,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum StorageCondition {
     Excellent,
@@ -145,7 +329,7 @@ pub enum StorageCondition {
 }
 
 /// Species-specific seed storage parameters
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SeedStorageGuide {
     pub species: String,
     pub variety: Option<String>,
@@ -154,23 +338,16 @@ pub struct SeedStorageGuide {
 }
 
 impl SeedStorageGuide {
-    /// Get recommended storage requirements for common species
-    pub fn for_species(species: &str) -> Self {
-        match species.to_lowercase().as_str() {
-            "tomato" | "tomatoes" => Self {
-                species: species.to_string(),
+    /// Recommended storage requirements for a registered species; species
+    /// without specific guidance get the standard protocol
+    pub fn for_species(species: &Species) -> Self {
+        match species.id {
+            "tomato" => Self {
+                species: species.id.to_string(),
                 variety: None,
                 storage_requirements: StorageRequirements {
-                    temperature_celsius: TemperatureRange {
-                        min: 2.0,
-                        max: 8.0,
-                        optimal: 5.0,
-                    },
-                    humidity_percent: HumidityRange {
-                        min: 20.0,
-                        max: 40.0,
-                        optimal: 30.0,
-                    },
+                    temperature_celsius: TemperatureRange { min: 2.0, max: 8.0, optimal: 5.0 },
+                    humidity_percent: HumidityRange { min: 20.0, max: 40.0, optimal: 30.0 },
                     light: LightExposure::Dark,
                     refrigeration_required: true,
                     freezing_required: false,
@@ -183,25 +360,17 @@ impl SeedStorageGuide {
                     "Label with variety and collection date".to_string(),
                 ],
             },
-            "pepper" | "peppers" => Self {
-                species: species.to_string(),
+            "pepper" => Self {
+                species: species.id.to_string(),
                 variety: None,
                 storage_requirements: StorageRequirements {
-                    temperature_celsius: TemperatureRange {
-                        min: 2.0,
-                        max: 8.0,
-                        optimal: 5.0,
-                    },
-                    humidity_percent: HumidityRange {
-                        min: 20.0,
-                        max: 40.0,
-                        optimal: 30.0,
-                    },
+                    temperature_celsius: TemperatureRange { min: 2.0, max: 8.0, optimal: 5.0 },
+                    humidity_percent: HumidityRange { min: 20.0, max: 40.0, optimal: 30.0 },
                     light: LightExposure::Dark,
                     refrigeration_required: true,
                     freezing_required: false,
                     max_storage_days: Some(730), // 2 years
-                    separation_required: true, // Different pepper varieties can cross
+                    separation_required: true,   // Different pepper varieties can cross
                     quarantine_days: Some(14),
                 },
                 special_instructions: vec![
@@ -209,20 +378,12 @@ impl SeedStorageGuide {
                     "Dry completely before storage".to_string(),
                 ],
             },
-            "lettuce" | "salad" => Self {
-                species: species.to_string(),
+            "lettuce" => Self {
+                species: species.id.to_string(),
                 variety: None,
                 storage_requirements: StorageRequirements {
-                    temperature_celsius: TemperatureRange {
-                        min: 2.0,
-                        max: 8.0,
-                        optimal: 5.0,
-                    },
-                    humidity_percent: HumidityRange {
-                        min: 20.0,
-                        max: 40.0,
-                        optimal: 30.0,
-                    },
+                    temperature_celsius: TemperatureRange { min: 2.0, max: 8.0, optimal: 5.0 },
+                    humidity_percent: HumidityRange { min: 20.0, max: 40.0, optimal: 30.0 },
                     light: LightExposure::Dark,
                     refrigeration_required: true,
                     freezing_required: false,
@@ -230,24 +391,14 @@ impl SeedStorageGuide {
                     separation_required: false,
                     quarantine_days: Some(7),
                 },
-                special_instructions: vec![
-                    "Quick germinator, use fresh seeds when possible".to_string(),
-                ],
+                special_instructions: vec!["Quick germinator, use fresh seeds when possible".to_string()],
             },
             "basil" => Self {
-                species: species.to_string(),
+                species: species.id.to_string(),
                 variety: None,
                 storage_requirements: StorageRequirements {
-                    temperature_celsius: TemperatureRange {
-                        min: 2.0,
-                        max: 8.0,
-                        optimal: 5.0,
-                    },
-                    humidity_percent: HumidityRange {
-                        min: 20.0,
-                        max: 40.0,
-                        optimal: 30.0,
-                    },
+                    temperature_celsius: TemperatureRange { min: 2.0, max: 8.0, optimal: 5.0 },
+                    humidity_percent: HumidityRange { min: 20.0, max: 40.0, optimal: 30.0 },
                     light: LightExposure::Dark,
                     refrigeration_required: true,
                     freezing_required: false,
@@ -255,30 +406,20 @@ impl SeedStorageGuide {
                     separation_required: false,
                     quarantine_days: Some(7),
                 },
-                special_instructions: vec![
-                    "Very viable when stored properly".to_string(),
-                ],
+                special_instructions: vec!["Very viable when stored properly".to_string()],
             },
-            "cannabis" | "marijuana" => Self {
-                species: species.to_string(),
+            "cannabis" => Self {
+                species: species.id.to_string(),
                 variety: None,
                 storage_requirements: StorageRequirements {
-                    temperature_celsius: TemperatureRange {
-                        min: 2.0,
-                        max: 8.0,
-                        optimal: 5.0,
-                    },
-                    humidity_percent: HumidityRange {
-                        min: 20.0,
-                        max: 30.0,
-                        optimal: 25.0,
-                    },
+                    temperature_celsius: TemperatureRange { min: 2.0, max: 8.0, optimal: 5.0 },
+                    humidity_percent: HumidityRange { min: 20.0, max: 30.0, optimal: 25.0 },
                     light: LightExposure::Dark,
                     refrigeration_required: true,
                     freezing_required: false,
                     max_storage_days: Some(730), // 2 years optimal
-                    separation_required: true, // MUST separate phenotypes
-                    quarantine_days: Some(30), // Extended quarantine
+                    separation_required: true,   // MUST separate phenotypes
+                    quarantine_days: Some(30),   // Extended quarantine
                 },
                 special_instructions: vec![
                     "⚠️ REQUIRES SEPARATE LICENSED FACILITY".to_string(),
@@ -288,19 +429,11 @@ impl SeedStorageGuide {
                 ],
             },
             _ => Self {
-                species: species.to_string(),
+                species: species.id.to_string(),
                 variety: None,
                 storage_requirements: StorageRequirements {
-                    temperature_celsius: TemperatureRange {
-                        min: 2.0,
-                        max: 8.0,
-                        optimal: 5.0,
-                    },
-                    humidity_percent: HumidityRange {
-                        min: 20.0,
-                        max: 40.0,
-                        optimal: 30.0,
-                    },
+                    temperature_celsius: TemperatureRange { min: 2.0, max: 8.0, optimal: 5.0 },
+                    humidity_percent: HumidityRange { min: 20.0, max: 40.0, optimal: 30.0 },
                     light: LightExposure::Dark,
                     refrigeration_required: true,
                     freezing_required: false,
@@ -308,16 +441,14 @@ impl SeedStorageGuide {
                     separation_required: false,
                     quarantine_days: Some(14),
                 },
-                special_instructions: vec![
-                    "Standard seed storage protocol".to_string(),
-                ],
+                special_instructions: vec!["Standard seed storage protocol".to_string()],
             },
         }
     }
 }
 
 /// Greenhouse workflow queue
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GreenhouseWorkflow {
     pub id: Uuid,
     pub workflow_type: GreenhouseWorkflowType,
@@ -330,7 +461,7 @@ pub struct GreenhouseWorkflow {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum GreenhouseWorkflowType {
     /// Move to germination zone
@@ -348,7 +479,7 @@ pub enum GreenhouseWorkflowType {
 }
 
 /// Inventory audit and quality check
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InventoryAudit {
     pub id: Uuid,
     pub audit_type: AuditType,
@@ -359,7 +490,7 @@ pub struct InventoryAudit {
     pub findings: Vec<AuditFinding>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AuditType {
     SeedStorageCheck,
@@ -369,7 +500,7 @@ pub enum AuditType {
     ShipmentQuality,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AuditFinding {
     pub item_id: Uuid,
     pub item_type: String,
@@ -378,11 +509,10 @@ pub struct AuditFinding {
     pub action_taken: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Severity {
     Info,
     Warning,
     Critical,
 }
-
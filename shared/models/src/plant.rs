@@ -1,9 +1,12 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::germination::{HumidityRange, TemperatureRange};
+
 /// Plant processing information for consumption or curing
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PlantProcessingGuide {
     pub id: Uuid,
     pub species: String,
@@ -17,7 +20,7 @@ pub struct PlantProcessingGuide {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EdiblePart {
     pub part_name: String, // "fruit", "leaves", "seeds", "roots", etc.
     pub edible: bool,
@@ -25,7 +28,7 @@ pub struct EdiblePart {
     pub toxicity_warning: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProcessingMethod {
     pub method_type: ProcessingType,
     pub name: String,
@@ -36,7 +39,7 @@ pub struct ProcessingMethod {
     pub steps: Vec<ProcessingStep>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ProcessingType {
     /// Immediate consumption
@@ -53,7 +56,7 @@ pub enum ProcessingType {
     Extraction,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProcessingStep {
     pub step_number: u32,
     pub instruction: String,
@@ -61,7 +64,7 @@ pub struct ProcessingStep {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NutritionalInfo {
     pub serving_size_grams: u32,
     pub calories: Option<u32>,
@@ -73,7 +76,7 @@ pub struct NutritionalInfo {
 }
 
 /// Curing protocol specifically for crops that require curing (like cannabis)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CuringProtocol {
     pub id: Uuid,
     pub plant_type: String,
@@ -82,7 +85,7 @@ pub struct CuringProtocol {
     pub total_duration_days: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CuringPhase {
     pub phase_number: u32,
     pub name: String,
@@ -94,21 +97,7 @@ pub struct CuringPhase {
     pub instructions: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TemperatureRange {
-    pub min: f32,
-    pub max: f32,
-    pub optimal: f32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct HumidityRange {
-    pub min: f32,
-    pub max: f32,
-    pub optimal: f32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum LightExposure {
     None,
@@ -118,7 +107,7 @@ pub enum LightExposure {
 }
 
 /// Recipe suggestions for edible crops
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Recipe {
     pub id: Uuid,
     pub name: String,
@@ -132,24 +121,24 @@ pub struct Recipe {
     pub instructions: Vec<RecipeStep>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum DifficultyLevel {
     Easy,
+    #[default]
     Medium,
     Hard,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Ingredient {
     pub item: String,
     pub amount: String,
     pub preparation: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RecipeStep {
     pub step_number: u32,
     pub instruction: String,
 }
-
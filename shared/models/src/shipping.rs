@@ -1,11 +1,12 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::subscription::Address;
 
 /// Multi-point shipping configuration for bag collection and return
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ShipmentCycle {
     pub id: Uuid,
     pub customer_id: Uuid,
@@ -14,9 +15,12 @@ pub struct ShipmentCycle {
     pub status: ShipmentStatus,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Optimistic concurrency counter, bumped on every successful update
+    #[serde(default)]
+    pub version: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ShipmentLeg {
     pub leg_number: u32,
     pub leg_type: ShipmentLegType,
@@ -29,7 +33,7 @@ pub struct ShipmentLeg {
     pub delivered_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ShipmentLegType {
     /// Customer sends bags to facility (Leg 1)
@@ -38,7 +42,7 @@ pub enum ShipmentLegType {
     FacilityToCustomer,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ShipmentStatus {
     Pending,
@@ -48,7 +52,7 @@ pub enum ShipmentStatus {
     Failed,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum LegStatus {
     Pending,
@@ -60,12 +64,12 @@ pub enum LegStatus {
 }
 
 /// Bag folding instructions for packaging
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BagPackagingInstructions {
     pub steps: Vec<PackagingStep>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PackagingStep {
     pub step_number: u32,
     pub instruction: String,
@@ -127,7 +131,7 @@ impl BagPackagingInstructions {
 }
 
 /// ShipStation API integration models
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ShipStationLabel {
     pub shipment_id: String,
     pub label_data: String, // Base64 encoded label
@@ -136,7 +140,7 @@ pub struct ShipStationLabel {
     pub service_code: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ShipStationCreateLabelRequest {
     pub order_id: String,
     pub carrier_code: String,
@@ -150,7 +154,7 @@ pub struct ShipStationCreateLabelRequest {
     pub is_return_label: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ShipStationAddress {
     pub name: String,
     pub street1: String,
@@ -161,17 +165,16 @@ pub struct ShipStationAddress {
     pub country: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Weight {
     pub value: f32,
     pub units: String, // "ounces" or "grams"
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Dimensions {
     pub length: f32,
     pub width: f32,
     pub height: f32,
     pub units: String, // "inches" or "centimeters"
 }
-
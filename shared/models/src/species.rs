@@ -0,0 +1,218 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// A species we grow, store and process, and the names customers and staff
+/// use for it
+///
+/// Services take species as free text; resolve it with [`Species::resolve`]
+/// and store the canonical `id`, so "Tomatoes" and "tomato" are one species.
+#[derive(Debug, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct Species {
+    /// Canonical identifier, lowercase snake_case (e.g. `sugar_cane`)
+    pub id: &'static str,
+    pub common_name: &'static str,
+    pub botanical_name: &'static str,
+    /// Other names it resolves from: plurals, regional names, misspellings
+    pub aliases: &'static [&'static str],
+    /// Empty when nothing is eaten
+    pub edible_parts: &'static [PlantPart],
+    /// Growing, storing or shipping it needs a licence
+    pub regulated: bool,
+}
+
+/// The part of a plant that is harvested for eating
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PlantPart {
+    /// Including berries and the pods of legumes
+    Fruit,
+    Leaves,
+    Stems,
+    Roots,
+    FlowerHeads,
+    Flowers,
+    Grain,
+    Stalks,
+}
+
+impl Species {
+    /// The registered species `name` refers to, by id or alias; case, spacing,
+    /// underscores and hyphens are ignored
+    pub fn resolve(name: &str) -> Option<&'static Species> {
+        let name = normalize(name);
+        REGISTRY.iter().find(|species| {
+            normalize(species.id) == name || species.aliases.iter().any(|alias| normalize(alias) == name)
+        })
+    }
+
+    /// Every registered species
+    pub fn all() -> &'static [Species] {
+        REGISTRY
+    }
+
+    pub fn bears_fruit(&self) -> bool {
+        self.edible_parts.contains(&PlantPart::Fruit)
+    }
+
+    pub fn has_edible_leaves(&self) -> bool {
+        self.edible_parts.contains(&PlantPart::Leaves)
+    }
+
+    /// Stalks (sugar cane) or the tender stems of herbs
+    pub fn has_edible_stalks(&self) -> bool {
+        self.edible_parts.iter().any(|part| matches!(part, PlantPart::Stalks | PlantPart::Stems))
+    }
+
+    pub fn is_edible(&self) -> bool {
+        !self.edible_parts.is_empty()
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace(['_', '-'], " ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+const fn species(
+    id: &'static str,
+    common_name: &'static str,
+    botanical_name: &'static str,
+    aliases: &'static [&'static str],
+    edible_parts: &'static [PlantPart],
+) -> Species {
+    Species { id, common_name, botanical_name, aliases, edible_parts, regulated: false }
+}
+
+static REGISTRY: &[Species] = {
+    use PlantPart::*;
+    &[
+        // Fruiting vegetables
+        species("tomato", "Tomato", "Solanum lycopersicum", &["tomatoes"], &[Fruit]),
+        species(
+            "pepper",
+            "Pepper",
+            "Capsicum annuum",
+            &["peppers", "bell pepper", "chili", "jalapeño", "jalapeno"],
+            &[Fruit],
+        ),
+        species("cucumber", "Cucumber", "Cucumis sativus", &["cucumbers"], &[Fruit]),
+        species("squash", "Squash", "Cucurbita pepo", &["zucchini"], &[Fruit]),
+        species("pumpkin", "Pumpkin", "Cucurbita maxima", &["pumpkins"], &[Fruit]),
+        species("melon", "Melon", "Cucumis melo", &["melons", "honeydew"], &[Fruit]),
+        species("cantaloupe", "Cantaloupe", "Cucumis melo var. cantalupensis", &["cantelope"], &[Fruit]),
+        species("watermelon", "Watermelon", "Citrullus lanatus", &[], &[Fruit]),
+        species("eggplant", "Eggplant", "Solanum melongena", &["aubergine"], &[Fruit]),
+        // Legumes; the pods are the fruit
+        species("bean", "Bean", "Phaseolus vulgaris", &["beans", "green bean", "snap bean"], &[Fruit]),
+        species("pea", "Pea", "Pisum sativum", &["peas", "snap pea", "snow pea"], &[Fruit]),
+        // Berries
+        species("strawberry", "Strawberry", "Fragaria × ananassa", &["strawberries"], &[Fruit]),
+        species("blueberry", "Blueberry", "Vaccinium corymbosum", &["blueberries"], &[Fruit]),
+        species("raspberry", "Raspberry", "Rubus idaeus", &["raspberries"], &[Fruit]),
+        species("blackberry", "Blackberry", "Rubus fruticosus", &["blackberries"], &[Fruit]),
+        // Tree fruits
+        species("apple", "Apple", "Malus domestica", &["apples"], &[Fruit]),
+        species("pear", "Pear", "Pyrus communis", &["pears"], &[Fruit]),
+        species("peach", "Peach", "Prunus persica", &["peaches"], &[Fruit]),
+        species("cherry", "Cherry", "Prunus avium", &["cherries"], &[Fruit]),
+        // Leafy greens
+        species("lettuce", "Lettuce", "Lactuca sativa", &["salad"], &[Leaves]),
+        species("spinach", "Spinach", "Spinacia oleracea", &[], &[Leaves]),
+        species("kale", "Kale", "Brassica oleracea var. sabellica", &[], &[Leaves]),
+        species("chard", "Swiss chard", "Beta vulgaris subsp. vulgaris", &["swiss chard"], &[Leaves]),
+        species("arugula", "Arugula", "Eruca vesicaria", &["rocket"], &[Leaves]),
+        species(
+            "collard",
+            "Collard greens",
+            "Brassica oleracea var. viridis",
+            &["collards", "collard greens"],
+            &[Leaves],
+        ),
+        // Herbs
+        species("basil", "Basil", "Ocimum basilicum", &[], &[Leaves, Stems]),
+        species("cilantro", "Cilantro", "Coriandrum sativum", &["coriander"], &[Leaves, Stems]),
+        species("parsley", "Parsley", "Petroselinum crispum", &[], &[Leaves, Stems]),
+        species("oregano", "Oregano", "Origanum vulgare", &[], &[Leaves, Stems]),
+        species("thyme", "Thyme", "Thymus vulgaris", &[], &[Leaves, Stems]),
+        species("rosemary", "Rosemary", "Salvia rosmarinus", &[], &[Leaves, Stems]),
+        species("sage", "Sage", "Salvia officinalis", &[], &[Leaves, Stems]),
+        species("mint", "Mint", "Mentha spicata", &[], &[Leaves, Stems]),
+        species("dill", "Dill", "Anethum graveolens", &[], &[Leaves, Stems]),
+        species("chives", "Chives", "Allium schoenoprasum", &[], &[Leaves]),
+        // Root vegetables
+        species("carrot", "Carrot", "Daucus carota subsp. sativus", &["carrots"], &[Roots]),
+        species("radish", "Radish", "Raphanus sativus", &["radishes"], &[Roots]),
+        species("beet", "Beet", "Beta vulgaris", &["beets", "beetroot"], &[Roots]),
+        species("turnip", "Turnip", "Brassica rapa subsp. rapa", &["turnips"], &[Roots]),
+        species("potato", "Potato", "Solanum tuberosum", &["potatoes"], &[Roots]),
+        // Brassicas
+        species("broccoli", "Broccoli", "Brassica oleracea var. italica", &[], &[FlowerHeads]),
+        species("cauliflower", "Cauliflower", "Brassica oleracea var. botrytis", &[], &[FlowerHeads]),
+        species("cabbage", "Cabbage", "Brassica oleracea var. capitata", &[], &[Leaves]),
+        species("brussels_sprouts", "Brussels sprouts", "Brassica oleracea var. gemmifera", &[], &[Leaves]),
+        // Grains and grasses
+        species("wheat", "Wheat", "Triticum aestivum", &[], &[Grain]),
+        species("sugar_cane", "Sugar cane", "Saccharum officinarum", &["sugarcane", "cane sugar"], &[Stalks]),
+        // Hemp is the same species; both need a licence to handle
+        Species {
+            id: "cannabis",
+            common_name: "Cannabis",
+            botanical_name: "Cannabis sativa",
+            aliases: &["marijuana", "hemp"],
+            edible_parts: &[Flowers],
+            regulated: true,
+        },
+    ]
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_resolve_to_one_species() {
+        assert_eq!(Species::resolve("Tomatoes").unwrap().id, "tomato");
+        assert_eq!(Species::resolve(" TOMATO ").unwrap().id, "tomato");
+        assert_eq!(Species::resolve("sugar cane").unwrap().id, "sugar_cane");
+        assert_eq!(Species::resolve("Sugar-Cane").unwrap().id, "sugar_cane");
+        assert_eq!(Species::resolve("hemp").unwrap().id, "cannabis");
+        assert!(Species::resolve("dragonfruit").is_none());
+    }
+
+    #[test]
+    fn test_fruit_bearing_species() {
+        let bears_fruit = |name| Species::resolve(name).unwrap().bears_fruit();
+        assert!(bears_fruit("tomato"));
+        assert!(bears_fruit("TOMATO"));
+        assert!(bears_fruit("Tomatoes"));
+        assert!(bears_fruit("pepper"));
+        assert!(bears_fruit("cucumber"));
+
+        assert!(!bears_fruit("basil"));
+        assert!(!bears_fruit("lettuce"));
+        assert!(!bears_fruit("spinach"));
+        assert!(!bears_fruit("carrot"));
+    }
+
+    #[test]
+    fn test_edible_parts() {
+        let parts = |name| Species::resolve(name).unwrap().edible_parts;
+        assert_eq!(parts("basil"), [PlantPart::Leaves, PlantPart::Stems]);
+        assert_eq!(parts("lettuce"), [PlantPart::Leaves]);
+        assert_eq!(parts("carrot"), [PlantPart::Roots]);
+        assert_eq!(parts("broccoli"), [PlantPart::FlowerHeads]);
+        assert!(Species::resolve("marijuana").unwrap().regulated);
+    }
+
+    #[test]
+    fn test_ids_and_aliases_are_unique() {
+        let mut names: Vec<String> = Species::all()
+            .iter()
+            .flat_map(|species| std::iter::once(species.id).chain(species.aliases.iter().copied()))
+            .map(normalize)
+            .collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+    }
+}
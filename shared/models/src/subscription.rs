@@ -1,9 +1,10 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Subscription tiers for the Seed Box Bag Box service
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SubscriptionTier {
     /// $8/month - Requires customer to provide bags
@@ -32,7 +33,7 @@ impl SubscriptionTier {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Subscription {
     pub id: Uuid,
     pub customer_id: Uuid,
@@ -45,7 +46,7 @@ pub struct Subscription {
     pub bags_required: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum SubscriptionStatus {
     Active,
@@ -54,7 +55,7 @@ pub enum SubscriptionStatus {
     PendingBags, // Waiting for customer to send bags
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Customer {
     pub id: Uuid,
     pub email: String,
@@ -64,7 +65,7 @@ pub struct Customer {
     pub cratejoy_customer_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Address {
     pub street1: String,
     pub street2: Option<String>,
@@ -73,4 +74,3 @@ pub struct Address {
     pub zip: String,
     pub country: String,
 }
-
@@ -1,8 +1,8 @@
 #!/bin/bash
-# Quick test script for mock API server
+# Quick test script for the local dev server (real lambda handlers, in-memory tables)
 
-echo "🔫 Testing Mock API Server"
-echo "=========================="
+echo "🔫 Testing Dev API Server"
+echo "========================="
 echo ""
 
 # Colors
@@ -31,7 +31,7 @@ echo ""
 echo -e "${BLUE}4. Create queue item${NC}"
 curl -s -X POST http://localhost:3000/api/queue \
   -H "Content-Type: application/json" \
-  -d '{"queue_type": "SEED_INTAKE", "priority": "normal"}' | jq .
+  -d '{"queueType": {"SEED_INTAKE": {"seed_ids": []}}, "priority": "normal"}' | jq .
 echo ""
 
 # List queue
@@ -43,7 +43,7 @@ echo ""
 echo -e "${BLUE}6. Store seed in cold storage${NC}"
 curl -s -X POST http://localhost:3000/api/storage/seeds \
   -H "Content-Type: application/json" \
-  -d '{"seed_id": "550e8400-e29b-41d4-a716-446655440000", "species": "tomato", "facility": "Portland", "room": "Cold-1"}' | jq .
+  -d '{"seedId": "550e8400-e29b-41d4-a716-446655440000", "species": "tomato", "facility": "Portland", "room": "Cold-1", "unitNumber": 1}' | jq .
 echo ""
 
 # Get storage guide